@@ -0,0 +1,59 @@
+use anyhow::{anyhow, Result};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use crate::options::{Command, FileOptions, Options};
+
+use datanymizer_dumper::file::{FileFormat, FileTransformer};
+use datanymizer_engine::{Engine, Settings};
+
+pub struct App {
+    options: Options,
+}
+
+impl App {
+    pub fn from_options(options: Options) -> Result<Self> {
+        Ok(App { options })
+    }
+
+    pub fn run(&self) -> Result<()> {
+        match &self.options.command {
+            Command::File(options) => self.transform_file(options),
+        }
+    }
+
+    fn transform_file(&self, options: &FileOptions) -> Result<()> {
+        let path = Path::new(&options.input);
+        let format = options
+            .format
+            .or_else(|| FileFormat::from_extension(path))
+            .ok_or_else(|| anyhow!("Unknown format of {} (use --format)", options.input))?;
+        let table = options
+            .table
+            .clone()
+            .or_else(|| FileTransformer::table_name(path))
+            .ok_or_else(|| anyhow!("Unknown table of {} (use --table)", options.input))?;
+
+        let settings = Settings::new(self.options.config.clone())?;
+        let transformer = FileTransformer::new(Engine::new(settings), table);
+        let input = BufReader::new(File::open(path)?);
+
+        match &options.output {
+            Some(filename) => {
+                let count = transformer.transform(
+                    format,
+                    input,
+                    BufWriter::new(File::create(filename)?),
+                )?;
+                println!("{} rows transformed", count);
+            }
+            None => {
+                transformer.transform(format, input, io::stdout().lock())?;
+            }
+        }
+        Ok(())
+    }
+}
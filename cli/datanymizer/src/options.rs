@@ -0,0 +1,113 @@
+use anyhow::Result;
+use clap::{ArgAction, Args, Parser, Subcommand};
+use datanymizer_dumper::file::FileFormat;
+
+#[derive(Parser, Debug, Clone)]
+#[command(
+    name = "datanymizer",
+    about = "Powerful data anonymizer with flexible rules",
+    version
+)]
+pub struct Options {
+    #[command(subcommand)]
+    pub command: Command,
+
+    #[arg(
+        short,
+        long,
+        help = "Path to config file",
+        default_value = "./config.yml",
+        global = true
+    )]
+    pub config: String,
+
+    #[arg(
+        action = ArgAction::Count,
+        short = 'v',
+        global = true,
+        help = "Turn on verbose logging features to get more information about errors"
+    )]
+    pub verbose: u8,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Anonymize a CSV (with the header) or JSON Lines file, the file is a table of the config
+    File(FileOptions),
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct FileOptions {
+    #[arg(name = "INPUT", help = "Path to the data file")]
+    pub input: String,
+
+    #[arg(short, long, help = "Path to the anonymized file [default: stdout]")]
+    pub output: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "Table name in the config [default: the file name without the extension]"
+    )]
+    pub table: Option<String>,
+
+    #[arg(
+        long,
+        value_parser = parse_format,
+        help = "File format: csv or jsonl [default: guessed from the file extension (.csv, .jsonl, .ndjson)]"
+    )]
+    pub format: Option<FileFormat>,
+}
+
+fn parse_format(s: &str) -> Result<FileFormat> {
+    s.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file() {
+        let options = Options::parse_from([
+            "datanymizer",
+            "file",
+            "-c",
+            "some_config.yml",
+            "exports/users.csv",
+        ]);
+
+        assert_eq!(options.config, "some_config.yml");
+        assert_eq!(options.verbose, 0);
+        let Command::File(file) = options.command;
+        assert_eq!(file.input, "exports/users.csv");
+        assert_eq!(file.output, None);
+        assert_eq!(file.table, None);
+        assert_eq!(file.format, None);
+    }
+
+    #[test]
+    fn file_options() {
+        let options = Options::parse_from([
+            "datanymizer",
+            "file",
+            "--format",
+            "jsonl",
+            "-t",
+            "users",
+            "-o",
+            "fake_users.txt",
+            "users.txt",
+        ]);
+
+        let Command::File(file) = options.command;
+        assert_eq!(file.output, Some(String::from("fake_users.txt")));
+        assert_eq!(file.table, Some(String::from("users")));
+        assert_eq!(file.format, Some(FileFormat::JsonLines));
+
+        assert!(
+            Options::try_parse_from(["datanymizer", "file", "--format", "xml", "users.xml"])
+                .is_err()
+        );
+    }
+}
@@ -1,18 +1,31 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use sha2::{Digest, Sha256};
 use std::{
-    fs::File,
-    io::{self, Write},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::PathBuf,
 };
 use url::Url;
 
-use crate::options::{Options, TransactionConfig};
+use crate::options::{
+    Command, DecryptOptions, DumpFormat, Options, PreviewOptions, ScanOptions, TransactionConfig,
+    TransformDumpOptions, ValidateOptions,
+};
 
 use datanymizer_dumper::{
+    compression::Compression,
     indicator::{ConsoleIndicator, Indicator, SilentIndicator},
-    postgres::{connector::Connector, dumper::PgDumper, IsolationLevel},
-    Dumper,
+    postgres::{
+        connector::{Connection, Connector},
+        dump_transformer::DumpTransformer,
+        dumper::PgDumper,
+        scanner::PgScanner,
+        validator::PgValidator,
+        ArchiveFormat, IsolationLevel,
+    },
+    scan, Dumper,
 };
-use datanymizer_engine::{Engine, Settings};
+use datanymizer_engine::{transformers::Fpe, validation::Validation, Engine, Settings};
 
 pub struct App {
     options: Options,
@@ -30,6 +43,25 @@ pub fn from_options(options: Options) -> Result<Self> {
     }
 
     pub fn run(&self) -> Result<()> {
+        match &self.options.command {
+            Some(Command::Scan(options)) => return self.scan(options),
+            Some(Command::Audit(_)) => return self.audit(),
+            Some(Command::Validate(options)) => return self.validate(options),
+            Some(Command::Preview(options)) => return self.preview(options),
+            Some(Command::TransformDump(options)) => return self.transform_dump(options),
+            Some(Command::Decrypt(options)) => return Self::decrypt(options),
+            None => {}
+        }
+
+        // The dumper writes archives and loads the target database itself
+        if self.archive()?.is_some() || self.options.target_url.is_some() {
+            return if self.options.no_indicator {
+                self.make_dump(io::sink(), SilentIndicator)
+            } else {
+                self.make_dump(io::sink(), ConsoleIndicator::new())
+            };
+        }
+
         match (&self.options.file, &self.options.no_indicator) {
             (Some(filename), false) => {
                 self.make_dump(File::create(filename)?, ConsoleIndicator::new())
@@ -47,7 +79,7 @@ fn make_dump<W, I>(&self, w: W, i: I) -> Result<()>
         let mut connection = self.connector().connect()?;
         let engine = self.engine()?;
 
-        PgDumper::new(
+        let mut dumper = PgDumper::new(
             engine,
             self.dump_isolation_level(),
             self.options.pg_dump_location.clone(),
@@ -55,13 +87,157 @@ fn make_dump<W, I>(&self, w: W, i: I) -> Result<()>
             i,
             self.options.pg_dump_args.clone(),
         )?
-        .dump(&mut connection)
+        .with_jobs(self.options.jobs.into(), self.connector())
+        .with_workers(self.options.workers.into());
+        if let Some(compression) = self.compression()? {
+            dumper = dumper.with_compression(compression)?;
+        }
+        if let Some((format, path)) = self.archive()? {
+            dumper = dumper.with_archive(format, path);
+        }
+        if let Some(dir) = self.checkpoint_dir()? {
+            dumper = dumper.with_checkpoints(dir, self.fingerprint()?);
+        }
+        if let Some(url) = self.options.target_url()? {
+            dumper = dumper.with_target(self.connector_to(url));
+        }
+        if self.options.strict {
+            dumper = dumper.with_strict();
+        }
+
+        dumper.dump(&mut connection)
+    }
+
+    fn scan(&self, options: &ScanOptions) -> Result<()> {
+        let mut connection = self.connector().connect()?;
+        let tables = PgScanner::new(options.sample_size).scan(&mut connection)?;
+        let config = scan::draft_config(&tables);
+
+        match &options.output {
+            Some(filename) => fs::write(filename, config)?,
+            None => io::stdout().write_all(config.as_bytes())?,
+        }
+        Ok(())
+    }
+
+    fn audit(&self) -> Result<()> {
+        let mut connection = self.connector().connect()?;
+        let dumper = self.prepared_dumper(&mut connection)?;
+
+        let uncovered = dumper.audit(&mut connection)?;
+        if uncovered.is_empty() {
+            println!("All columns that look like personal data have rules");
+            return Ok(());
+        }
+
+        println!("{}", scan::report(&uncovered));
+        let count: usize = uncovered.iter().map(|t| t.columns.len()).sum();
+        bail!(
+            "{} columns look like personal data, but have no rules (add rules or list them in `audit.ignore`)",
+            count
+        )
+    }
+
+    fn preview(&self, options: &PreviewOptions) -> Result<()> {
+        let mut connection = self.connector().connect()?;
+        let dumper = self.prepared_dumper(&mut connection)?;
+
+        let preview = dumper.preview(&mut connection, &options.table, options.limit)?;
+        println!("{}", preview.render());
+        Ok(())
+    }
+
+    /// The dumper for inspecting the database (it doesn't dump anything)
+    fn prepared_dumper(
+        &self,
+        connection: &mut Connection,
+    ) -> Result<PgDumper<io::Sink, SilentIndicator>> {
+        let mut dumper = PgDumper::new(
+            self.engine()?,
+            None,
+            self.options.pg_dump_location.clone(),
+            io::sink(),
+            SilentIndicator,
+            vec![],
+        )?;
+        dumper.prepare(connection)?;
+        Ok(dumper)
+    }
+
+    fn transform_dump(&self, options: &TransformDumpOptions) -> Result<()> {
+        let transformer = DumpTransformer::new(self.engine()?);
+        let input: Box<dyn BufRead> = match &options.input {
+            Some(filename) => Box::new(BufReader::new(File::open(filename)?)),
+            None => Box::new(io::stdin().lock()),
+        };
+
+        match &options.output {
+            Some(filename) => {
+                let count =
+                    transformer.transform(input, BufWriter::new(File::create(filename)?))?;
+                println!("{} rows transformed", count);
+            }
+            None => {
+                transformer.transform(input, io::stdout().lock())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate(&self, options: &ValidateOptions) -> Result<()> {
+        let config = fs::read_to_string(&self.options.config)?;
+        let mut validation = Validation::new(&config);
+
+        match &options.schema {
+            Some(filename) => {
+                let schema = serde_json::from_str(&fs::read_to_string(filename)?)?;
+                validation.check_schema(&schema);
+            }
+            None => {
+                let mut connection = self.connector().connect()?;
+                if let Some(filename) = &options.save_schema {
+                    let schema = PgValidator.schema(&mut connection)?;
+                    fs::write(filename, serde_json::to_string_pretty(&schema)?)?;
+                }
+                PgValidator.validate(&mut connection, &mut validation)?;
+            }
+        }
+
+        if !validation.warnings.is_empty() {
+            println!("{}", validation.report_warnings(&self.options.config));
+        }
+        if validation.is_valid() {
+            println!("The config is valid");
+            return Ok(());
+        }
+
+        println!("{}", validation.report(&self.options.config));
+        bail!("{} problems found", validation.problems.len())
+    }
+
+    fn decrypt(options: &DecryptOptions) -> Result<()> {
+        let fpe = Fpe::new(&options.key, options.alphabet, &options.tweak);
+        let mut output = io::stdout().lock();
+        if options.values.is_empty() {
+            for line in io::stdin().lock().lines() {
+                writeln!(output, "{}", fpe.decrypt(&line?)?)?;
+            }
+        } else {
+            for value in &options.values {
+                writeln!(output, "{}", fpe.decrypt(value)?)?;
+            }
+        }
+        Ok(())
     }
 
     fn connector(&self) -> Connector {
+        self.connector_to(self.database_url.clone())
+    }
+
+    fn connector_to(&self, url: Url) -> Connector {
         let options = &self.options;
         Connector::new(
-            self.database_url.clone(),
+            url,
             options.accept_invalid_hostnames,
             options.accept_invalid_certs,
         )
@@ -72,6 +248,61 @@ fn engine(&self) -> Result<Engine> {
         Ok(Engine::new(settings))
     }
 
+    fn archive(&self) -> Result<Option<(ArchiveFormat, PathBuf)>> {
+        let format = match self.options.format {
+            DumpFormat::Plain => return Ok(None),
+            DumpFormat::Custom => ArchiveFormat::Custom,
+            DumpFormat::Directory => ArchiveFormat::Directory,
+        };
+
+        match &self.options.file {
+            Some(file) => Ok(Some((format, PathBuf::from(file)))),
+            None => Err(anyhow!(
+                "The {:?} format needs the output path (--file)",
+                self.options.format
+            )),
+        }
+    }
+
+    fn compression(&self) -> Result<Option<Compression>> {
+        let options = &self.options;
+        if options.format != DumpFormat::Plain {
+            return match options.compress {
+                Some(_) => Err(anyhow!("Only the plain format can be compressed")),
+                None => Ok(None),
+            };
+        }
+
+        Ok(options.compress.or_else(|| {
+            options
+                .file
+                .as_deref()
+                .and_then(Compression::from_extension)
+        }))
+    }
+
+    fn checkpoint_dir(&self) -> Result<Option<PathBuf>> {
+        match &self.options.checkpoint_dir {
+            Some(_) if self.options.format != DumpFormat::Plain => Err(anyhow!(
+                "Only the plain dump can be resumed from checkpoints"
+            )),
+            dir => Ok(dir.as_ref().map(PathBuf::from)),
+        }
+    }
+
+    /// Checkpoints can be reused only with the same config and database
+    fn fingerprint(&self) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(fs::read(&self.options.config)?);
+        hasher.update(self.database_url.as_str());
+
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    }
+
     fn dump_isolation_level(&self) -> Option<IsolationLevel> {
         match self.options.dump_transaction {
             TransactionConfig::NoTransaction => None,
@@ -87,6 +318,155 @@ fn dump_isolation_level(&self) -> Option<IsolationLevel> {
 mod tests {
     use super::*;
 
+    mod archive {
+        use super::*;
+        use clap::Parser;
+
+        fn archive(args: &[&str]) -> Result<Option<(ArchiveFormat, PathBuf)>> {
+            let options = Options::parse_from(
+                ["DBNAME", "postgres://postgres@localhost/dbname"]
+                    .iter()
+                    .chain(args),
+            );
+            App::from_options(options).unwrap().archive()
+        }
+
+        #[test]
+        fn plain() {
+            assert!(archive(&[]).unwrap().is_none());
+            assert!(archive(&["-f", "dump.sql"]).unwrap().is_none());
+        }
+
+        #[test]
+        fn custom() {
+            assert_eq!(
+                archive(&["-Fc", "-f", "dump.pgdump"]).unwrap(),
+                Some((ArchiveFormat::Custom, PathBuf::from("dump.pgdump")))
+            );
+        }
+
+        #[test]
+        fn directory() {
+            assert_eq!(
+                archive(&["--format", "directory", "-f", "dump"]).unwrap(),
+                Some((ArchiveFormat::Directory, PathBuf::from("dump")))
+            );
+        }
+
+        #[test]
+        fn no_file() {
+            assert_eq!(
+                archive(&["-Fd"]).unwrap_err().to_string(),
+                "The Directory format needs the output path (--file)"
+            );
+        }
+    }
+
+    mod compression {
+        use super::*;
+        use clap::Parser;
+
+        fn compression(args: &[&str]) -> Result<Option<Compression>> {
+            let options = Options::parse_from(
+                ["DBNAME", "postgres://postgres@localhost/dbname"]
+                    .iter()
+                    .chain(args),
+            );
+            App::from_options(options).unwrap().compression()
+        }
+
+        #[test]
+        fn none() {
+            assert_eq!(compression(&[]).unwrap(), None);
+            assert_eq!(compression(&["-f", "dump.sql"]).unwrap(), None);
+        }
+
+        #[test]
+        fn from_extension() {
+            assert_eq!(
+                compression(&["-f", "dump.sql.gz"]).unwrap(),
+                Some(Compression::Gzip(6))
+            );
+            assert_eq!(
+                compression(&["-f", "dump.sql.zst"]).unwrap(),
+                Some(Compression::Zstd(3))
+            );
+        }
+
+        #[test]
+        fn explicit() {
+            assert_eq!(
+                compression(&["--compress", "gzip:1", "-f", "dump.sql.zst"]).unwrap(),
+                Some(Compression::Gzip(1))
+            );
+        }
+
+        #[test]
+        fn archive() {
+            assert_eq!(compression(&["-Fd", "-f", "dump.gz"]).unwrap(), None);
+            assert!(compression(&["-Fc", "--compress", "gzip", "-f", "dump"]).is_err());
+        }
+    }
+
+    mod checkpoints {
+        use super::*;
+        use clap::Parser;
+
+        fn app(args: &[&str]) -> App {
+            let options = Options::parse_from(
+                ["DBNAME", "postgres://postgres@localhost/dbname"]
+                    .iter()
+                    .chain(args),
+            );
+            App::from_options(options).unwrap()
+        }
+
+        #[test]
+        fn checkpoint_dir() {
+            assert_eq!(app(&[]).checkpoint_dir().unwrap(), None);
+            assert_eq!(
+                app(&["--checkpoint-dir", "/tmp/checkpoints"])
+                    .checkpoint_dir()
+                    .unwrap(),
+                Some(PathBuf::from("/tmp/checkpoints"))
+            );
+            assert!(
+                app(&["--checkpoint-dir", "/tmp/checkpoints", "-Fd", "-f", "dump"])
+                    .checkpoint_dir()
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn fingerprint() {
+            let config = tempfile::NamedTempFile::new().unwrap();
+            fs::write(config.path(), "tables: []").unwrap();
+            let config_path = config.path().to_str().unwrap();
+
+            let fingerprint = app(&["-c", config_path]).fingerprint().unwrap();
+            assert_eq!(fingerprint.len(), 64);
+            assert_eq!(
+                fingerprint,
+                app(&["-c", config_path]).fingerprint().unwrap()
+            );
+
+            let options = Options::parse_from([
+                "DBNAME",
+                "postgres://postgres@localhost/other",
+                "-c",
+                config_path,
+            ]);
+            let other_db = App::from_options(options).unwrap().fingerprint().unwrap();
+            assert_ne!(fingerprint, other_db);
+
+            fs::write(config.path(), "tables: [] # changed").unwrap();
+            assert_ne!(
+                fingerprint,
+                app(&["-c", config_path]).fingerprint().unwrap()
+            );
+        }
+    }
+
     mod isolation_level {
         use super::*;
         use clap::Parser;
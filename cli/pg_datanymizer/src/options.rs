@@ -1,21 +1,32 @@
 use anyhow::{anyhow, Result};
-use clap::{ArgAction, Parser, ValueEnum};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use datanymizer_dumper::compression::Compression;
+use datanymizer_engine::transformers::FpeAlphabet;
 use url::Url;
 
-#[derive(ValueEnum, Debug, Clone)]
+#[derive(ValueEnum, Debug, Clone, Default)]
 #[value(rename_all = "PascalCase")]
 pub enum TransactionConfig {
     NoTransaction,
     ReadUncommitted,
+    #[default]
     ReadCommitted,
     RepeatableRead,
     Serializable,
 }
 
-impl Default for TransactionConfig {
-    fn default() -> Self {
-        Self::ReadCommitted
-    }
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// Plain SQL
+    #[default]
+    #[value(alias = "p")]
+    Plain,
+    /// A single archive file for `pg_restore`
+    #[value(alias = "c")]
+    Custom,
+    /// A directory archive for `pg_restore`
+    #[value(alias = "d")]
+    Directory,
 }
 
 #[derive(Parser, Debug, Clone, Default)]
@@ -23,20 +34,26 @@ fn default() -> Self {
     name = "pg_datanymizer",
     about = "Powerful Postgres database anonymizer with flexible rules",
     version,
-    disable_help_flag = true
+    disable_help_flag = true,
+    subcommand_negates_reqs = true
 )]
 pub struct Options {
-    #[arg(long, action = ArgAction::HelpLong)]
+    #[arg(long, global = true, action = ArgAction::HelpLong)]
     help: Option<bool>,
 
-    #[arg(name = "DBNAME", env = "PGDATABASE")]
-    database: String,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    // Optional only for subcommands
+    #[arg(name = "DBNAME", env = "PGDATABASE", required = true)]
+    database: Option<String>,
 
     #[arg(
         short,
         long,
         help = "Path to config file",
-        default_value = "./config.yml"
+        default_value = "./config.yml",
+        global = true
     )]
     pub config: String,
 
@@ -52,6 +69,7 @@ pub struct Options {
         short,
         long = "dbname",
         help = "database to dump",
+        global = true,
         default_value = "postgres"
     )]
     pub db_name: String,
@@ -60,6 +78,7 @@ pub struct Options {
         short,
         long,
         help = "Database server host or socket directory",
+        global = true,
         default_value = "localhost",
         env = "PGHOST"
     )]
@@ -69,6 +88,7 @@ pub struct Options {
         short,
         long,
         help = "Database server port number [default: 5432]",
+        global = true,
         env = "PGPORT"
     )]
     pub port: Option<u16>,
@@ -77,11 +97,18 @@ pub struct Options {
         short = 'U',
         long,
         help = "Connect as specified database user",
+        global = true,
         env = "PGUSER"
     )]
     pub username: Option<String>,
 
-    #[arg(short = 'W', long, help = "User password", env = "PGPASSWORD")]
+    #[arg(
+        short = 'W',
+        long,
+        help = "User password",
+        env = "PGPASSWORD",
+        global = true
+    )]
     pub password: Option<String>,
 
     #[arg(
@@ -102,12 +129,14 @@ pub struct Options {
 
     #[arg(
         long = "accept_invalid_hostnames",
+        global = true,
         help = "Accept or not invalid hostnames when using SSL"
     )]
     pub accept_invalid_hostnames: bool,
 
     #[arg(
         long = "accept_invalid_certs",
+        global = true,
         help = "Accept or not invalid certificates (e.g., self-signed) when using SSL"
     )]
     pub accept_invalid_certs: bool,
@@ -121,23 +150,220 @@ pub struct Options {
     #[arg(
         action = ArgAction::Count,
         short = 'v',
+        global = true,
         help = "Turn on verbose logging features to get more information about dumper errors"
     )]
     pub verbose: u8,
 
     #[arg(long, name = "no-indicator", help = "Disable indicator")]
     pub no_indicator: bool,
+
+    #[arg(
+        short,
+        long,
+        help = "Number of tables dumped at the same time (each job uses its own connection)",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub jobs: u16,
+
+    #[arg(
+        long,
+        help = "Number of threads transforming rows of each table",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub workers: u16,
+
+    #[arg(
+        value_enum,
+        short = 'F',
+        long,
+        default_value_t,
+        ignore_case = true,
+        help = "Output format (the custom and directory formats need the output file or directory in --file)"
+    )]
+    pub format: DumpFormat,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["FILE", "format"],
+        help = "Load the anonymized schema and data directly to the target database (an URL), instead of writing the dump"
+    )]
+    pub target_url: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "target_url",
+        help = "Compress the plain dump: gzip or zstd with an optional level, e.g. zstd:19 [default: guessed from the --file extension (.gz, .zst)]"
+    )]
+    pub compress: Option<Compression>,
+
+    #[arg(
+        long,
+        conflicts_with = "target_url",
+        help = "Save every dumped table to this directory, so a failed dump can be resumed by running it again with the same options"
+    )]
+    pub checkpoint_dir: Option<String>,
+
+    #[arg(
+        long,
+        help = "Fail before dumping if some columns look like personal data, but have no rules"
+    )]
+    pub strict: bool,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Detect columns with personal data and write a draft config with rules for them
+    Scan(ScanOptions),
+    /// Report columns that look like personal data, but have no rules in the config (fails if there are any)
+    Audit(AuditOptions),
+    /// Check the config: rules, tables, columns and SQL conditions (against the database or a schema file)
+    Validate(ValidateOptions),
+    /// Show original and transformed values of the first rows of a table (nothing is dumped)
+    Preview(PreviewOptions),
+    /// Anonymize the data of an existing plain dump file (made by `pg_dump`) without a database connection
+    TransformDump(TransformDumpOptions),
+    /// Decrypt values encrypted by the `fpe` transformer
+    Decrypt(DecryptOptions),
+}
+
+impl Command {
+    fn database(&self) -> Option<&str> {
+        match self {
+            Self::Scan(options) => Some(&options.database),
+            Self::Audit(options) => Some(&options.database),
+            Self::Validate(options) => options.database.as_deref(),
+            Self::Preview(options) => Some(&options.database),
+            Self::TransformDump(_) | Self::Decrypt(_) => None,
+        }
+    }
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ScanOptions {
+    #[arg(name = "DBNAME", env = "PGDATABASE")]
+    database: String,
+
+    #[arg(short, long, help = "Path to the draft config file [default: stdout]")]
+    pub output: Option<String>,
+
+    #[arg(
+        long,
+        help = "Number of rows sampled from each table",
+        default_value_t = 100
+    )]
+    pub sample_size: u32,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct AuditOptions {
+    #[arg(name = "DBNAME", env = "PGDATABASE")]
+    database: String,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct ValidateOptions {
+    #[arg(name = "DBNAME", env = "PGDATABASE")]
+    database: Option<String>,
+
+    #[arg(
+        long,
+        help = "Check tables and columns against this schema file (saved with --save-schema) instead of the database (no connection is made)"
+    )]
+    pub schema: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "schema",
+        help = "Save tables and columns of the database to this schema file"
+    )]
+    pub save_schema: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct PreviewOptions {
+    #[arg(name = "DBNAME", env = "PGDATABASE")]
+    database: String,
+
+    #[arg(short, long, help = "Table name (with or without the schema)")]
+    pub table: String,
+
+    #[arg(
+        short,
+        long,
+        help = "Number of rows",
+        default_value_t = 10,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub limit: u64,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct TransformDumpOptions {
+    #[arg(short, long, help = "Path to the plain dump file [default: stdin]")]
+    pub input: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        help = "Path to the anonymized dump file [default: stdout]"
+    )]
+    pub output: Option<String>,
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct DecryptOptions {
+    #[arg(
+        short,
+        long,
+        env = "DATANYMIZER_FPE_KEY",
+        hide_env_values = true,
+        help = "The key of the `fpe` transformer"
+    )]
+    pub key: String,
+
+    #[arg(
+        short,
+        long,
+        default_value = "digits",
+        value_parser = parse_alphabet,
+        help = "The alphabet of the `fpe` transformer: digits, lower_alphanumeric or alphanumeric"
+    )]
+    pub alphabet: FpeAlphabet,
+
+    #[arg(
+        short,
+        long,
+        default_value = "",
+        help = "The tweak of the `fpe` transformer"
+    )]
+    pub tweak: String,
+
+    #[arg(name = "VALUES", help = "Encrypted values [default: lines of stdin]")]
+    pub values: Vec<String>,
 }
 
 impl Options {
     pub fn database_url(&self) -> Result<Url> {
-        if let Ok(url) = Url::parse(self.database.as_str()) {
-            return match url.scheme() {
-                "postgres" | "postgresql" => Ok(url),
-                _ => Err(anyhow!("Scheme url error")),
-            };
+        let database = match &self.command {
+            Some(command) => command.database(),
+            None => self.database.as_deref(),
+        }
+        .unwrap_or_default();
+
+        if let Ok(url) = Url::parse(database) {
+            return check_scheme(url);
         }
-        self.build_url(Some(self.database.to_string()).filter(|x| !x.is_empty()))
+        self.build_url(Some(database.to_string()).filter(|x| !x.is_empty()))
+    }
+
+    pub fn target_url(&self) -> Result<Option<Url>> {
+        self.target_url
+            .as_deref()
+            .map(|url| check_scheme(Url::parse(url)?))
+            .transpose()
     }
 
     fn build_url(&self, override_db_name: Option<String>) -> Result<Url> {
@@ -162,6 +388,18 @@ fn build_url(&self, override_db_name: Option<String>) -> Result<Url> {
     }
 }
 
+fn parse_alphabet(s: &str) -> Result<FpeAlphabet, String> {
+    serde_json::from_value(serde_json::Value::from(s))
+        .map_err(|_| format!("unknown alphabet `{}`", s))
+}
+
+fn check_scheme(url: Url) -> Result<Url> {
+    match url.scheme() {
+        "postgres" | "postgresql" => Ok(url),
+        _ => Err(anyhow!("Scheme url error")),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,7 +407,7 @@ mod tests {
     #[test]
     fn parse_empty_config() {
         let cfg = Options {
-            database: "postgres://hostname/test".to_string(),
+            database: Some("postgres://hostname/test".to_string()),
             config: "./config.yml".to_string(),
             db_name: "test".to_string(),
             host: "localhost".to_string(),
@@ -243,6 +481,285 @@ fn parse_args() {
             TransactionConfig::ReadCommitted
         ));
         assert_eq!(options.pg_dump_args, vec!["--no-owner", "--no-acl"]);
+        assert_eq!(options.jobs, 1);
+        assert_eq!(options.workers, 1);
+        assert_eq!(options.format, DumpFormat::Plain);
+        assert_eq!(options.compress, None);
+        assert_eq!(options.checkpoint_dir, None);
+        assert!(!options.strict);
+    }
+
+    #[test]
+    fn scan() {
+        let cmd = vec![
+            "pg_datanymizer",
+            "scan",
+            "-h",
+            "hostname",
+            "-U",
+            "user",
+            "--sample-size",
+            "10",
+            "test",
+        ];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(
+            options.database_url().unwrap().as_str(),
+            "postgres://user@hostname/test"
+        );
+        match options.command {
+            Some(Command::Scan(scan)) => {
+                assert_eq!(scan.sample_size, 10);
+                assert_eq!(scan.output, None);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let options = Options::parse_from(vec!["pg_datanymizer", "database"]);
+        assert!(options.command.is_none());
+    }
+
+    #[test]
+    fn audit() {
+        let cmd = vec!["pg_datanymizer", "audit", "-c", "some_config.yml", "test"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.config, "some_config.yml");
+        assert_eq!(
+            options.database_url().unwrap().as_str(),
+            "postgres://localhost/test"
+        );
+        assert!(matches!(options.command, Some(Command::Audit(_))));
+    }
+
+    #[test]
+    fn validate() {
+        let cmd = vec![
+            "pg_datanymizer",
+            "validate",
+            "-c",
+            "some_config.yml",
+            "test",
+        ];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.config, "some_config.yml");
+        assert_eq!(
+            options.database_url().unwrap().as_str(),
+            "postgres://localhost/test"
+        );
+        assert!(matches!(options.command, Some(Command::Validate(_))));
+
+        let cmd = vec!["pg_datanymizer", "validate", "--schema", "schema.json"];
+        match Options::parse_from(cmd).command {
+            Some(Command::Validate(validate)) => {
+                assert_eq!(validate.schema, Some(String::from("schema.json")));
+                assert_eq!(validate.save_schema, None);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let cmd = vec![
+            "pg_datanymizer",
+            "validate",
+            "--schema",
+            "schema.json",
+            "--save-schema",
+            "new_schema.json",
+        ];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn preview() {
+        let cmd = vec![
+            "pg_datanymizer",
+            "preview",
+            "--table",
+            "users",
+            "--limit",
+            "20",
+            "test",
+        ];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(
+            options.database_url().unwrap().as_str(),
+            "postgres://localhost/test"
+        );
+        match options.command {
+            Some(Command::Preview(preview)) => {
+                assert_eq!(preview.table, "users");
+                assert_eq!(preview.limit, 20);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let cmd = vec!["pg_datanymizer", "preview", "-t", "users", "test"];
+        match Options::parse_from(cmd).command {
+            Some(Command::Preview(preview)) => assert_eq!(preview.limit, 10),
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let cmd = vec!["pg_datanymizer", "preview", "test"];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn transform_dump() {
+        let cmd = vec![
+            "pg_datanymizer",
+            "transform-dump",
+            "-c",
+            "some_config.yml",
+            "--input",
+            "dump.sql",
+            "-o",
+            "fake_dump.sql",
+        ];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.config, "some_config.yml");
+        match options.command {
+            Some(Command::TransformDump(transform_dump)) => {
+                assert_eq!(transform_dump.input, Some(String::from("dump.sql")));
+                assert_eq!(transform_dump.output, Some(String::from("fake_dump.sql")));
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let cmd = vec!["pg_datanymizer", "transform-dump"];
+        match Options::parse_from(cmd).command {
+            Some(Command::TransformDump(transform_dump)) => {
+                assert_eq!(transform_dump.input, None);
+                assert_eq!(transform_dump.output, None);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+    }
+
+    #[test]
+    fn decrypt() {
+        let cmd = vec![
+            "pg_datanymizer",
+            "decrypt",
+            "--key",
+            "secret",
+            "--alphabet",
+            "alphanumeric",
+            "a1b2c3",
+            "d4e5f6",
+        ];
+        match Options::parse_from(cmd).command {
+            Some(Command::Decrypt(decrypt)) => {
+                assert_eq!(decrypt.key, "secret");
+                assert_eq!(decrypt.alphabet, FpeAlphabet::Alphanumeric);
+                assert_eq!(decrypt.tweak, "");
+                assert_eq!(decrypt.values, vec!["a1b2c3", "d4e5f6"]);
+            }
+            command => panic!("unexpected command {:?}", command),
+        }
+
+        let cmd = vec!["pg_datanymizer", "decrypt", "-k", "secret", "-a", "hex"];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn strict() {
+        let options = Options::parse_from(vec!["pg_datanymizer", "--strict", "database"]);
+        assert!(options.strict);
+    }
+
+    #[test]
+    fn jobs() {
+        let cmd = vec!["pg_datanymizer", "-j", "4", "database"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.jobs, 4);
+
+        let cmd = vec!["pg_datanymizer", "--jobs", "0", "database"];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn workers() {
+        let cmd = vec!["pg_datanymizer", "--workers", "8", "database"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.workers, 8);
+
+        let cmd = vec!["pg_datanymizer", "--workers", "0", "database"];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn format() {
+        let cmd = vec!["pg_datanymizer", "--format", "directory", "database"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.format, DumpFormat::Directory);
+
+        let cmd = vec!["pg_datanymizer", "-Fc", "database"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.format, DumpFormat::Custom);
+
+        let cmd = vec!["pg_datanymizer", "--format", "tar", "database"];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn target_url() {
+        let cmd = vec![
+            "pg_datanymizer",
+            "--target-url",
+            "postgres://user@hostname/staging",
+            "database",
+        ];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(
+            options.target_url().unwrap().unwrap().as_str(),
+            "postgres://user@hostname/staging"
+        );
+
+        let options = Options::parse_from(vec!["pg_datanymizer", "database"]);
+        assert!(options.target_url().unwrap().is_none());
+
+        let cmd = vec![
+            "pg_datanymizer",
+            "--target-url",
+            "mysql://user@hostname/staging",
+            "database",
+        ];
+        assert!(Options::parse_from(cmd).target_url().is_err());
+
+        let cmd = vec![
+            "pg_datanymizer",
+            "--target-url",
+            "postgres://user@hostname/staging",
+            "-f",
+            "dump.sql",
+            "database",
+        ];
+        assert!(Options::try_parse_from(cmd).is_err());
+    }
+
+    #[test]
+    fn compress() {
+        let cmd = vec!["pg_datanymizer", "--compress", "zstd:19", "database"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.compress, Some(Compression::Zstd(19)));
+
+        let cmd = vec!["pg_datanymizer", "--compress", "gzip", "database"];
+        let options = Options::parse_from(cmd);
+
+        assert_eq!(options.compress, Some(Compression::Gzip(6)));
+
+        let cmd = vec!["pg_datanymizer", "--compress", "lz4", "database"];
+        assert!(Options::try_parse_from(cmd).is_err());
     }
 
     #[test]
@@ -251,11 +768,11 @@ fn support_multiple_schemes() {
         let scheme2 = "postgresql://user@hostname/test";
 
         let opts1 = Options {
-            database: scheme1.to_string(),
+            database: Some(scheme1.to_string()),
             ..Default::default()
         };
         let opts2 = Options {
-            database: scheme2.to_string(),
+            database: Some(scheme2.to_string()),
             ..Default::default()
         };
 
@@ -0,0 +1,59 @@
+use anyhow::Result;
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::options::Options;
+
+use datanymizer_dumper::{
+    indicator::{ConsoleIndicator, Indicator, SilentIndicator},
+    sqlite::{connector::Connector, dumper::SqliteDumper},
+    Dumper,
+};
+use datanymizer_engine::{Engine, Settings};
+
+pub struct App {
+    options: Options,
+}
+
+impl App {
+    pub fn from_options(options: Options) -> Result<Self> {
+        Ok(App { options })
+    }
+
+    pub fn run(&self) -> Result<()> {
+        // The dumper writes the target database itself
+        if self.options.target.is_some() {
+            return if self.options.no_indicator {
+                self.make_dump(io::sink(), SilentIndicator)
+            } else {
+                self.make_dump(io::sink(), ConsoleIndicator::new())
+            };
+        }
+
+        match (&self.options.file, &self.options.no_indicator) {
+            (Some(filename), false) => {
+                self.make_dump(File::create(filename)?, ConsoleIndicator::new())
+            }
+            (Some(filename), true) => self.make_dump(File::create(filename)?, SilentIndicator),
+            _ => self.make_dump(io::stdout(), SilentIndicator),
+        }
+    }
+
+    fn make_dump<W, I>(&self, w: W, i: I) -> Result<()>
+    where
+        W: 'static + Write + Send,
+        I: 'static + Indicator + Send,
+    {
+        let mut connection = Connector::new(PathBuf::from(&self.options.database)).connect()?;
+        let settings = Settings::new(self.options.config.clone())?;
+
+        let mut dumper = SqliteDumper::new(Engine::new(settings), w, i)?;
+        if let Some(target) = &self.options.target {
+            dumper = dumper.with_target(PathBuf::from(target))?;
+        }
+        dumper.dump(&mut connection)
+    }
+}
@@ -0,0 +1,91 @@
+use clap::{ArgAction, Parser};
+
+#[derive(Parser, Debug, Clone, Default)]
+#[command(
+    name = "sqlite_datanymizer",
+    about = "Powerful SQLite database anonymizer with flexible rules",
+    version
+)]
+pub struct Options {
+    #[arg(name = "DATABASE", help = "Path to the source database file")]
+    pub database: String,
+
+    #[arg(
+        short,
+        long,
+        help = "Path to config file",
+        default_value = "./config.yml"
+    )]
+    pub config: String,
+
+    #[arg(
+        short,
+        long,
+        name = "FILE",
+        help = "Path to the SQL script, example: /tmp/dump.sql [default: stdout]"
+    )]
+    pub file: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        conflicts_with = "FILE",
+        help = "Write a new database file instead of the SQL script (the file shouldn't exist)"
+    )]
+    pub target: Option<String>,
+
+    #[arg(
+        action = ArgAction::Count,
+        short = 'v',
+        help = "Turn on verbose logging features to get more information about dumper errors"
+    )]
+    pub verbose: u8,
+
+    #[arg(long, name = "no-indicator", help = "Disable indicator")]
+    pub no_indicator: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args() {
+        let options = Options::parse_from([
+            "sqlite_datanymizer",
+            "-c",
+            "some_config.yml",
+            "-f",
+            "some_file.sql",
+            "app.sqlite",
+        ]);
+
+        assert_eq!(options.database, "app.sqlite");
+        assert_eq!(options.config, "some_config.yml");
+        assert_eq!(options.file, Some(String::from("some_file.sql")));
+        assert_eq!(options.target, None);
+        assert_eq!(options.verbose, 0);
+        assert!(!options.no_indicator);
+    }
+
+    #[test]
+    fn target() {
+        let options = Options::parse_from([
+            "sqlite_datanymizer",
+            "--target",
+            "fake.sqlite",
+            "app.sqlite",
+        ]);
+        assert_eq!(options.target, Some(String::from("fake.sqlite")));
+
+        assert!(Options::try_parse_from([
+            "sqlite_datanymizer",
+            "--target",
+            "fake.sqlite",
+            "-f",
+            "dump.sql",
+            "app.sqlite",
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use anyhow::{anyhow, Result};
+use flate2::write::GzEncoder;
+use std::{
+    io::{self, Write},
+    str::FromStr,
+};
+
+/// Compression of the plain dump
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip with the level (0 - 9)
+    Gzip(u32),
+    /// zstd with the level (1 - 22)
+    Zstd(i32),
+}
+
+impl Compression {
+    pub const DEFAULT_GZIP_LEVEL: u32 = 6;
+    pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+    /// Guesses the compression from the file extension (`.gz` or `.zst`)
+    pub fn from_extension(path: &str) -> Option<Self> {
+        if path.ends_with(".gz") {
+            Some(Self::Gzip(Self::DEFAULT_GZIP_LEVEL))
+        } else if path.ends_with(".zst") {
+            Some(Self::Zstd(Self::DEFAULT_ZSTD_LEVEL))
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = anyhow::Error;
+
+    /// Parses `gzip` or `zstd` with an optional level, e.g. `zstd:19`
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, level) = match s.split_once(':') {
+            Some((name, level)) => (name, Some(level)),
+            None => (s, None),
+        };
+
+        match name {
+            "gzip" => {
+                let level = level.map_or(Ok(Self::DEFAULT_GZIP_LEVEL), str::parse)?;
+                if level > 9 {
+                    return Err(anyhow!("gzip level must be from 0 to 9"));
+                }
+                Ok(Self::Gzip(level))
+            }
+            "zstd" => {
+                let level = level.map_or(Ok(Self::DEFAULT_ZSTD_LEVEL), str::parse)?;
+                if !zstd::compression_level_range().contains(&level) {
+                    return Err(anyhow!("Invalid zstd level {}", level));
+                }
+                Ok(Self::Zstd(level))
+            }
+            _ => Err(anyhow!(
+                "Unknown compression {} (possible values: gzip, zstd)",
+                name
+            )),
+        }
+    }
+}
+
+/// The dump writer (compressed or not)
+pub enum DumpWriter<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> DumpWriter<W> {
+    pub fn new(w: W, compression: Option<Compression>) -> io::Result<Self> {
+        Ok(match compression {
+            None => Self::Plain(w),
+            Some(Compression::Gzip(level)) => {
+                Self::Gzip(GzEncoder::new(w, flate2::Compression::new(level)))
+            }
+            Some(Compression::Zstd(level)) => Self::Zstd(zstd::Encoder::new(w, level)?),
+        })
+    }
+
+    /// Writes the end of the compressed stream and flushes the writer
+    pub fn finish(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gzip(encoder) => encoder.try_finish(),
+            Self::Zstd(encoder) => {
+                encoder.do_finish()?;
+                encoder.get_mut().flush()
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for DumpWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn parse() {
+        assert_eq!("gzip".parse::<Compression>().unwrap(), Compression::Gzip(6));
+        assert_eq!(
+            "gzip:9".parse::<Compression>().unwrap(),
+            Compression::Gzip(9)
+        );
+        assert_eq!("zstd".parse::<Compression>().unwrap(), Compression::Zstd(3));
+        assert_eq!(
+            "zstd:19".parse::<Compression>().unwrap(),
+            Compression::Zstd(19)
+        );
+
+        assert!("gzip:10".parse::<Compression>().is_err());
+        assert!("zstd:100".parse::<Compression>().is_err());
+        assert!("zstd:high".parse::<Compression>().is_err());
+        assert!("bzip2".parse::<Compression>().is_err());
+    }
+
+    #[test]
+    fn from_extension() {
+        assert_eq!(
+            Compression::from_extension("/tmp/dump.sql.gz"),
+            Some(Compression::Gzip(6))
+        );
+        assert_eq!(
+            Compression::from_extension("dump.sql.zst"),
+            Some(Compression::Zstd(3))
+        );
+        assert_eq!(Compression::from_extension("dump.sql"), None);
+    }
+
+    fn write(compression: Option<Compression>) -> Vec<u8> {
+        let mut w = DumpWriter::new(vec![], compression).unwrap();
+        w.write_all(b"COPY table FROM STDIN;\n").unwrap();
+        w.finish().unwrap();
+        match w {
+            DumpWriter::Plain(w) => w,
+            DumpWriter::Gzip(encoder) => encoder.finish().unwrap(),
+            DumpWriter::Zstd(encoder) => encoder.finish().unwrap(),
+        }
+    }
+
+    #[test]
+    fn plain() {
+        assert_eq!(write(None), b"COPY table FROM STDIN;\n");
+    }
+
+    #[test]
+    fn gzip() {
+        let mut s = String::new();
+        flate2::read::GzDecoder::new(write(Some(Compression::Gzip(9))).as_slice())
+            .read_to_string(&mut s)
+            .unwrap();
+        assert_eq!(s, "COPY table FROM STDIN;\n");
+    }
+
+    #[test]
+    fn zstd() {
+        let data = zstd::decode_all(write(Some(Compression::Zstd(3))).as_slice()).unwrap();
+        assert_eq!(data, b"COPY table FROM STDIN;\n");
+    }
+}
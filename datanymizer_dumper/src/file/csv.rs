@@ -0,0 +1,105 @@
+use anyhow::{anyhow, Result};
+use datanymizer_engine::Engine;
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+/// Columns are taken from the header. Empty values are passed to transformers as they are,
+/// NULL values from transformers are written as empty ones.
+pub fn transform<R: BufRead, W: Write>(
+    engine: &Engine,
+    table: &str,
+    input: R,
+    output: W,
+) -> Result<u64> {
+    let mut reader = ::csv::Reader::from_reader(input);
+    let mut writer = ::csv::Writer::from_writer(output);
+
+    let headers = reader.headers()?.clone();
+    let column_indexes: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, column)| (column.to_string(), i))
+        .collect();
+    writer.write_record(&headers)?;
+
+    let mut record = ::csv::StringRecord::new();
+    let mut count = 0;
+    while reader.read_record(&mut record)? {
+        let values: Vec<Option<&str>> = record.iter().map(Some).collect();
+        let transformed_values = engine
+            .process_row(table.to_string(), &column_indexes, &values)
+            .map_err(|e| {
+                let line = record.position().map_or(0, |p| p.line());
+                anyhow!("Line {}: {}", line, e)
+            })?;
+        writer.write_record(
+            transformed_values
+                .iter()
+                .map(|v| v.as_deref().unwrap_or("")),
+        )?;
+        count += 1;
+    }
+    writer.flush()?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datanymizer_engine::Settings;
+
+    fn transform_csv(config: &str, input: &str) -> Result<String> {
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+        let mut output = vec![];
+        transform(&engine, "users", input.as_bytes(), &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn transform_rows() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ _0 }}, Jr."
+                comment:
+                  set_null: ~
+        "#;
+        let input = "id,name,comment\n1,\"Smith, John\",\"Multi\nline\"\n2,Ann,\n";
+
+        assert_eq!(
+            transform_csv(config, input).unwrap(),
+            "id,name,comment\n1,\"Smith, John, Jr.\",\n2,\"Ann, Jr.\",\n"
+        );
+    }
+
+    #[test]
+    fn without_rules() {
+        let input = "id,name\n1,\"a \"\"quoted\"\" name\"\n";
+
+        assert_eq!(transform_csv("tables: []", input).unwrap(), input);
+    }
+
+    #[test]
+    fn unknown_column() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                email:
+                  email: {}
+        "#;
+
+        assert!(transform_csv(config, "id,name\n1,John\n").is_err());
+    }
+
+    #[test]
+    fn wrong_number_of_values() {
+        assert!(transform_csv("tables: []", "id,name\n1,John,extra\n").is_err());
+    }
+}
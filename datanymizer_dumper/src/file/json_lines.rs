@@ -0,0 +1,123 @@
+use anyhow::{anyhow, Result};
+use datanymizer_engine::Engine;
+use serde_json::{Map, Value};
+use std::io::{BufRead, Write};
+
+/// Every line is an object (see [Engine::transform_json_object])
+pub fn transform<R: BufRead, W: Write>(
+    engine: &Engine,
+    table: &str,
+    input: R,
+    mut output: W,
+) -> Result<u64> {
+    let mut count = 0;
+    for (i, line) in input.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            output.write_all(line.as_bytes())?;
+            output.write_all(b"\n")?;
+            continue;
+        }
+
+        let object: Map<String, Value> = serde_json::from_str(&line)
+            .map_err(|e| anyhow!("Line {}: the line isn't a JSON object ({})", i + 1, e))?;
+        match engine
+            .transform_json_object(table, &object)
+            .map_err(|e| anyhow!("Line {}: {}", i + 1, e))?
+        {
+            Some(transformed) => serde_json::to_writer(&mut output, &transformed)?,
+            // Untouched lines are written as they are
+            None => output.write_all(line.as_bytes())?,
+        }
+        output.write_all(b"\n")?;
+        count += 1;
+    }
+    output.flush()?;
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datanymizer_engine::Settings;
+
+    fn transform_lines(config: &str, input: &str) -> Result<String> {
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+        let mut output = vec![];
+        transform(&engine, "users", input.as_bytes(), &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn transform_values() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ _0 }} Jr."
+                age:
+                  template:
+                    format: "{{ _0 | int + 1 }}"
+                comment:
+                  set_null: ~
+                nickname:
+                  template:
+                    format: "{{ prev.name }}"
+                  on_null: transform
+        "#;
+        let input = "{\"id\":1,\"name\":\"John\",\"age\":30,\"comment\":\"text\"}\n\
+            \n\
+            {\"name\":\"Ann\",\"age\":25,\"comment\":null,\"nickname\":\"an\"}\n";
+
+        assert_eq!(
+            transform_lines(config, input).unwrap(),
+            "{\"id\":1,\"name\":\"John Jr.\",\"age\":31,\"comment\":null,\"nickname\":\"John\"}\n\
+            \n\
+            {\"name\":\"Ann Jr.\",\"age\":26,\"comment\":null,\"nickname\":\"Ann\"}\n"
+        );
+    }
+
+    #[test]
+    fn nested_fields() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                profile:
+                  json:
+                    fields:
+                      - name: "email"
+                        selector: "$.contacts.email"
+                        quote: true
+                        rule:
+                          template:
+                            format: "user@example.com"
+        "#;
+        let input = r#"{"id":1,"profile":{"contacts":{"email":"john@mail.org","phone":"123"}}}"#;
+
+        assert_eq!(
+            transform_lines(config, input).unwrap(),
+            "{\"id\":1,\"profile\":{\"contacts\":{\"email\":\"user@example.com\",\"phone\":\"123\"}}}\n"
+        );
+    }
+
+    #[test]
+    fn untouched_lines() {
+        let input = "{ \"id\": 1.50, \"name\": \"John\" }\n";
+
+        assert_eq!(transform_lines("tables: []", input).unwrap(), input);
+    }
+
+    #[test]
+    fn invalid_line() {
+        assert_eq!(
+            transform_lines("tables: []", "{\"id\": 1}\n[1, 2]\n")
+                .unwrap_err()
+                .to_string(),
+            "Line 2: the line isn't a JSON object (invalid type: sequence, expected a map at line 1 column 0)"
+        );
+    }
+}
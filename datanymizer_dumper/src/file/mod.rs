@@ -0,0 +1,116 @@
+//! Anonymization of data files (CSV and JSON Lines). Every file is a table of the config.
+
+use anyhow::{anyhow, Result};
+use datanymizer_engine::Engine;
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    str::FromStr,
+};
+
+mod csv;
+mod json_lines;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileFormat {
+    /// CSV with the header
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl FileFormat {
+    /// Guesses the format from the file extension (`.csv`, `.jsonl` or `.ndjson`)
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "jsonl" | "ndjson" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for FileFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "csv" => Ok(Self::Csv),
+            "jsonl" | "ndjson" => Ok(Self::JsonLines),
+            _ => Err(anyhow!(
+                "Unknown file format {} (possible values: csv, jsonl)",
+                s
+            )),
+        }
+    }
+}
+
+/// Transforms rows of a file with the rules of the table, the output has the same format
+pub struct FileTransformer {
+    engine: Engine,
+    table: String,
+}
+
+impl FileTransformer {
+    pub fn new(engine: Engine, table: String) -> Self {
+        Self { engine, table }
+    }
+
+    /// The table name is the file name without the extension (e.g. `users` for `exports/users.csv`)
+    pub fn table_name(path: &Path) -> Option<String> {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(String::from)
+    }
+
+    /// Reads and writes rows one by one. Returns the number of rows.
+    pub fn transform<R: BufRead, W: Write>(
+        &self,
+        format: FileFormat,
+        input: R,
+        output: W,
+    ) -> Result<u64> {
+        match format {
+            FileFormat::Csv => csv::transform(&self.engine, &self.table, input, output),
+            FileFormat::JsonLines => {
+                json_lines::transform(&self.engine, &self.table, input, output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_extension() {
+        assert_eq!(
+            FileFormat::from_extension(Path::new("data/users.CSV")),
+            Some(FileFormat::Csv)
+        );
+        assert_eq!(
+            FileFormat::from_extension(Path::new("users.ndjson")),
+            Some(FileFormat::JsonLines)
+        );
+        assert_eq!(FileFormat::from_extension(Path::new("users.json")), None);
+        assert_eq!(FileFormat::from_extension(Path::new("users")), None);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            "jsonl".parse::<FileFormat>().unwrap(),
+            FileFormat::JsonLines
+        );
+        assert!("xml".parse::<FileFormat>().is_err());
+    }
+
+    #[test]
+    fn table_name() {
+        assert_eq!(
+            FileTransformer::table_name(Path::new("exports/users.csv")),
+            Some(String::from("users"))
+        );
+    }
+}
@@ -1,7 +1,12 @@
-use indicatif::{HumanDuration, ProgressBar, ProgressStyle};
+use indicatif::{HumanDuration, MultiProgress, ProgressBar, ProgressStyle};
 use std::time::Duration;
 
 pub trait Indicator {
+    /// Returns an indicator for a parallel job (with its own progress bar)
+    fn fork(&self) -> Self
+    where
+        Self: Sized;
+
     fn start_pb(&self, _size: u64, _prefix: &str) {}
 
     fn inc_pb(&self, _i: u64) {}
@@ -13,10 +18,15 @@ fn debug_msg(&self, _msg: &str) {}
 
 pub struct SilentIndicator;
 
-impl Indicator for SilentIndicator {}
+impl Indicator for SilentIndicator {
+    fn fork(&self) -> Self {
+        Self
+    }
+}
 
 pub struct ConsoleIndicator {
     pb: ProgressBar,
+    multi: MultiProgress,
 }
 
 impl ConsoleIndicator {
@@ -28,11 +38,24 @@ pub fn new() -> Self {
 impl Default for ConsoleIndicator {
     fn default() -> Self {
         let pb = ProgressBar::new(0);
-        Self { pb }
+        let multi = MultiProgress::new();
+        Self { pb, multi }
+    }
+}
+
+impl Drop for ConsoleIndicator {
+    fn drop(&mut self) {
+        self.multi.remove(&self.pb);
     }
 }
 
 impl Indicator for ConsoleIndicator {
+    fn fork(&self) -> Self {
+        let pb = self.multi.add(ProgressBar::new(0));
+        let multi = self.multi.clone();
+        Self { pb, multi }
+    }
+
     fn start_pb(&self, size: u64, name: &str) {
         self.pb.set_length(size);
         self.pb.set_prefix(name.to_owned());
@@ -65,7 +88,7 @@ fn finish_pb(&self, name: &str, duration: Duration) {
     }
 
     fn debug_msg(&self, msg: &str) {
-        println!("{}", msg);
+        self.multi.suspend(|| println!("{}", msg));
     }
 }
 
@@ -107,5 +130,19 @@ fn pb_overflow_progress() {
             ci.inc_pb(100);
             ci.finish_pb("name", Duration::new(1, 0));
         }
+
+        #[test]
+        fn forks() {
+            let ci = ConsoleIndicator::new();
+            let jobs: Vec<_> = (0..3).map(|_| ci.fork()).collect();
+            for (i, job) in jobs.iter().enumerate() {
+                job.start_pb(100, format!("table{}", i).as_str());
+                job.inc_pb(10);
+                job.debug_msg("some message");
+            }
+            for (i, job) in jobs.iter().enumerate() {
+                job.finish_pb(format!("table{}", i).as_str(), Duration::new(1, 0));
+            }
+        }
     }
 }
@@ -1,12 +1,20 @@
 use anyhow::Result;
 use core::iter::Iterator;
-use datanymizer_engine::{Filter, Settings};
+use datanymizer_engine::{ColumnType, Filter, Settings};
 use indicatif::HumanDuration;
 use solvent::DepGraph;
 use std::{collections::HashMap, hash::Hash, time::Instant};
 
+pub mod compression;
+pub mod file;
 pub mod indicator;
+#[cfg(feature = "mysql")]
+pub mod mysql;
 pub mod postgres;
+pub mod preview;
+pub mod scan;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 // Dumper makes dump with same stages
 pub trait Dumper: 'static + Sized + Send {
@@ -32,6 +40,13 @@ fn prepare(&mut self, connection: &mut Self::Connection) -> Result<()> {
         let mut tables = self.schema_inspector().ordered_tables(connection)?;
         sort_tables(&mut tables, &self.settings().table_order);
         let tables: Vec<_> = tables.into_iter().map(|(t, _)| t).collect();
+        if self.settings().domains.link_foreign_keys {
+            self.link_foreign_keys(&tables);
+        }
+        for table in &tables {
+            self.settings_mut()
+                .set_column_types(&table.get_names(), &table.get_column_types());
+        }
         self.filter_mut()
             .load_tables(tables.iter().map(|t| t.get_full_name()).collect());
         self.set_tables(tables);
@@ -39,6 +54,41 @@ fn prepare(&mut self, connection: &mut Self::Connection) -> Result<()> {
         Ok(())
     }
 
+    /// Copies rules of the referenced columns to the foreign key columns (see [Settings::link_foreign_key]).
+    /// Repeats until nothing changes, so chains of foreign keys are linked too.
+    fn link_foreign_keys(&mut self, tables: &[<Self::SchemaInspector as SchemaInspector>::Table]) {
+        let names: HashMap<_, _> = tables
+            .iter()
+            .map(|t| (t.get_full_name(), t.get_names()))
+            .collect();
+
+        let mut linked = true;
+        while linked {
+            linked = false;
+            for table in tables {
+                for (column, parent, parent_column) in table.get_foreign_key_columns() {
+                    if let Some(parent_names) = names.get(&parent) {
+                        if self.settings_mut().link_foreign_key(
+                            &table.get_names(),
+                            &column,
+                            parent_names,
+                            &parent_column,
+                        ) {
+                            self.debug(format!(
+                                "Linked {}.{} to {}.{}",
+                                table.get_full_name(),
+                                column,
+                                parent,
+                                parent_column
+                            ));
+                            linked = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// Stage before dumping data. It makes dump schema with any options
     fn pre_data(&mut self, _connection: &mut Self::Connection) -> Result<()>;
 
@@ -57,7 +107,11 @@ fn filter_table(&self, table: String) -> bool {
 
     fn settings(&self) -> &Settings;
 
-    fn filter_mut(&mut self) -> &mut Filter;
+    fn settings_mut(&mut self) -> &mut Settings;
+
+    fn filter_mut(&mut self) -> &mut Filter {
+        &mut self.settings_mut().filter
+    }
 
     fn write_log(&mut self, message: String) -> Result<()>;
 
@@ -154,12 +208,25 @@ pub trait Table<T>: Sized + Send + Clone + Eq + Hash {
     fn get_column_indexes(&self) -> &HashMap<String, usize>;
     /// Get depended table names
     fn get_dep_table_names(&self) -> Vec<String>;
+    /// Get foreign key columns as (column name, referenced table full name, referenced column name)
+    fn get_foreign_key_columns(&self) -> Vec<(String, String, String)>;
+    /// Get column name - type map (columns with unknown types are skipped)
+    fn get_column_types(&self) -> HashMap<String, ColumnType> {
+        self.get_columns()
+            .iter()
+            .filter_map(|c| c.column_type().map(|t| (c.name().to_string(), t)))
+            .collect()
+    }
 }
 
 pub trait ColumnData<T> {
     fn position(&self) -> usize;
     fn name(&self) -> &str;
     fn inner_kind(&self) -> Option<T>;
+    /// Type metadata for the engine (see [datanymizer_engine::Rule::on_overflow])
+    fn column_type(&self) -> Option<ColumnType> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -189,6 +256,7 @@ struct MockTable {
         schema: &'static str,
         name: &'static str,
         dep_table_names: Vec<&'static str>,
+        fk_columns: Vec<(&'static str, &'static str, &'static str)>,
         col_map: HashMap<String, usize>,
     }
 
@@ -200,6 +268,7 @@ fn new(schema: &'static str, name: &'static str) -> Self {
                 schema,
                 name,
                 dep_table_names,
+                fk_columns: vec![],
                 col_map,
             }
         }
@@ -214,9 +283,22 @@ fn with_deps(
                 schema,
                 name,
                 dep_table_names,
+                fk_columns: vec![],
                 col_map,
             }
         }
+
+        fn with_fk_columns(
+            schema: &'static str,
+            name: &'static str,
+            fk_columns: Vec<(&'static str, &'static str, &'static str)>,
+        ) -> Self {
+            let dep_table_names = fk_columns.iter().map(|(_, t, _)| *t).collect();
+            Self {
+                fk_columns,
+                ..Self::with_deps(schema, name, dep_table_names)
+            }
+        }
     }
 
     impl Hash for MockTable {
@@ -261,6 +343,13 @@ fn get_column_indexes(&self) -> &HashMap<String, usize> {
         fn get_dep_table_names(&self) -> Vec<String> {
             self.dep_table_names.iter().map(|s| s.to_string()).collect()
         }
+
+        fn get_foreign_key_columns(&self) -> Vec<(String, String, String)> {
+            self.fk_columns
+                .iter()
+                .map(|(c, t, pc)| (c.to_string(), t.to_string(), pc.to_string()))
+                .collect()
+        }
     }
 
     struct MockConnection;
@@ -358,8 +447,8 @@ fn settings(&self) -> &Settings {
             &self.settings
         }
 
-        fn filter_mut(&mut self) -> &mut Filter {
-            &mut self.settings.filter
+        fn settings_mut(&mut self) -> &mut Settings {
+            &mut self.settings
         }
 
         fn write_log(&mut self, _message: String) -> Result<()> {
@@ -451,6 +540,83 @@ fn prepare() {
         }
     }
 
+    mod link_foreign_keys {
+        use super::*;
+
+        fn tables() -> Vec<MockTable> {
+            vec![
+                MockTable::new("public", "users"),
+                MockTable::with_fk_columns(
+                    "public",
+                    "orders",
+                    vec![("user_email", "public.users", "email")],
+                ),
+                MockTable::with_fk_columns(
+                    "public",
+                    "payments",
+                    vec![
+                        ("order_email", "public.orders", "user_email"),
+                        ("user_id", "public.users", "id"),
+                    ],
+                ),
+            ]
+        }
+
+        fn prepare(cfg: &str) -> MockDumper {
+            let mut dumper = MockDumper::new(cfg, MockSchemaInspector::new(tables()));
+            dumper.prepare(&mut MockConnection).unwrap();
+            dumper
+        }
+
+        #[test]
+        fn enabled() {
+            let dumper = prepare(
+                r#"
+                tables:
+                  - name: users
+                    rules:
+                      email:
+                        email: {}
+                domains:
+                  link_foreign_keys: true
+                "#,
+            );
+
+            let s = &dumper.settings;
+            assert_eq!(
+                s.get_table("users").unwrap().rules["email"].domain,
+                Some(String::from("users.email"))
+            );
+            assert_eq!(
+                s.get_table("public.orders").unwrap().rules["user_email"].domain,
+                Some(String::from("users.email"))
+            );
+            let payments = s.get_table("public.payments").unwrap();
+            assert_eq!(
+                payments.rules["order_email"].domain,
+                Some(String::from("users.email"))
+            );
+            assert!(!payments.rules.contains_key("user_id"));
+        }
+
+        #[test]
+        fn disabled() {
+            let dumper = prepare(
+                r#"
+                tables:
+                  - name: users
+                    rules:
+                      email:
+                        email: {}
+                "#,
+            );
+
+            let s = &dumper.settings;
+            assert_eq!(s.get_table("users").unwrap().rules["email"].domain, None);
+            assert!(s.find_table(&["public.orders", "orders"]).is_none());
+        }
+    }
+
     mod inspector {
         use super::*;
 
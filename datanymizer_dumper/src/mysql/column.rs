@@ -0,0 +1,97 @@
+use crate::ColumnData;
+use datanymizer_engine::ColumnType;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Eq)]
+pub struct MySqlColumn {
+    /// Ordinal position of column
+    pub position: u32,
+    /// Column name
+    pub name: String,
+    /// Column data type (e.g. `varchar`)
+    pub data_type: String,
+    /// Type metadata (length, precision and nullability)
+    pub column_type: Option<ColumnType>,
+}
+
+/// Columns of the column query: name, position, data type, full column type (e.g. `int(10) unsigned`),
+/// maximum length, numeric precision, numeric scale and nullability (`YES` or `NO`)
+pub type ColumnRow = (
+    String,
+    u32,
+    String,
+    String,
+    Option<u64>,
+    Option<u64>,
+    Option<u64>,
+    String,
+);
+
+impl PartialEq for MySqlColumn {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+    }
+}
+
+impl PartialOrd for MySqlColumn {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MySqlColumn {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.position.cmp(&other.position)
+    }
+}
+
+impl From<ColumnRow> for MySqlColumn {
+    fn from(
+        (name, position, data_type, full_type, length, precision, scale, nullable): ColumnRow,
+    ) -> Self {
+        let type_name = if full_type.contains("unsigned") {
+            format!("{} unsigned", data_type)
+        } else {
+            data_type.clone()
+        };
+        let mut column_type = ColumnType::new(&type_name);
+        match (data_type.as_str(), length, precision) {
+            ("varchar" | "char", Some(length), _) => {
+                column_type = column_type.with_length(length as u32)
+            }
+            ("decimal", _, Some(precision)) => {
+                column_type =
+                    column_type.with_precision(precision as u32, scale.unwrap_or(0) as u32)
+            }
+            _ => {}
+        }
+        if nullable == "NO" {
+            column_type = column_type.not_null();
+        }
+
+        Self {
+            position,
+            name,
+            data_type,
+            column_type: Some(column_type),
+        }
+    }
+}
+
+impl ColumnData<String> for MySqlColumn {
+    fn position(&self) -> usize {
+        (self.position - 1) as usize
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    fn inner_kind(&self) -> Option<String> {
+        Some(self.data_type.clone())
+    }
+
+    fn column_type(&self) -> Option<ColumnType> {
+        self.column_type.clone()
+    }
+}
@@ -0,0 +1,331 @@
+use super::{
+    connector::{self, ClientOptions},
+    row::MySqlRow,
+    schema_inspector::MySqlSchemaInspector,
+    table::MySqlTable,
+};
+use crate::{indicator::Indicator, Dumper, SchemaInspector, Table};
+use anyhow::{bail, Result};
+use datanymizer_engine::{Engine, Filter, Settings, TableList};
+use log::warn;
+use mysql::{prelude::Queryable, Row};
+use std::{
+    io::{self, prelude::*},
+    process::Command,
+    time::Instant,
+};
+
+/// Number of rows in one `INSERT` statement
+const ROWS_IN_INSERT: usize = 100;
+
+pub struct MySqlDumper<W: Write + Send, I: Indicator + Send> {
+    schema_inspector: MySqlSchemaInspector,
+    engine: Engine,
+    dump_writer: W,
+    indicator: I,
+    mysqldump_location: String,
+    mysqldump_args: Vec<String>,
+    tables: Vec<MySqlTable>,
+}
+
+impl<W: 'static + Write + Send, I: 'static + Indicator + Send> MySqlDumper<W, I> {
+    pub fn new(
+        engine: Engine,
+        mysqldump_location: String,
+        dump_writer: W,
+        indicator: I,
+        mysqldump_args: Vec<String>,
+    ) -> Result<Self> {
+        Ok(Self {
+            engine,
+            dump_writer,
+            indicator,
+            mysqldump_location,
+            schema_inspector: MySqlSchemaInspector {},
+            mysqldump_args,
+            tables: Vec::new(),
+        })
+    }
+
+    fn run_mysqldump(&mut self, args: &[&str], connection: &connector::Connection) -> Result<()> {
+        let program = &self.mysqldump_location;
+        let client_options = ClientOptions::from_url(&connection.url)?;
+        let table_args = table_args(&self.engine.settings.filter, &client_options.database);
+
+        let mut command = Command::new(program);
+        command
+            .args(&client_options.args)
+            .args(&self.mysqldump_args)
+            .args(args)
+            .args(&table_args.options)
+            .arg(&client_options.database)
+            .args(&table_args.tables);
+        if let Some(password) = &client_options.password {
+            command.env("MYSQL_PWD", password);
+        }
+
+        let dump_output = command.output()?;
+        if !dump_output.status.success() {
+            io::stderr().write_all(&dump_output.stderr)?;
+            bail!("mysqldump error (program: {})", program);
+        }
+
+        self.dump_writer.write_all(&dump_output.stdout)?;
+        Ok(())
+    }
+
+    fn dump_table(
+        &mut self,
+        connection: &mut connector::Connection,
+        table: &MySqlTable,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let engine = &self.engine;
+        let cfg = engine.settings.find_table(&table.get_names());
+
+        self.indicator
+            .start_pb(table.count_of_query_to(cfg), &table.get_full_name());
+
+        let mut inserts = InsertWriter::new(&mut self.dump_writer, table.insert_prefix());
+        let mut count: u64 = 0;
+        if let Some(cfg) = cfg {
+            if let Some(transformed_query) = table.transformed_query_to(Some(cfg)) {
+                for row in connection.conn.query_iter(transformed_query)? {
+                    let row = MySqlRow::new(Row::unwrap(row?));
+                    let values =
+                        row.transform(engine, table, cfg.name.as_str())
+                            .map_err(|err| {
+                                warn!("{:#?}", err);
+                                err
+                            })?;
+                    inserts.write(values)?;
+                    self.indicator.inc_pb(1);
+                    count += 1;
+                }
+            }
+        }
+
+        if let Some(untransformed_query) = table.untransformed_query_to(cfg, count) {
+            for row in connection.conn.query_iter(untransformed_query)? {
+                inserts.write(MySqlRow::new(Row::unwrap(row?)).untransformed())?;
+                self.indicator.inc_pb(1);
+            }
+        }
+        inserts.finish()?;
+
+        let finished = started.elapsed();
+        self.indicator
+            .finish_pb(table.get_full_name().as_str(), finished);
+
+        Ok(())
+    }
+}
+
+/// Writes rows with batched `INSERT` statements
+struct InsertWriter<'w, W: Write> {
+    w: &'w mut W,
+    prefix: String,
+    rows: usize,
+}
+
+impl<'w, W: Write> InsertWriter<'w, W> {
+    fn new(w: &'w mut W, prefix: String) -> Self {
+        Self { w, prefix, rows: 0 }
+    }
+
+    fn write(&mut self, values: String) -> Result<()> {
+        if self.rows == 0 {
+            self.w.write_all(self.prefix.as_bytes())?;
+            self.w.write_all(b"\n")?;
+        } else {
+            self.w.write_all(b",\n")?;
+        }
+        self.w.write_all(values.as_bytes())?;
+
+        self.rows += 1;
+        if self.rows == ROWS_IN_INSERT {
+            self.finish()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.rows > 0 {
+            self.w.write_all(b";\n")?;
+            self.rows = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<W: 'static + Write + Send, I: 'static + Indicator + Send> Dumper for MySqlDumper<W, I> {
+    type Connection = connector::Connection;
+    type SchemaInspector = MySqlSchemaInspector;
+
+    // Stage before dumping data. It makes dump schema with any options
+    fn pre_data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        if self.engine.settings.subset.is_some() {
+            bail!("The `subset` section is supported only by PostgreSQL");
+        }
+
+        self.debug("Prepare data scheme...".into());
+        self.run_mysqldump(&["--no-data", "--skip-triggers"], connection)
+    }
+
+    // This stage makes dump data only
+    fn data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        self.write_log("Start dumping data".into())?;
+        // Tables are dumped in the order of their dependencies, but there can be cycles
+        self.dump_writer
+            .write_all(b"SET NAMES utf8mb4;\nSET FOREIGN_KEY_CHECKS = 0;\n")?;
+
+        // All tables are read from the same snapshot
+        connection
+            .conn
+            .query_drop("SET SESSION TRANSACTION ISOLATION LEVEL REPEATABLE READ")?;
+        connection
+            .conn
+            .query_drop("START TRANSACTION WITH CONSISTENT SNAPSHOT")?;
+
+        let tables = self.tables.clone();
+        let all_tables_count = tables.len();
+        for (ind, table) in tables.iter().enumerate() {
+            self.debug(format!(
+                "[{} / {}] Prepare to dump table: {}",
+                ind + 1,
+                all_tables_count,
+                table.get_full_name(),
+            ));
+
+            if self.filter_table(table.get_full_name()) {
+                self.dump_table(connection, table)?;
+            } else {
+                self.debug(format!("[Dumping: {}] --- SKIP ---", table.get_full_name()));
+            }
+        }
+
+        connection.conn.query_drop("COMMIT")?;
+        self.dump_writer
+            .write_all(b"SET FOREIGN_KEY_CHECKS = 1;\n")?;
+        self.write_log("End dumping data".into())?;
+        Ok(())
+    }
+
+    // This stage makes dump triggers
+    fn post_data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        self.debug("Finishing with triggers...".into());
+        self.run_mysqldump(
+            &["--no-data", "--no-create-info", "--skip-add-drop-table"],
+            connection,
+        )?;
+        self.dump_writer.flush()?;
+        Ok(())
+    }
+
+    fn schema_inspector(&self) -> Self::SchemaInspector {
+        self.schema_inspector.clone()
+    }
+
+    fn set_tables(&mut self, tables: Vec<<Self::SchemaInspector as SchemaInspector>::Table>) {
+        self.tables = tables;
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.engine.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.engine.settings
+    }
+
+    fn write_log(&mut self, message: String) -> Result<()> {
+        // `--` starts a comment in MySQL only if it's followed by a whitespace
+        self.dump_writer
+            .write_all(format!("\n--\n-- {}\n--\n", message).as_bytes())
+            .map_err(|e| e.into())
+    }
+
+    fn debug(&self, message: String) {
+        self.indicator.debug_msg(message.as_str());
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TableArgs {
+    /// Options before the database name
+    options: Vec<String>,
+    /// Table names after the database name
+    tables: Vec<String>,
+}
+
+fn table_args(f: &Filter, database: &str) -> TableArgs {
+    let mut args = TableArgs::default();
+
+    let list = f.schema_match_list();
+    for table in list.tables() {
+        let (db, name) = match table.split_once('.') {
+            Some((db, name)) => (db, name),
+            None => (database, table.as_str()),
+        };
+        match list {
+            TableList::Only(_) => args.tables.push(String::from(name)),
+            TableList::Except(_) => args.options.push(format!("--ignore-table={}.{}", db, name)),
+        }
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_args() {
+        let tables = vec![String::from("app.table1"), String::from("app.table2")];
+
+        assert_eq!(table_args(&Filter::default(), "app"), TableArgs::default());
+
+        let mut filter = Filter::new(
+            TableList::Except(vec![String::from("app.table1")]),
+            TableList::default(),
+        );
+        filter.load_tables(tables.clone());
+        assert_eq!(
+            table_args(&filter, "app"),
+            TableArgs {
+                options: vec![String::from("--ignore-table=app.table1")],
+                tables: vec![],
+            }
+        );
+
+        let mut filter = Filter::new(
+            TableList::Only(vec![String::from("app.table1"), String::from("*.table2")]),
+            TableList::default(),
+        );
+        filter.load_tables(tables);
+        assert_eq!(
+            table_args(&filter, "app"),
+            TableArgs {
+                options: vec![],
+                tables: vec![String::from("table1"), String::from("table2")],
+            }
+        );
+    }
+
+    #[test]
+    fn insert_writer() {
+        let mut w = vec![];
+        let mut inserts = InsertWriter::new(&mut w, String::from("INSERT INTO `t` (`a`) VALUES"));
+        for i in 0..ROWS_IN_INSERT + 1 {
+            inserts.write(format!("({})", i)).unwrap();
+        }
+        inserts.finish().unwrap();
+        inserts.finish().unwrap();
+
+        let dump = String::from_utf8(w).unwrap();
+        assert_eq!(dump.matches("INSERT INTO").count(), 2);
+        assert!(dump.starts_with("INSERT INTO `t` (`a`) VALUES\n(0),\n(1),\n"));
+        assert!(dump.ends_with("(99);\nINSERT INTO `t` (`a`) VALUES\n(100);\n"));
+    }
+}
@@ -0,0 +1,52 @@
+/// String literal for MySQL (like `mysqldump` escapes strings)
+pub fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('\'');
+    for c in s.chars() {
+        match c {
+            '\0' => quoted.push_str(r#"\0"#),
+            '\n' => quoted.push_str(r#"\n"#),
+            '\r' => quoted.push_str(r#"\r"#),
+            '\x1A' => quoted.push_str(r#"\Z"#),
+            '\'' => quoted.push_str(r#"\'"#),
+            '"' => quoted.push_str(r#"\""#),
+            '\\' => quoted.push_str(r#"\\"#),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+/// Hexadecimal literal for binary values
+pub fn hex(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return String::from("''");
+    }
+
+    let mut literal = String::with_capacity(bytes.len() * 2 + 2);
+    literal.push_str("0x");
+    for b in bytes {
+        literal.push_str(&format!("{:02X}", b));
+    }
+    literal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_strings() {
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("O'Neil"), r#"'O\'Neil'"#);
+        assert_eq!(quote("a\\b\n\"c\"\0\r\x1A"), r#"'a\\b\n\"c\"\0\r\Z'"#);
+        assert_eq!(quote("tab\tи"), "'tab\tи'");
+    }
+
+    #[test]
+    fn hex_bytes() {
+        assert_eq!(hex(&[]), "''");
+        assert_eq!(hex(&[0, 15, 255]), "0x000FFF");
+    }
+}
@@ -0,0 +1,41 @@
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MySqlForeignKey {
+    // Source
+    pub table_schema: String,
+    pub table_name: String,
+    pub constraint_name: String,
+
+    pub column_name: String,
+
+    // Reference
+    pub foreign_table_schema: String,
+    pub foreign_table_name: String,
+    pub foreign_column_name: String,
+}
+
+/// Columns of the foreign key query
+pub type ForeignKeyRow = (String, String, String, String, String, String, String);
+
+impl From<ForeignKeyRow> for MySqlForeignKey {
+    fn from(row: ForeignKeyRow) -> Self {
+        let (
+            table_schema,
+            table_name,
+            constraint_name,
+            column_name,
+            foreign_table_schema,
+            foreign_table_name,
+            foreign_column_name,
+        ) = row;
+
+        Self {
+            table_schema,
+            table_name,
+            constraint_name,
+            column_name,
+            foreign_table_schema,
+            foreign_table_name,
+            foreign_column_name,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+//! The MySQL / MariaDB backend: the schema is dumped with `mysqldump`, the data - with batched `INSERT` statements.
+
+pub mod column;
+pub mod connector;
+pub mod dumper;
+pub mod foreign_key;
+pub mod row;
+pub mod schema_inspector;
+pub mod table;
+
+mod escaper;
@@ -0,0 +1,150 @@
+use super::escaper;
+use crate::Table;
+use anyhow::Result;
+use datanymizer_engine::Engine;
+use mysql::Value;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct MySqlRow {
+    values: Vec<Value>,
+}
+
+impl MySqlRow {
+    pub fn new(values: Vec<Value>) -> Self {
+        Self { values }
+    }
+
+    /// Applies the transform engine to every column in the row.
+    /// Returns the row for the `INSERT` statement, e.g. `(1, 'value')`.
+    pub fn transform<T: Table<String>>(
+        &self,
+        engine: &Engine,
+        table: &T,
+        cfg_tbl_name: &str,
+    ) -> Result<String> {
+        let texts: Vec<Option<Cow<str>>> = self.values.iter().map(text).collect();
+        let values: Vec<Option<&str>> = texts.iter().map(|t| t.as_deref()).collect();
+        let transformed_values = engine.process_row(
+            String::from(cfg_tbl_name),
+            table.get_column_indexes(),
+            &values,
+        )?;
+
+        let literals: Vec<String> = self
+            .values
+            .iter()
+            .zip(transformed_values)
+            .map(|(value, transformed)| match transformed {
+                // Untouched values keep their types (e.g. binary)
+                Some(Cow::Borrowed(_)) => literal(value),
+                Some(Cow::Owned(s)) => escaper::quote(&s),
+                None => String::from("NULL"),
+            })
+            .collect();
+        Ok(format!("({})", literals.join(", ")))
+    }
+
+    /// The row for the `INSERT` statement without transformations
+    pub fn untransformed(&self) -> String {
+        let literals: Vec<String> = self.values.iter().map(literal).collect();
+        format!("({})", literals.join(", "))
+    }
+}
+
+/// The value for transformers
+fn text(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::NULL => None,
+        Value::Bytes(bytes) => Some(String::from_utf8_lossy(bytes)),
+        value => Some(Cow::Owned(value.as_sql(false))),
+    }
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Bytes(bytes) => match std::str::from_utf8(bytes) {
+            Ok(s) => escaper::quote(s),
+            Err(_) => escaper::hex(bytes),
+        },
+        value => value.as_sql(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mysql::{column::MySqlColumn, table::MySqlTable};
+    use datanymizer_engine::Settings;
+
+    fn table() -> MySqlTable {
+        let mut table = MySqlTable::new(String::from("users"), String::from("app"));
+        let columns = ["id", "name", "comment", "avatar"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| MySqlColumn {
+                position: i as u32 + 1,
+                name: String::from(*name),
+                data_type: String::new(),
+                column_type: None,
+            })
+            .collect();
+        table.set_columns(columns);
+        table
+    }
+
+    fn row() -> MySqlRow {
+        MySqlRow::new(vec![
+            Value::Bytes(b"1".to_vec()),
+            Value::Bytes(b"O'Neil".to_vec()),
+            Value::NULL,
+            Value::Bytes(vec![0xFF, 0x00]),
+        ])
+    }
+
+    #[test]
+    fn transform() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ _0 }}\nJr."
+                comment:
+                  template:
+                    format: "{{ prev.name }}"
+                  on_null: transform
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+
+        assert_eq!(
+            row().transform(&engine, &table(), "users").unwrap(),
+            r#"('1', 'O\'Neil\nJr.', 'O\'Neil', 0xFF00)"#
+        );
+    }
+
+    #[test]
+    fn transform_to_null() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  set_null: ~
+                comment:
+                  first_name: {}
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+
+        assert_eq!(
+            row().transform(&engine, &table(), "users").unwrap(),
+            "('1', NULL, NULL, 0xFF00)"
+        );
+    }
+
+    #[test]
+    fn untransformed() {
+        assert_eq!(row().untransformed(), r#"('1', 'O\'Neil', NULL, 0xFF00)"#);
+    }
+}
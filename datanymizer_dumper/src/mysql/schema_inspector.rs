@@ -0,0 +1,97 @@
+use super::{
+    column::{ColumnRow, MySqlColumn},
+    connector,
+    foreign_key::{ForeignKeyRow, MySqlForeignKey},
+    table::MySqlTable,
+};
+use crate::SchemaInspector;
+use anyhow::Result;
+use mysql::prelude::Queryable;
+
+const TABLES_QUERY: &str = "SELECT TABLE_SCHEMA, TABLE_NAME
+    FROM information_schema.TABLES
+    WHERE TABLE_SCHEMA = DATABASE() AND TABLE_TYPE = 'BASE TABLE'";
+
+const TABLE_FK_QUERY: &str = "SELECT TABLE_SCHEMA,
+        TABLE_NAME,
+        CONSTRAINT_NAME,
+        COLUMN_NAME,
+        REFERENCED_TABLE_SCHEMA,
+        REFERENCED_TABLE_NAME,
+        REFERENCED_COLUMN_NAME
+    FROM information_schema.KEY_COLUMN_USAGE
+    WHERE REFERENCED_TABLE_NAME IS NOT NULL AND TABLE_SCHEMA = ? AND TABLE_NAME = ?";
+
+// Generated columns can't be inserted
+const TABLE_COLUMNS_QUERY: &str = "SELECT COLUMN_NAME, ORDINAL_POSITION, DATA_TYPE, COLUMN_TYPE,
+        CHARACTER_MAXIMUM_LENGTH, NUMERIC_PRECISION, NUMERIC_SCALE, IS_NULLABLE
+    FROM information_schema.COLUMNS
+    WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ? AND EXTRA NOT LIKE '%GENERATED%'
+    ORDER BY ORDINAL_POSITION ASC";
+
+// The number of rows is an estimation for InnoDB tables
+const TABLE_SIZE_QUERY: &str = "SELECT CAST(COALESCE(TABLE_ROWS, 0) AS SIGNED)
+    FROM information_schema.TABLES
+    WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?";
+
+#[derive(Clone)]
+pub struct MySqlSchemaInspector;
+
+impl SchemaInspector for MySqlSchemaInspector {
+    type Type = String;
+    type Connection = connector::Connection;
+    type Table = MySqlTable;
+    type Column = MySqlColumn;
+    type ForeignKey = MySqlForeignKey;
+
+    // Get all tables in the database
+    fn get_tables(&self, connection: &mut Self::Connection) -> Result<Vec<Self::Table>> {
+        let names: Vec<(String, String)> = connection.conn.query(TABLES_QUERY)?;
+
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let mut table = MySqlTable::from(name);
+            table.set_columns(self.get_columns(connection, &table)?);
+            table.set_foreign_keys(self.get_foreign_keys(connection, &table)?);
+            table.size = self.get_table_size(connection, &table)?;
+            tables.push(table);
+        }
+        Ok(tables)
+    }
+
+    /// Get table size
+    fn get_table_size(
+        &self,
+        connection: &mut Self::Connection,
+        table: &Self::Table,
+    ) -> Result<i64> {
+        let size: Option<i64> = connection
+            .conn
+            .exec_first(TABLE_SIZE_QUERY, (&table.schemaname, &table.tablename))?;
+        Ok(size.unwrap_or_default())
+    }
+
+    /// Get foreign keys for table
+    fn get_foreign_keys(
+        &self,
+        connection: &mut Self::Connection,
+        table: &Self::Table,
+    ) -> Result<Vec<Self::ForeignKey>> {
+        let rows: Vec<ForeignKeyRow> = connection
+            .conn
+            .exec(TABLE_FK_QUERY, (&table.schemaname, &table.tablename))?;
+        Ok(rows.into_iter().map(MySqlForeignKey::from).collect())
+    }
+
+    /// Get columns for table
+    fn get_columns(
+        &self,
+        connection: &mut Self::Connection,
+        table: &Self::Table,
+    ) -> Result<Vec<Self::Column>> {
+        let rows: Vec<ColumnRow> = connection
+            .conn
+            .exec(TABLE_COLUMNS_QUERY, (&table.schemaname, &table.tablename))?;
+        Ok(rows.into_iter().map(MySqlColumn::from).collect())
+    }
+}
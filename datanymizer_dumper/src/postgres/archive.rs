@@ -0,0 +1,872 @@
+use super::{sequence::PgSequence, table::PgTable};
+use anyhow::{anyhow, bail, Result};
+use flate2::{
+    write::{GzEncoder, ZlibEncoder},
+    Compression,
+};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, BufWriter, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+use tempfile::NamedTempFile;
+
+// See pg_backup_archiver.h and pg_backup_archiver.c in the PostgreSQL sources
+const MAGIC: &[u8] = b"PGDMP";
+/// The first supported version (PostgreSQL 12, adds table access methods)
+const K_VERS_1_14: Version = (1, 14, 0);
+/// Adds the compression algorithm to the header (PostgreSQL 16)
+const K_VERS_1_15: Version = (1, 15, 0);
+/// Adds relkind to TOC entries (PostgreSQL 17)
+const K_VERS_1_16: Version = (1, 16, 0);
+const K_VERS_MAX: Version = (1, 16, u8::MAX);
+
+const SECTION_DATA: i32 = 3;
+const SECTION_POST_DATA: i32 = 4;
+
+const K_OFFSET_POS_NOT_SET: u8 = 1;
+const K_OFFSET_POS_SET: u8 = 2;
+const K_OFFSET_NO_DATA: u8 = 3;
+
+const BLK_DATA: u8 = 1;
+
+const Z_DEFAULT_COMPRESSION: i32 = -1;
+const PG_COMPRESSION_GZIP: u8 = 1;
+
+/// The archive format end of table data (the same as `pg_dump` writes)
+pub(super) const END_OF_DATA: &[u8] = b"\\.\n\n\n";
+
+type Version = (u8, u8, u8);
+
+/// `pg_dump` archive formats (can be restored with `pg_restore`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// A single file (`pg_dump -Fc`)
+    Custom,
+    /// A directory with the TOC file and a file for each table (`pg_dump -Fd`)
+    Directory,
+}
+
+impl ArchiveFormat {
+    fn code(self) -> u8 {
+        match self {
+            Self::Custom => 1,
+            Self::Directory => 3,
+        }
+    }
+}
+
+/// Sizes of integers and offsets in the archive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Layout {
+    int_size: usize,
+    off_size: usize,
+}
+
+impl Layout {
+    fn write_int<W: Write>(&self, w: &mut W, value: i32) -> io::Result<()> {
+        let sign = u8::from(value < 0);
+        let mut abs = value.unsigned_abs();
+        w.write_all(&[sign])?;
+        for _ in 0..self.int_size {
+            w.write_all(&[(abs & 0xFF) as u8])?;
+            abs >>= 8;
+        }
+        Ok(())
+    }
+
+    fn write_str<W: Write>(&self, w: &mut W, value: Option<&str>) -> io::Result<()> {
+        match value {
+            Some(s) => {
+                self.write_int(w, s.len() as i32)?;
+                w.write_all(s.as_bytes())
+            }
+            None => self.write_int(w, -1),
+        }
+    }
+
+    fn write_offset<W: Write>(&self, w: &mut W, state: u8, offset: u64) -> io::Result<()> {
+        w.write_all(&[state])?;
+        for i in 0..self.off_size {
+            w.write_all(&[offset.checked_shr(8 * i as u32).unwrap_or(0) as u8])?;
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    layout: Layout,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            bail!("Unexpected end of the pg_dump archive");
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn int(&mut self) -> Result<i32> {
+        let sign = self.byte()?;
+        let mut value: i64 = 0;
+        for (i, b) in self.bytes(self.layout.int_size)?.iter().enumerate() {
+            if *b != 0 {
+                if i >= 4 {
+                    bail!("Too large integer in the pg_dump archive");
+                }
+                value |= i64::from(*b) << (8 * i);
+            }
+        }
+        if sign != 0 {
+            value = -value;
+        }
+        i32::try_from(value).map_err(|_| anyhow!("Too large integer in the pg_dump archive"))
+    }
+
+    fn str(&mut self) -> Result<Option<String>> {
+        let len = self.int()?;
+        if len < 0 {
+            return Ok(None);
+        }
+        let bytes = self.bytes(len as usize)?;
+        Ok(Some(String::from_utf8(bytes.to_vec())?))
+    }
+
+    fn offset(&mut self) -> Result<(u8, u64)> {
+        let state = self.byte()?;
+        let mut offset = 0;
+        for (i, b) in self.bytes(self.layout.off_size)?.iter().enumerate() {
+            offset |= u64::from(*b).checked_shl(8 * i as u32).unwrap_or(0);
+        }
+        Ok((state, offset))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Header {
+    version: Version,
+    layout: Layout,
+    /// sec, min, hour, mday, mon, year, isdst
+    timestamp: [i32; 7],
+    dbname: Option<String>,
+    remote_version: Option<String>,
+    dumper_version: Option<String>,
+}
+
+impl Header {
+    fn read(data: &[u8]) -> Result<(Self, Reader<'_>)> {
+        if !data.starts_with(MAGIC) {
+            bail!("pg_dump output is not a custom-format archive");
+        }
+        let mut r = Reader {
+            data: &data[MAGIC.len()..],
+            layout: Layout {
+                int_size: 4,
+                off_size: 8,
+            },
+        };
+
+        let version = (r.byte()?, r.byte()?, r.byte()?);
+        if version < K_VERS_1_14 || version > K_VERS_MAX {
+            bail!(
+                "Unsupported pg_dump archive version {}.{}.{}",
+                version.0,
+                version.1,
+                version.2
+            );
+        }
+        r.layout = Layout {
+            int_size: r.byte()? as usize,
+            off_size: r.byte()? as usize,
+        };
+        if r.byte()? != ArchiveFormat::Custom.code() {
+            bail!("pg_dump output is not a custom-format archive");
+        }
+        // The schema has no data, so the compression doesn't matter
+        if version >= K_VERS_1_15 {
+            r.byte()?;
+        } else {
+            r.int()?;
+        }
+
+        let mut timestamp = [0; 7];
+        for t in timestamp.iter_mut() {
+            *t = r.int()?;
+        }
+
+        let header = Self {
+            version,
+            layout: r.layout,
+            timestamp,
+            dbname: r.str()?,
+            remote_version: r.str()?,
+            dumper_version: r.str()?,
+        };
+        Ok((header, r))
+    }
+
+    /// Writes the header. Table data is always compressed with gzip.
+    fn write<W: Write>(&self, w: &mut W, format: ArchiveFormat) -> io::Result<()> {
+        let l = &self.layout;
+
+        w.write_all(MAGIC)?;
+        w.write_all(&[self.version.0, self.version.1, self.version.2])?;
+        w.write_all(&[l.int_size as u8, l.off_size as u8, format.code()])?;
+        if self.version >= K_VERS_1_15 {
+            w.write_all(&[PG_COMPRESSION_GZIP])?;
+        } else {
+            l.write_int(w, Z_DEFAULT_COMPRESSION)?;
+        }
+        for t in self.timestamp {
+            l.write_int(w, t)?;
+        }
+        l.write_str(w, self.dbname.as_deref())?;
+        l.write_str(w, self.remote_version.as_deref())?;
+        l.write_str(w, self.dumper_version.as_deref())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct TocEntry {
+    dump_id: i32,
+    has_data: bool,
+    table_oid: Option<String>,
+    oid: Option<String>,
+    tag: Option<String>,
+    desc: Option<String>,
+    section: i32,
+    defn: Option<String>,
+    drop_stmt: Option<String>,
+    copy_stmt: Option<String>,
+    namespace: Option<String>,
+    tablespace: Option<String>,
+    tableam: Option<String>,
+    relkind: i32,
+    owner: Option<String>,
+    deps: Vec<i32>,
+}
+
+impl TocEntry {
+    /// Reads an entry (without the format-specific data pointer)
+    fn read(r: &mut Reader, version: Version) -> Result<Self> {
+        let mut entry = Self {
+            dump_id: r.int()?,
+            has_data: r.int()? != 0,
+            table_oid: r.str()?,
+            oid: r.str()?,
+            tag: r.str()?,
+            desc: r.str()?,
+            section: r.int()?,
+            defn: r.str()?,
+            drop_stmt: r.str()?,
+            copy_stmt: r.str()?,
+            namespace: r.str()?,
+            tablespace: r.str()?,
+            tableam: r.str()?,
+            ..Self::default()
+        };
+        if version >= K_VERS_1_16 {
+            entry.relkind = r.int()?;
+        }
+        entry.owner = r.str()?;
+        // WITH OIDS (always false)
+        r.str()?;
+        while let Some(dep) = r.str()? {
+            entry.deps.push(dep.parse()?);
+        }
+
+        Ok(entry)
+    }
+
+    fn write<W: Write>(
+        &self,
+        w: &mut W,
+        header: &Header,
+        format: ArchiveFormat,
+        offsets: &HashMap<i32, u64>,
+    ) -> io::Result<()> {
+        let l = &header.layout;
+
+        l.write_int(w, self.dump_id)?;
+        l.write_int(w, i32::from(self.has_data))?;
+        l.write_str(w, self.table_oid.as_deref())?;
+        l.write_str(w, self.oid.as_deref())?;
+        l.write_str(w, self.tag.as_deref())?;
+        l.write_str(w, self.desc.as_deref())?;
+        l.write_int(w, self.section)?;
+        l.write_str(w, self.defn.as_deref())?;
+        l.write_str(w, self.drop_stmt.as_deref())?;
+        l.write_str(w, self.copy_stmt.as_deref())?;
+        l.write_str(w, self.namespace.as_deref())?;
+        l.write_str(w, self.tablespace.as_deref())?;
+        l.write_str(w, self.tableam.as_deref())?;
+        if header.version >= K_VERS_1_16 {
+            l.write_int(w, self.relkind)?;
+        }
+        l.write_str(w, self.owner.as_deref())?;
+        l.write_str(w, Some("false"))?;
+        for dep in &self.deps {
+            l.write_str(w, Some(dep.to_string().as_str()))?;
+        }
+        l.write_str(w, None)?;
+
+        match format {
+            ArchiveFormat::Custom => match (self.has_data, offsets.get(&self.dump_id)) {
+                (true, Some(offset)) => l.write_offset(w, K_OFFSET_POS_SET, *offset),
+                (true, None) => l.write_offset(w, K_OFFSET_POS_NOT_SET, 0),
+                (false, _) => l.write_offset(w, K_OFFSET_NO_DATA, 0),
+            },
+            ArchiveFormat::Directory => {
+                let filename = if self.has_data {
+                    data_file_name(self.dump_id)
+                } else {
+                    String::new()
+                };
+                l.write_str(w, Some(filename.as_str()))
+            }
+        }
+    }
+
+    fn is(&self, desc: &str, namespace: &str, tag: &str) -> bool {
+        self.desc.as_deref() == Some(desc)
+            && self.namespace.as_deref() == Some(namespace)
+            && self.tag.as_deref() == Some(tag)
+    }
+}
+
+fn data_file_name(dump_id: i32) -> String {
+    format!("{}.dat", dump_id)
+}
+
+/// Dumped data of a table
+pub(super) struct TableData {
+    pub file: NamedTempFile,
+    /// Sequences of the table with their last values
+    pub sequences: Vec<(PgSequence, i64)>,
+}
+
+/// Creates compressed files for table data
+#[derive(Clone, Debug)]
+pub(super) struct DataFiles {
+    format: ArchiveFormat,
+    dir: Option<PathBuf>,
+    layout: Layout,
+}
+
+impl DataFiles {
+    pub fn create(&self) -> Result<DataWriter> {
+        let file = match &self.dir {
+            Some(dir) => NamedTempFile::new_in(dir)?,
+            None => NamedTempFile::new()?,
+        };
+        let w = BufWriter::new(file);
+
+        Ok(match self.format {
+            ArchiveFormat::Custom => DataWriter::Chunked(ZlibEncoder::new(
+                ChunkWriter {
+                    w,
+                    layout: self.layout,
+                },
+                Compression::default(),
+            )),
+            ArchiveFormat::Directory => DataWriter::Gzip(GzEncoder::new(w, Compression::default())),
+        })
+    }
+}
+
+/// Compresses table data: as a gzip file (the directory format)
+/// or as a zlib stream split into chunks (a data block of the custom format)
+pub(super) enum DataWriter {
+    Gzip(GzEncoder<BufWriter<NamedTempFile>>),
+    Chunked(ZlibEncoder<ChunkWriter<BufWriter<NamedTempFile>>>),
+}
+
+impl DataWriter {
+    pub fn finish(self) -> Result<NamedTempFile> {
+        let w = match self {
+            Self::Gzip(encoder) => encoder.finish()?,
+            Self::Chunked(encoder) => encoder.finish()?.finish()?,
+        };
+        Ok(w.into_inner().map_err(|e| e.into_error())?)
+    }
+}
+
+impl Write for DataWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Chunked(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Chunked(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Writes every chunk with its length. The zero length marks the end of data.
+pub(super) struct ChunkWriter<W: Write> {
+    w: W,
+    layout: Layout,
+}
+
+impl<W: Write> ChunkWriter<W> {
+    fn finish(mut self) -> io::Result<W> {
+        self.layout.write_int(&mut self.w, 0)?;
+        Ok(self.w)
+    }
+}
+
+impl<W: Write> Write for ChunkWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() {
+            self.layout.write_int(&mut self.w, buf.len() as i32)?;
+            self.w.write_all(buf)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.w.flush()
+    }
+}
+
+/// A `pg_dump` archive.
+/// The schema is taken from the `pg_dump` custom-format output, and entries for the table data are added to it.
+pub(super) struct Archive {
+    format: ArchiveFormat,
+    path: PathBuf,
+    header: Header,
+    entries: Vec<TocEntry>,
+    /// Data entries are inserted here (before post-data entries)
+    data_position: usize,
+    next_dump_id: i32,
+    /// Data blocks of the custom format
+    blocks: Vec<(i32, NamedTempFile)>,
+}
+
+impl Archive {
+    /// `schema` is the output of `pg_dump --format=custom --schema-only`
+    pub fn new(format: ArchiveFormat, path: PathBuf, schema: &[u8]) -> Result<Self> {
+        let (header, mut r) = Header::read(schema)?;
+        let count = r.int()?;
+        let mut entries = Vec::with_capacity(count.max(0) as usize);
+        for _ in 0..count {
+            let entry = TocEntry::read(&mut r, header.version)?;
+            let (state, _) = r.offset()?;
+            if entry.has_data || state != K_OFFSET_NO_DATA {
+                bail!("pg_dump output contains data");
+            }
+            entries.push(entry);
+        }
+
+        if format == ArchiveFormat::Directory {
+            fs::create_dir_all(&path)?;
+            if fs::read_dir(&path)?.next().is_some() {
+                bail!("Directory {} is not empty", path.display());
+            }
+        }
+
+        Ok(Self {
+            format,
+            path,
+            data_position: entries
+                .iter()
+                .position(|e| e.section == SECTION_POST_DATA)
+                .unwrap_or(entries.len()),
+            next_dump_id: entries.iter().map(|e| e.dump_id).max().unwrap_or(0) + 1,
+            header,
+            entries,
+            blocks: vec![],
+        })
+    }
+
+    pub fn data_files(&self) -> DataFiles {
+        DataFiles {
+            format: self.format,
+            dir: match self.format {
+                ArchiveFormat::Custom => None,
+                ArchiveFormat::Directory => Some(self.path.clone()),
+            },
+            layout: self.header.layout,
+        }
+    }
+
+    /// Adds the table data (`TABLE DATA` and `SEQUENCE SET` entries)
+    pub fn add_table(&mut self, table: &PgTable, data: TableData) -> Result<()> {
+        let dump_id = self.next_dump_id;
+        self.next_dump_id += 1;
+
+        let mut entry = TocEntry {
+            dump_id,
+            has_data: true,
+            table_oid: Some(String::from("0")),
+            oid: Some(String::from("0")),
+            tag: Some(table.tablename.clone()),
+            desc: Some(String::from("TABLE DATA")),
+            section: SECTION_DATA,
+            defn: Some(String::new()),
+            drop_stmt: Some(String::new()),
+            copy_stmt: Some(format!("{}\n", table.query_from())),
+            namespace: Some(table.schemaname.clone()),
+            ..TocEntry::default()
+        };
+        if let Some(t) = self.find_entry("TABLE", &table.schemaname, &table.tablename) {
+            entry.oid = t.oid.clone();
+            entry.owner = t.owner.clone();
+            entry.deps = vec![t.dump_id];
+        }
+        self.insert_entry(entry);
+
+        match self.format {
+            ArchiveFormat::Custom => self.blocks.push((dump_id, data.file)),
+            ArchiveFormat::Directory => {
+                data.file
+                    .persist(self.path.join(format!("{}.gz", data_file_name(dump_id))))?;
+            }
+        }
+
+        for (seq, last_value) in data.sequences {
+            self.add_sequence(&seq, last_value);
+        }
+
+        Ok(())
+    }
+
+    fn add_sequence(&mut self, seq: &PgSequence, last_value: i64) {
+        let (namespace, tag) = seq.schema_and_name();
+        let mut entry = TocEntry {
+            dump_id: self.next_dump_id,
+            table_oid: Some(String::from("0")),
+            oid: Some(String::from("0")),
+            desc: Some(String::from("SEQUENCE SET")),
+            section: SECTION_DATA,
+            defn: Some(format!("{}\n", seq.setval_query(last_value))),
+            drop_stmt: Some(String::new()),
+            ..TocEntry::default()
+        };
+        self.next_dump_id += 1;
+        if let Some(s) = self.find_entry("SEQUENCE", &namespace, &tag) {
+            entry.owner = s.owner.clone();
+            entry.deps = vec![s.dump_id];
+        }
+        entry.namespace = Some(namespace);
+        entry.tag = Some(tag);
+        self.insert_entry(entry);
+    }
+
+    fn find_entry(&self, desc: &str, namespace: &str, tag: &str) -> Option<&TocEntry> {
+        self.entries.iter().find(|e| e.is(desc, namespace, tag))
+    }
+
+    fn insert_entry(&mut self, entry: TocEntry) {
+        self.entries.insert(self.data_position, entry);
+        self.data_position += 1;
+    }
+
+    /// Writes the TOC (and data blocks of the custom format)
+    pub fn finish(self) -> Result<()> {
+        match self.format {
+            ArchiveFormat::Custom => {
+                let mut w = BufWriter::new(File::create(&self.path)?);
+                self.write_custom(&mut w)?;
+                w.flush()?;
+            }
+            ArchiveFormat::Directory => {
+                let mut w = BufWriter::new(File::create(self.path.join("toc.dat"))?);
+                self.header.write(&mut w, self.format)?;
+                self.write_toc(&mut w, &HashMap::new())?;
+                w.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the TOC without offsets, then data blocks, then rewrites the TOC with offsets
+    /// (like `pg_dump` does)
+    fn write_custom<W: Write + Seek>(&self, w: &mut W) -> Result<()> {
+        self.header.write(w, self.format)?;
+        let toc_position = w.stream_position()?;
+        self.write_toc(w, &HashMap::new())?;
+
+        let mut offsets = HashMap::with_capacity(self.blocks.len());
+        for (dump_id, file) in &self.blocks {
+            offsets.insert(*dump_id, w.stream_position()?);
+            w.write_all(&[BLK_DATA])?;
+            self.header.layout.write_int(w, *dump_id)?;
+            io::copy(&mut file.reopen()?, w)?;
+        }
+
+        w.seek(SeekFrom::Start(toc_position))?;
+        self.write_toc(w, &offsets)
+    }
+
+    fn write_toc<W: Write>(&self, w: &mut W, offsets: &HashMap<i32, u64>) -> Result<()> {
+        self.header.layout.write_int(w, self.entries.len() as i32)?;
+        for entry in &self.entries {
+            entry.write(w, &self.header, self.format, offsets)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::{GzDecoder, ZlibDecoder};
+    use std::io::{Cursor, Read};
+
+    const LAYOUT: Layout = Layout {
+        int_size: 4,
+        off_size: 8,
+    };
+
+    fn header(version: Version) -> Header {
+        Header {
+            version,
+            layout: LAYOUT,
+            timestamp: [1, 2, 3, 4, 5, 124, 0],
+            dbname: Some(String::from("db")),
+            remote_version: Some(String::from("15.1")),
+            dumper_version: Some(String::from("15.1")),
+        }
+    }
+
+    fn entry(dump_id: i32, desc: &str, section: i32, namespace: &str, tag: &str) -> TocEntry {
+        TocEntry {
+            dump_id,
+            table_oid: Some(String::from("1259")),
+            oid: Some(format!("{}00", dump_id)),
+            tag: Some(String::from(tag)),
+            desc: Some(String::from(desc)),
+            section,
+            defn: Some(format!("CREATE {};\n", tag)),
+            drop_stmt: Some(String::new()),
+            namespace: Some(String::from(namespace)),
+            owner: Some(String::from("postgres")),
+            deps: vec![dump_id - 1],
+            ..TocEntry::default()
+        }
+    }
+
+    fn schema(version: Version, entries: &[TocEntry]) -> Vec<u8> {
+        let header = header(version);
+        let mut schema = vec![];
+        header.write(&mut schema, ArchiveFormat::Custom).unwrap();
+        LAYOUT.write_int(&mut schema, entries.len() as i32).unwrap();
+        for e in entries {
+            e.write(&mut schema, &header, ArchiveFormat::Custom, &HashMap::new())
+                .unwrap();
+        }
+        schema
+    }
+
+    fn entries() -> Vec<TocEntry> {
+        vec![
+            entry(1, "SEQUENCE", 2, "public", "users_id_seq"),
+            entry(2, "TABLE", 2, "public", "users"),
+            entry(3, "INDEX", 4, "public", "users_name_idx"),
+        ]
+    }
+
+    fn table() -> PgTable {
+        let mut table = PgTable::new(String::from("users"), String::from("public"));
+        table.set_sequences(vec![PgSequence {
+            full_name: String::from("public.users_id_seq"),
+        }]);
+        table
+    }
+
+    fn table_data(archive: &Archive, rows: &[u8]) -> TableData {
+        let mut w = archive.data_files().create().unwrap();
+        w.write_all(rows).unwrap();
+        TableData {
+            file: w.finish().unwrap(),
+            sequences: vec![(table().sequences[0].clone(), 42)],
+        }
+    }
+
+    #[test]
+    fn ints() {
+        for value in [0, 1, -1, 255, 256, i32::MAX, -i32::MAX] {
+            let mut buf = vec![];
+            LAYOUT.write_int(&mut buf, value).unwrap();
+            assert_eq!(buf.len(), 5);
+
+            let mut r = Reader {
+                data: &buf,
+                layout: LAYOUT,
+            };
+            assert_eq!(r.int().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn read_schema() {
+        for version in [K_VERS_1_14, K_VERS_1_15, K_VERS_1_16] {
+            let schema = schema(version, &entries());
+            let archive =
+                Archive::new(ArchiveFormat::Custom, PathBuf::from("dump"), &schema).unwrap();
+
+            assert_eq!(archive.header, header(version));
+            assert_eq!(archive.entries, entries());
+            assert_eq!(archive.data_position, 2);
+            assert_eq!(archive.next_dump_id, 4);
+        }
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let schema = schema((1, 13, 0), &entries());
+        let result = Archive::new(ArchiveFormat::Custom, PathBuf::from("dump"), &schema);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "Unsupported pg_dump archive version 1.13.0"
+        );
+    }
+
+    #[test]
+    fn add_table() {
+        let schema = schema(K_VERS_1_14, &entries());
+        let mut archive =
+            Archive::new(ArchiveFormat::Custom, PathBuf::from("dump"), &schema).unwrap();
+        let data = table_data(&archive, b"1\tJohn\n");
+        archive.add_table(&table(), data).unwrap();
+
+        let descs: Vec<_> = archive
+            .entries
+            .iter()
+            .map(|e| (e.dump_id, e.desc.clone().unwrap()))
+            .collect();
+        assert_eq!(
+            descs,
+            vec![
+                (1, String::from("SEQUENCE")),
+                (2, String::from("TABLE")),
+                (4, String::from("TABLE DATA")),
+                (5, String::from("SEQUENCE SET")),
+                (3, String::from("INDEX")),
+            ]
+        );
+
+        let table_data = &archive.entries[2];
+        assert!(table_data.has_data);
+        assert_eq!(table_data.oid, Some(String::from("200")));
+        assert_eq!(table_data.section, SECTION_DATA);
+        assert_eq!(table_data.deps, vec![2]);
+        assert_eq!(
+            table_data.copy_stmt,
+            Some(String::from("COPY \"public\".\"users\" FROM STDIN;\n"))
+        );
+
+        let sequence_set = &archive.entries[3];
+        assert!(!sequence_set.has_data);
+        assert_eq!(sequence_set.tag, Some(String::from("users_id_seq")));
+        assert_eq!(sequence_set.deps, vec![1]);
+        assert_eq!(
+            sequence_set.defn,
+            Some(String::from(
+                "SELECT pg_catalog.setval('public.users_id_seq', 42, true);\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn custom() {
+        let schema = schema(K_VERS_1_14, &entries());
+        let mut archive =
+            Archive::new(ArchiveFormat::Custom, PathBuf::from("dump"), &schema).unwrap();
+        let data = table_data(&archive, b"1\tJohn\n");
+        archive.add_table(&table(), data).unwrap();
+
+        let mut out = Cursor::new(vec![]);
+        archive.write_custom(&mut out).unwrap();
+        let out = out.into_inner();
+
+        let (_, mut r) = Header::read(&out).unwrap();
+        assert_eq!(r.int().unwrap(), 5);
+        let mut offset = None;
+        for _ in 0..5 {
+            let entry = TocEntry::read(&mut r, K_VERS_1_14).unwrap();
+            let (state, pos) = r.offset().unwrap();
+            if entry.has_data {
+                assert_eq!(entry.dump_id, 4);
+                assert_eq!(state, K_OFFSET_POS_SET);
+                offset = Some(pos as usize);
+            } else {
+                assert_eq!(state, K_OFFSET_NO_DATA);
+            }
+        }
+
+        let mut r = Reader {
+            data: &out[offset.unwrap()..],
+            layout: LAYOUT,
+        };
+        assert_eq!(r.byte().unwrap(), BLK_DATA);
+        assert_eq!(r.int().unwrap(), 4);
+        let mut compressed = vec![];
+        loop {
+            let len = r.int().unwrap();
+            if len == 0 {
+                break;
+            }
+            compressed.extend_from_slice(r.bytes(len as usize).unwrap());
+        }
+        assert!(r.data.is_empty());
+
+        let mut rows = String::new();
+        ZlibDecoder::new(compressed.as_slice())
+            .read_to_string(&mut rows)
+            .unwrap();
+        assert_eq!(rows, "1\tJohn\n");
+    }
+
+    #[test]
+    fn directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dump");
+        let schema = schema(K_VERS_1_14, &entries());
+        let mut archive = Archive::new(ArchiveFormat::Directory, path.clone(), &schema).unwrap();
+        let data = table_data(&archive, b"1\tJohn\n");
+        archive.add_table(&table(), data).unwrap();
+        archive.finish().unwrap();
+
+        let mut files: Vec<_> = fs::read_dir(&path)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["4.dat.gz", "toc.dat"]);
+
+        let mut rows = String::new();
+        GzDecoder::new(File::open(path.join("4.dat.gz")).unwrap())
+            .read_to_string(&mut rows)
+            .unwrap();
+        assert_eq!(rows, "1\tJohn\n");
+
+        let toc = fs::read(path.join("toc.dat")).unwrap();
+        assert_eq!(toc[MAGIC.len() + 5], ArchiveFormat::Directory.code());
+    }
+
+    #[test]
+    fn not_empty_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("file"), "").unwrap();
+        let schema = schema(K_VERS_1_14, &entries());
+
+        let result = Archive::new(ArchiveFormat::Directory, dir.path().to_path_buf(), &schema);
+        assert!(result.err().unwrap().to_string().ends_with("is not empty"));
+    }
+}
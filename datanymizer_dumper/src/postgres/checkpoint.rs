@@ -0,0 +1,262 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use tempfile::NamedTempFile;
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct Manifest {
+    /// Identifies the dump options (e.g. a hash of the config)
+    fingerprint: String,
+    tables: Vec<DoneTable>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct DoneTable {
+    name: String,
+    file: String,
+    /// The snapshot the table was dumped with (if it is known)
+    snapshot: Option<String>,
+}
+
+/// Every dumped table is saved to its own file in the checkpoint directory,
+/// and the manifest lists the dumped tables.
+/// So a failed dump can be restarted without dumping these tables again.
+pub(super) struct Checkpoints {
+    dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Checkpoints {
+    /// Opens the checkpoint directory (or creates it).
+    /// The directory can be reused only by the dump with the same `fingerprint`,
+    /// a new dump needs an empty directory.
+    pub fn open(dir: PathBuf, fingerprint: &str) -> Result<Self> {
+        fs::create_dir_all(&dir)?;
+
+        let (manifest, new) = match fs::read(dir.join(MANIFEST_FILE)) {
+            Ok(content) => (serde_json::from_slice(&content)?, false),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if fs::read_dir(&dir)?.next().is_some() {
+                    bail!(
+                        "The checkpoint directory {} is not empty and has no {}",
+                        dir.display(),
+                        MANIFEST_FILE
+                    );
+                }
+                let manifest = Manifest {
+                    fingerprint: String::from(fingerprint),
+                    tables: vec![],
+                };
+                (manifest, true)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        if manifest.fingerprint != fingerprint {
+            bail!(
+                "The checkpoint directory {} belongs to a dump with other options",
+                dir.display()
+            );
+        }
+
+        let checkpoints = Self { dir, manifest };
+        // The directory is recognized on the restart even if no table is dumped
+        if new {
+            checkpoints.save_manifest()?;
+        }
+        Ok(checkpoints)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn done_count(&self) -> usize {
+        self.manifest.tables.len()
+    }
+
+    pub fn is_done(&self, table: &str) -> bool {
+        self.manifest.tables.iter().any(|t| t.name == table)
+    }
+
+    /// Whether some tables were dumped with another snapshot than `current` (or with an unknown one),
+    /// so their rows can be inconsistent with rows of the tables dumped now
+    pub fn other_snapshots(&self, current: Option<&str>) -> bool {
+        self.manifest
+            .tables
+            .iter()
+            .any(|t| current.is_none() || t.snapshot.as_deref() != current)
+    }
+
+    /// Saves the table dump (a file from [create_file]) and updates the manifest
+    pub fn add(&mut self, table: &str, file: NamedTempFile, snapshot: Option<&str>) -> Result<()> {
+        let name = format!("{}.sql", self.manifest.tables.len());
+        file.persist(self.dir.join(&name))?;
+
+        self.manifest.tables.push(DoneTable {
+            name: String::from(table),
+            file: name,
+            snapshot: snapshot.map(String::from),
+        });
+        self.save_manifest()
+    }
+
+    /// Writes dumped tables in the order of `tables`
+    pub fn write_tables<W: Write>(&self, tables: &[String], w: &mut W) -> Result<()> {
+        let files: HashMap<_, _> = self
+            .manifest
+            .tables
+            .iter()
+            .map(|t| (t.name.as_str(), t.file.as_str()))
+            .collect();
+
+        for table in tables {
+            match files.get(table.as_str()) {
+                Some(file) => {
+                    io::copy(&mut File::open(self.dir.join(file))?, w)?;
+                }
+                None => bail!("Table {} is not dumped", table),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes the manifest and the table files (when the dump is finished).
+    /// The directory is removed only if nothing else is left in it.
+    pub fn remove(self) -> Result<()> {
+        for table in &self.manifest.tables {
+            fs::remove_file(self.dir.join(&table.file))?;
+        }
+        fs::remove_file(self.dir.join(MANIFEST_FILE))?;
+        let _ = fs::remove_dir(&self.dir);
+        Ok(())
+    }
+
+    // Written to a temporary file first, so a failure can't leave a broken manifest
+    fn save_manifest(&self) -> Result<()> {
+        let mut file = NamedTempFile::new_in(&self.dir)?;
+        serde_json::to_writer_pretty(&mut file, &self.manifest)?;
+        file.as_file().sync_all()?;
+        file.persist(self.dir.join(MANIFEST_FILE))?;
+        Ok(())
+    }
+}
+
+/// Creates a temporary file for a table dump in the checkpoint directory
+pub(super) fn create_file(dir: &Path) -> Result<NamedTempFile> {
+    Ok(NamedTempFile::new_in(dir)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_file(dir: &Path, content: &str) -> NamedTempFile {
+        let mut file = create_file(dir).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file
+    }
+
+    #[test]
+    fn resume() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("checkpoints");
+
+        let mut checkpoints = Checkpoints::open(dir.clone(), "hash").unwrap();
+        assert_eq!(checkpoints.done_count(), 0);
+        let file = table_file(checkpoints.dir(), "table2\n");
+        checkpoints
+            .add("public.table2", file, Some("00000003-0000001B-1"))
+            .unwrap();
+        drop(checkpoints);
+
+        let mut checkpoints = Checkpoints::open(dir.clone(), "hash").unwrap();
+        assert_eq!(checkpoints.done_count(), 1);
+        assert!(checkpoints.is_done("public.table2"));
+        assert!(!checkpoints.is_done("public.table1"));
+        assert_eq!(
+            checkpoints.manifest.tables[0].snapshot,
+            Some(String::from("00000003-0000001B-1"))
+        );
+
+        assert!(checkpoints.other_snapshots(Some("00000004-0000002A-1")));
+        assert!(checkpoints.other_snapshots(None));
+        assert!(!checkpoints.other_snapshots(Some("00000003-0000001B-1")));
+
+        let file = table_file(checkpoints.dir(), "table1\n");
+        checkpoints.add("public.table1", file, None).unwrap();
+        assert!(checkpoints.other_snapshots(Some("00000003-0000001B-1")));
+
+        let mut w = vec![];
+        let tables = vec![String::from("public.table1"), String::from("public.table2")];
+        checkpoints.write_tables(&tables, &mut w).unwrap();
+        assert_eq!(String::from_utf8(w).unwrap(), "table1\ntable2\n");
+
+        checkpoints.remove().unwrap();
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn other_fingerprint() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+
+        let mut checkpoints = Checkpoints::open(dir.clone(), "hash").unwrap();
+        let file = table_file(checkpoints.dir(), "table1\n");
+        checkpoints.add("public.table1", file, None).unwrap();
+
+        let result = Checkpoints::open(dir, "other hash");
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .ends_with("belongs to a dump with other options"));
+    }
+
+    #[test]
+    fn other_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let result = Checkpoints::open(dir.clone(), "hash");
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .ends_with("is not empty and has no manifest.json"));
+
+        fs::remove_file(dir.join("notes.txt")).unwrap();
+        let mut checkpoints = Checkpoints::open(dir.clone(), "hash").unwrap();
+        let file = table_file(checkpoints.dir(), "table1\n");
+        checkpoints.add("public.table1", file, None).unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        checkpoints.remove().unwrap();
+        let files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(files, vec!["notes.txt"]);
+    }
+
+    #[test]
+    fn not_dumped_table() {
+        let tmp = tempfile::tempdir().unwrap();
+        let checkpoints = Checkpoints::open(tmp.path().to_path_buf(), "hash").unwrap();
+
+        let mut w = vec![];
+        let result = checkpoints.write_tables(&[String::from("public.table1")], &mut w);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Table public.table1 is not dumped"
+        );
+    }
+}
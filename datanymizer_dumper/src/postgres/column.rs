@@ -1,4 +1,5 @@
 use crate::ColumnData;
+use datanymizer_engine::ColumnType;
 use postgres::{types::Type, Row as PostgresRow};
 use std::cmp::Ordering;
 
@@ -13,6 +14,9 @@ pub struct PgColumn {
 
     /// Inner postgres type (oid)
     pub inner_type: Option<u32>,
+
+    /// Type metadata (length, precision and nullability)
+    pub column_type: Option<ColumnType>,
 }
 
 impl PartialEq for PgColumn {
@@ -36,12 +40,33 @@ fn cmp(&self, other: &Self) -> Ordering {
 impl From<PostgresRow> for PgColumn {
     fn from(row: PostgresRow) -> Self {
         let oid: u32 = row.get("oid");
+        let data_type: String = row.get("data_type");
+
+        let mut column_type = ColumnType::new(&data_type);
+        let length: Option<i32> = row.get("character_maximum_length");
+        let precision: Option<i32> = row.get("numeric_precision");
+        let scale: Option<i32> = row.get("numeric_scale");
+        match (data_type.as_str(), length, precision) {
+            ("character varying" | "character", Some(length), _) => {
+                column_type = column_type.with_length(length as u32)
+            }
+            // Precision of other numeric types is in bits
+            ("numeric", _, Some(precision)) => {
+                column_type =
+                    column_type.with_precision(precision as u32, scale.unwrap_or(0) as u32)
+            }
+            _ => {}
+        }
+        if row.get::<_, String>("is_nullable") == "NO" {
+            column_type = column_type.not_null();
+        }
 
         Self {
             position: row.get("ordinal_position"),
             name: row.get("column_name"),
-            data_type: row.get("data_type"),
+            data_type,
             inner_type: Some(oid),
+            column_type: Some(column_type),
         }
     }
 }
@@ -61,6 +86,10 @@ fn inner_kind(&self) -> Option<Type> {
             None => None,
         }
     }
+
+    fn column_type(&self) -> Option<ColumnType> {
+        self.column_type.clone()
+    }
 }
 
 #[cfg(test)]
@@ -74,6 +103,7 @@ fn new_column(position: i32, name: &str) -> PgColumn {
             name: String::from(name),
             data_type: String::new(),
             inner_type: Some(0),
+            column_type: None,
         }
     }
 
@@ -19,6 +19,7 @@ pub fn new(client: Client, url: Url) -> Self {
     }
 }
 
+#[derive(Clone)]
 pub struct Connector {
     url: Url,
     accept_invalid_hostnames: bool,
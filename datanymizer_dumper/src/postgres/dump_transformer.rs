@@ -0,0 +1,450 @@
+//! Anonymizes existing plain dumps (made by `pg_dump`) without a database connection.
+
+use super::escaper::{self, NULL};
+use anyhow::{anyhow, bail, Result};
+use datanymizer_engine::{Engine, Table as TableCfg};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+const END_OF_DATA: &[u8] = b"\\.";
+
+/// Transforms rows of `COPY ... FROM stdin;` blocks of a plain dump, everything else is written unchanged
+pub struct DumpTransformer {
+    engine: Engine,
+}
+
+impl DumpTransformer {
+    pub fn new(engine: Engine) -> Self {
+        Self { engine }
+    }
+
+    /// Returns the number of transformed rows
+    pub fn transform<R: BufRead, W: Write>(&self, mut input: R, mut output: W) -> Result<u64> {
+        if self.engine.settings.subset.is_some() {
+            bail!("The `subset` section can't be applied to a dump file");
+        }
+
+        let mut line = Vec::new();
+        let mut line_number = 0;
+        let mut block: Option<CopyBlock> = None;
+        let mut count = 0;
+
+        loop {
+            line.clear();
+            if input.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+            line_number += 1;
+            let content = line.strip_suffix(b"\n").unwrap_or(&line);
+
+            match &block {
+                Some(_) if content == END_OF_DATA => {
+                    block = None;
+                    output.write_all(&line)?;
+                }
+                Some(CopyBlock {
+                    cfg: Some(cfg),
+                    column_indexes,
+                }) => {
+                    let row = std::str::from_utf8(content)
+                        .map_err(|_| anyhow!("Line {}: the row isn't valid UTF-8", line_number))?;
+                    let transformed = self
+                        .transform_row(row, cfg, column_indexes)
+                        .map_err(|e| anyhow!("Line {}: {}", line_number, e))?;
+                    output.write_all(transformed.as_bytes())?;
+                    output.write_all(&line[content.len()..])?;
+                    count += 1;
+                }
+                Some(_) => output.write_all(&line)?,
+                None => {
+                    if let Some(header) = std::str::from_utf8(content)
+                        .ok()
+                        .and_then(CopyHeader::parse)
+                    {
+                        block = Some(self.copy_block(header)?);
+                    }
+                    output.write_all(&line)?;
+                }
+            }
+        }
+        output.flush()?;
+
+        if block.is_some() {
+            bail!("Unexpected end of the dump: no end of the COPY data (`\\.`)");
+        }
+        Ok(count)
+    }
+
+    fn copy_block(&self, header: CopyHeader) -> Result<CopyBlock<'_>> {
+        let cfg = self.engine.settings.find_table(&header.names());
+        if let Some(cfg) = cfg {
+            if cfg.query.is_some() {
+                bail!(
+                    "The `query` section of the `{}` table can't be applied to a dump file",
+                    cfg.name
+                );
+            }
+        }
+
+        Ok(CopyBlock {
+            cfg,
+            column_indexes: header
+                .columns
+                .into_iter()
+                .enumerate()
+                .map(|(i, column)| (column, i))
+                .collect(),
+        })
+    }
+
+    fn transform_row(
+        &self,
+        row: &str,
+        cfg: &TableCfg,
+        column_indexes: &HashMap<String, usize>,
+    ) -> Result<String> {
+        let fields: Vec<&str> = row.split('\t').collect();
+        if fields.len() != column_indexes.len() {
+            bail!(
+                "expected {} values, got {}",
+                column_indexes.len(),
+                fields.len()
+            );
+        }
+
+        let decoded: Vec<Option<Cow<str>>> = fields
+            .iter()
+            .map(|&f| {
+                if f == NULL {
+                    None
+                } else {
+                    Some(escaper::unescape(f))
+                }
+            })
+            .collect();
+        let values: Vec<Option<&str>> = decoded.iter().map(|v| v.as_deref()).collect();
+        let transformed_values =
+            self.engine
+                .process_row(cfg.name.clone(), column_indexes, &values)?;
+
+        let encoded: Vec<Cow<str>> = fields
+            .iter()
+            .zip(transformed_values)
+            .map(|(&field, transformed)| match transformed {
+                // Untouched values are written as they are in the dump
+                Some(Cow::Borrowed(_)) => Cow::Borrowed(field),
+                Some(Cow::Owned(mut s)) => {
+                    escaper::replace_chars(&mut s);
+                    Cow::Owned(s)
+                }
+                None => Cow::Borrowed(NULL),
+            })
+            .collect();
+        Ok(encoded.join("\t"))
+    }
+}
+
+struct CopyBlock<'a> {
+    /// `None` if the table has no rules (rows are written unchanged)
+    cfg: Option<&'a TableCfg>,
+    column_indexes: HashMap<String, usize>,
+}
+
+/// The `COPY schema.table (columns) FROM stdin;` line
+#[derive(Debug, PartialEq, Eq)]
+struct CopyHeader {
+    schema: Option<String>,
+    table: String,
+    columns: Vec<String>,
+}
+
+impl CopyHeader {
+    fn parse(line: &str) -> Option<Self> {
+        let rest = line.strip_prefix("COPY ")?;
+        let rest = rest.strip_suffix(" FROM stdin;")?;
+
+        let (first, rest) = identifier(rest)?;
+        let (schema, table, rest) = match rest.strip_prefix('.') {
+            Some(rest) => {
+                let (table, rest) = identifier(rest)?;
+                (Some(first), table, rest)
+            }
+            None => (None, first, rest),
+        };
+
+        let mut columns = vec![];
+        let rest = rest.trim_start();
+        if let Some(mut list) = rest.strip_prefix('(') {
+            loop {
+                let (column, rest) = identifier(list.trim_start())?;
+                columns.push(column);
+                match rest.strip_prefix(',') {
+                    Some(rest) => list = rest,
+                    None => {
+                        if rest != ")" {
+                            return None;
+                        }
+                        break;
+                    }
+                }
+            }
+        } else if !rest.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            schema,
+            table,
+            columns,
+        })
+    }
+
+    /// Possible names of the table in the config
+    fn names(&self) -> Vec<String> {
+        match &self.schema {
+            Some(schema) => vec![format!("{}.{}", schema, self.table), self.table.clone()],
+            None => vec![self.table.clone()],
+        }
+    }
+}
+
+/// Parses a plain or quoted identifier at the beginning of the string.
+/// Returns the identifier and the rest of the string.
+fn identifier(s: &str) -> Option<(String, &str)> {
+    match s.strip_prefix('"') {
+        Some(quoted) => {
+            let mut name = String::new();
+            let mut chars = quoted.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                if c == '"' {
+                    if let Some((_, '"')) = chars.peek() {
+                        chars.next();
+                    } else {
+                        return Some((name, &quoted[i + 1..]));
+                    }
+                }
+                name.push(c);
+            }
+            None
+        }
+        None => {
+            let end = s
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(s.len());
+            if end == 0 {
+                None
+            } else {
+                Some((s[..end].to_string(), &s[end..]))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datanymizer_engine::Settings;
+
+    fn transformer(config: &str) -> DumpTransformer {
+        DumpTransformer::new(Engine::new(Settings::from_yaml(config).unwrap()))
+    }
+
+    fn transform(transformer: &DumpTransformer, dump: &str) -> Result<(String, u64)> {
+        let mut output = vec![];
+        let count = transformer.transform(dump.as_bytes(), &mut output)?;
+        Ok((String::from_utf8(output).unwrap(), count))
+    }
+
+    mod header {
+        use super::*;
+
+        #[test]
+        fn parse() {
+            assert_eq!(
+                CopyHeader::parse("COPY public.users (id, name, \"E-mail\") FROM stdin;"),
+                Some(CopyHeader {
+                    schema: Some(String::from("public")),
+                    table: String::from("users"),
+                    columns: vec![
+                        String::from("id"),
+                        String::from("name"),
+                        String::from("E-mail")
+                    ],
+                })
+            );
+        }
+
+        #[test]
+        fn quoted_names() {
+            let header =
+                CopyHeader::parse(r#"COPY "My ""Schema""".users_2 ("a.b", c) FROM stdin;"#)
+                    .unwrap();
+
+            assert_eq!(header.schema, Some(String::from(r#"My "Schema""#)));
+            assert_eq!(header.table, "users_2");
+            assert_eq!(header.columns, vec!["a.b", "c"]);
+            assert_eq!(header.names(), vec![r#"My "Schema".users_2"#, "users_2"]);
+        }
+
+        #[test]
+        fn without_schema_and_columns() {
+            let header = CopyHeader::parse("COPY users FROM stdin;").unwrap();
+
+            assert_eq!(header.schema, None);
+            assert_eq!(header.columns, Vec::<String>::new());
+            assert_eq!(header.names(), vec!["users"]);
+        }
+
+        #[test]
+        fn other_lines() {
+            assert_eq!(CopyHeader::parse("COPY users (id) TO stdout;"), None);
+            assert_eq!(CopyHeader::parse("COPY users (id FROM stdin;"), None);
+            assert_eq!(CopyHeader::parse("-- COPY users (id) FROM stdin;"), None);
+            assert_eq!(CopyHeader::parse(r#"COPY "users (id) FROM stdin;"#), None);
+        }
+    }
+
+    #[test]
+    fn transform_copy_blocks() {
+        let transformer = transformer(
+            r#"
+            tables:
+              - name: public.users
+                rules:
+                  name:
+                    template:
+                      format: "{{ _0 }}\tJr."
+                  comment:
+                    template:
+                      format: "{{ prev.name | length }}"
+            "#,
+        );
+        let dump = "SET client_encoding = 'UTF8';\n\
+            \n\
+            COPY public.users (id, name, comment, bio) FROM stdin;\n\
+            1\tJohn\\nSmith\t\\N\tLine\\r\\nbreak\\x21\n\
+            2\tAnn\told\t\\N\n\
+            \\.\n\
+            \n\
+            COPY public.orders (id, name) FROM stdin;\n\
+            1\tJohn\n\
+            \\.\n\
+            \n\
+            ALTER TABLE ONLY public.users ADD CONSTRAINT users_pkey PRIMARY KEY (id);\n";
+
+        let (output, count) = transform(&transformer, dump).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(
+            output,
+            "SET client_encoding = 'UTF8';\n\
+            \n\
+            COPY public.users (id, name, comment, bio) FROM stdin;\n\
+            1\tJohn\\nSmith\\tJr.\t\\N\tLine\\r\\nbreak\\x21\n\
+            2\tAnn\\tJr.\t3\t\\N\n\
+            \\.\n\
+            \n\
+            COPY public.orders (id, name) FROM stdin;\n\
+            1\tJohn\n\
+            \\.\n\
+            \n\
+            ALTER TABLE ONLY public.users ADD CONSTRAINT users_pkey PRIMARY KEY (id);\n"
+        );
+    }
+
+    #[test]
+    fn transform_to_null() {
+        let transformer = transformer(
+            r#"
+            tables:
+              - name: users
+                rules:
+                  name:
+                    set_null: ~
+            "#,
+        );
+        let dump = "COPY public.users (id, name) FROM stdin;\n1\tJohn\n\\.";
+
+        let (output, _) = transform(&transformer, dump).unwrap();
+        assert_eq!(
+            output,
+            "COPY public.users (id, name) FROM stdin;\n1\t\\N\n\\."
+        );
+    }
+
+    #[test]
+    fn wrong_number_of_values() {
+        let transformer = transformer(
+            r#"
+            tables:
+              - name: users
+                rules:
+                  name:
+                    first_name: {}
+            "#,
+        );
+        let dump = "COPY public.users (id, name) FROM stdin;\n1\tJohn\textra\n\\.\n";
+
+        assert_eq!(
+            transform(&transformer, dump).unwrap_err().to_string(),
+            "Line 2: expected 2 values, got 3"
+        );
+    }
+
+    #[test]
+    fn unknown_column() {
+        let transformer = transformer(
+            r#"
+            tables:
+              - name: users
+                rules:
+                  email:
+                    email: {}
+            "#,
+        );
+        let dump = "COPY public.users (id, name) FROM stdin;\n1\tJohn\n\\.\n";
+
+        assert!(transform(&transformer, dump).is_err());
+    }
+
+    #[test]
+    fn query_conditions() {
+        let transformer = transformer(
+            r#"
+            tables:
+              - name: users
+                query:
+                  limit: 10
+                rules:
+                  name:
+                    first_name: {}
+            "#,
+        );
+        let dump = "COPY public.users (id, name) FROM stdin;\n1\tJohn\n\\.\n";
+
+        assert_eq!(
+            transform(&transformer, dump).unwrap_err().to_string(),
+            "The `query` section of the `users` table can't be applied to a dump file"
+        );
+    }
+
+    #[test]
+    fn subset() {
+        let transformer = transformer("subset:\n  roots:\n    - table: users\n");
+
+        assert_eq!(
+            transform(&transformer, "").unwrap_err().to_string(),
+            "The `subset` section can't be applied to a dump file"
+        );
+    }
+
+    #[test]
+    fn unfinished_copy_block() {
+        let transformer = transformer("tables: []");
+        let dump = "COPY public.users (id, name) FROM stdin;\n1\tJohn\n";
+
+        assert!(transform(&transformer, dump).is_err());
+    }
+}
@@ -1,14 +1,34 @@
 use super::{
-    connector, query_wrapper::QueryWrapper, row::PgRow, schema_inspector::PgSchemaInspector,
+    archive::{Archive, ArchiveFormat, DataFiles, TableData, END_OF_DATA},
+    checkpoint::{self, Checkpoints},
+    connector::{self, Connector},
+    escaper::{self, NULL},
+    parallel::ParallelDump,
+    pipeline::{RowPipeline, BATCH_SIZE},
+    query_wrapper::QueryWrapper,
+    scanner::PgScanner,
+    schema_inspector::PgSchemaInspector,
+    sequence::PgSequence,
+    subset,
     table::PgTable,
+    target::Target,
 };
-use crate::{indicator::Indicator, Dumper, SchemaInspector, Table};
-use anyhow::Result;
+use crate::{
+    compression::{Compression, DumpWriter},
+    indicator::Indicator,
+    preview::Preview,
+    scan::{self, TableScan},
+    Dumper, SchemaInspector, Table,
+};
+use anyhow::{bail, Result};
 use datanymizer_engine::{Engine, Filter, Settings, TableList};
 use log::warn;
 use postgres::IsolationLevel;
 use std::{
-    io::{self, prelude::*},
+    borrow::Cow,
+    fs::File,
+    io::{self, prelude::*, BufWriter},
+    path::PathBuf,
     process::{self, Command},
     time::Instant,
 };
@@ -16,12 +36,20 @@
 pub struct PgDumper<W: Write + Send, I: Indicator + Send> {
     schema_inspector: PgSchemaInspector,
     engine: Engine,
-    dump_writer: W,
+    dump_writer: DumpWriter<W>,
     indicator: I,
     dump_isolation_level: Option<IsolationLevel>,
     pg_dump_location: String,
     pg_dump_args: Vec<String>,
     tables: Vec<PgTable>,
+    jobs: Option<(usize, Connector)>,
+    workers: usize,
+    archive_output: Option<(ArchiveFormat, PathBuf)>,
+    archive: Option<Archive>,
+    target: Option<Target>,
+    checkpoint_dir: Option<(PathBuf, String)>,
+    checkpoints: Option<Checkpoints>,
+    strict: bool,
 }
 
 impl<W: 'static + Write + Send, I: 'static + Indicator + Send> PgDumper<W, I> {
@@ -35,24 +63,159 @@ pub fn new(
     ) -> Result<Self> {
         Ok(Self {
             engine,
-            dump_writer,
+            dump_writer: DumpWriter::Plain(dump_writer),
             indicator,
             dump_isolation_level,
             pg_dump_location,
             schema_inspector: PgSchemaInspector {},
             pg_dump_args,
             tables: Vec::new(),
+            jobs: None,
+            workers: 1,
+            archive_output: None,
+            archive: None,
+            target: None,
+            checkpoint_dir: None,
+            checkpoints: None,
+            strict: false,
+        })
+    }
+
+    /// Dumps tables in parallel with `jobs` connections (opened by `connector`).
+    /// All connections use the snapshot of the main connection.
+    pub fn with_jobs(mut self, jobs: usize, connector: Connector) -> Self {
+        if jobs > 1 {
+            self.jobs = Some((jobs, connector));
+        }
+        self
+    }
+
+    /// Transforms rows of each table with `workers` threads
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Compresses the plain dump
+    pub fn with_compression(self, compression: Compression) -> Result<Self> {
+        let dump_writer = match self.dump_writer {
+            DumpWriter::Plain(w) => DumpWriter::new(w, Some(compression))?,
+            w => w,
+        };
+        Ok(Self {
+            dump_writer,
+            ..self
         })
     }
 
-    fn run_pg_dump(&mut self, section: &str, db_url: &str) -> Result<()> {
+    /// Writes a `pg_dump` archive to `path` instead of the plain SQL (the dump writer isn't used then)
+    pub fn with_archive(mut self, format: ArchiveFormat, path: PathBuf) -> Self {
+        self.archive_output = Some((format, path));
+        self
+    }
+
+    /// Loads the schema and the data to the target database instead of writing the dump
+    /// (the dump writer isn't used then)
+    pub fn with_target(mut self, connector: Connector) -> Self {
+        self.target = Some(Target::new(connector));
+        self
+    }
+
+    /// Saves every dumped table to `dir`, so the failed dump can be resumed (only the plain dump).
+    /// The directory can be reused only by the dump with the same `fingerprint` (e.g. a hash of the config),
+    /// and it is removed when the dump is finished.
+    pub fn with_checkpoints(mut self, dir: PathBuf, fingerprint: String) -> Self {
+        self.checkpoint_dir = Some((dir, fingerprint));
+        self
+    }
+
+    /// Fails before dumping if some dumped columns look like personal data, but have no rules
+    /// (see [PgDumper::audit])
+    pub fn with_strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Returns dumped columns that look like personal data, but have no rules
+    /// and aren't listed in `audit.ignore`. The dumper should be prepared ([Dumper::prepare]).
+    pub fn audit(&self, connection: &mut connector::Connection) -> Result<Vec<TableScan>> {
+        let settings = &self.engine.settings;
+        PgScanner::new(settings.audit.sample_size()).uncovered(connection, &self.tables, settings)
+    }
+
+    /// Transforms first `limit` rows of the table like in the dump (nothing is written).
+    /// The dumper should be prepared ([Dumper::prepare]).
+    pub fn preview(
+        &self,
+        connection: &mut connector::Connection,
+        table_name: &str,
+        limit: u64,
+    ) -> Result<Preview> {
+        let table = match self
+            .tables
+            .iter()
+            .find(|t| t.get_names().iter().any(|name| name == table_name))
+        {
+            Some(table) => table,
+            None => bail!("The table `{}` doesn't exist", table_name),
+        };
+        let cfg = match self.engine.settings.find_table(&table.get_names()) {
+            Some(cfg) => cfg,
+            None => bail!("The table `{}` has no rules", table_name),
+        };
+
+        let indexes = table.get_column_indexes();
+        let mut columns: Vec<_> = cfg
+            .rules
+            .keys()
+            .filter_map(|name| indexes.get(name).map(|&i| (i, name.clone())))
+            .collect();
+        columns.sort();
+
+        let reader = connection
+            .client
+            .copy_out(table.preview_query_to(cfg, limit).as_str())?;
+        let mut rows = vec![];
+        for line in reader.lines() {
+            let line = line?;
+            let values: Vec<_> = line.split('\t').collect();
+            let nullable_values: Vec<_> = values
+                .iter()
+                .map(|&v| if v == NULL { None } else { Some(v) })
+                .collect();
+            let transformed =
+                self.engine
+                    .process_row(cfg.name.clone(), indexes, &nullable_values)?;
+            // Untouched values are still in the COPY format, transformed ones are plain
+            let decode = |v: Option<&str>| v.map(|v| escaper::unescape(v).into_owned());
+            rows.push(
+                columns
+                    .iter()
+                    .map(|&(i, _)| {
+                        let value = match &transformed[i] {
+                            Some(Cow::Owned(v)) => Some(v.clone()),
+                            v => decode(v.as_deref()),
+                        };
+                        (decode(nullable_values[i]), value)
+                    })
+                    .collect(),
+            );
+        }
+
+        Ok(Preview {
+            table: table.get_full_name(),
+            columns: columns.into_iter().map(|(_, name)| name).collect(),
+            rows,
+        })
+    }
+
+    fn run_pg_dump(&self, args: &[&str], db_url: &str) -> Result<Vec<u8>> {
         let program = &self.pg_dump_location;
-        let args = vec!["--section", section];
         let table_args = table_args(&self.engine.settings.filter)?;
 
         let dump_output = Command::new(program)
             .args(&self.pg_dump_args)
-            .args(&args)
+            .args(args)
             .args(&table_args)
             .arg(db_url)
             .output()?;
@@ -60,7 +223,8 @@ fn run_pg_dump(&mut self, section: &str, db_url: &str) -> Result<()> {
             eprintln!(
                 "pg_dump error. Command:\n{} {} {}\nOutput:",
                 program,
-                args.into_iter()
+                args.iter()
+                    .copied()
                     .chain(table_args.iter().map(|s| s.as_str()))
                     .collect::<Vec<_>>()
                     .join(" "),
@@ -71,74 +235,417 @@ fn run_pg_dump(&mut self, section: &str, db_url: &str) -> Result<()> {
             process::exit(1);
         }
 
-        self.dump_writer
-            .write_all(&dump_output.stdout)
-            .map_err(|e| e.into())
+        Ok(dump_output.stdout)
     }
 
-    fn dump_table(&mut self, table: &PgTable, qw: &mut QueryWrapper) -> Result<()> {
-        let started = Instant::now();
+    fn run_pg_dump_section(&mut self, section: &str, db_url: &str) -> Result<()> {
+        let output = self.run_pg_dump(&["--section", section], db_url)?;
+        match &self.target {
+            Some(target) => target.execute_schema(&output),
+            None => self.dump_writer.write_all(&output).map_err(|e| e.into()),
+        }
+    }
 
-        self.write_log(format!("Dump table: {}", &table.get_full_name()))?;
+    fn sequential_data(&mut self, connection: &mut connector::Connection) -> Result<()> {
+        let all_tables_count = self.tables.len();
 
-        self.dump_writer.write_all(b"\n")?;
-        self.dump_writer.write_all(table.query_from().as_bytes())?;
-        self.dump_writer.write_all(b"\n")?;
+        let mut query_wrapper =
+            QueryWrapper::with_isolation_level(&mut connection.client, self.dump_isolation_level)?;
+        // Only the transaction snapshot is the same for all tables, so only it is recorded
+        let snapshot: Option<String> = match (&self.checkpoints, self.dump_isolation_level) {
+            (Some(_), Some(IsolationLevel::RepeatableRead | IsolationLevel::Serializable)) => Some(
+                query_wrapper
+                    .query_one("SELECT pg_export_snapshot()", &[])?
+                    .get(0),
+            ),
+            _ => None,
+        };
+        if self
+            .checkpoints
+            .as_ref()
+            .is_some_and(|c| c.other_snapshots(snapshot.as_deref()))
+        {
+            warn_other_snapshots();
+        }
 
-        let cfg = self.engine.settings.find_table(&table.get_names());
+        if let Some(cfg) = &self.engine.settings.subset {
+            self.debug("Computing the subset...".into());
+            subset::apply(
+                cfg,
+                &self.engine.settings,
+                &mut self.tables,
+                &mut query_wrapper,
+                |message| self.indicator.debug_msg(&message),
+            )?;
+        }
 
-        self.indicator
-            .start_pb(table.count_of_query_to(cfg), &table.get_full_name());
+        for (ind, table) in self.tables.iter().enumerate() {
+            self.debug(format!(
+                "[{} / {}] Prepare to dump table: {}",
+                ind + 1,
+                all_tables_count,
+                table.get_full_name(),
+            ));
+
+            if !self.filter_table(table.get_full_name()) {
+                self.debug(format!("[Dumping: {}] --- SKIP ---", table.get_full_name()));
+            } else if self.checkpointed(table) {
+                self.debug(format!("[Dumping: {}] --- DONE ---", table.get_full_name()));
+            } else {
+                match (&mut self.archive, &self.target, &mut self.checkpoints) {
+                    (Some(archive), _, _) => {
+                        let data = dump_table_data(
+                            &self.engine,
+                            &self.indicator,
+                            &archive.data_files(),
+                            table,
+                            &mut query_wrapper,
+                            self.workers,
+                        )?;
+                        archive.add_table(table, data)?;
+                    }
+                    (None, Some(target), _) => load_table(
+                        &self.engine,
+                        &self.indicator,
+                        target,
+                        table,
+                        &mut query_wrapper,
+                        self.workers,
+                    )?,
+                    (None, None, Some(checkpoints)) => {
+                        let mut file = checkpoint::create_file(checkpoints.dir())?;
+                        dump_table_to_file(
+                            &self.engine,
+                            &self.indicator,
+                            file.as_file_mut(),
+                            table,
+                            &mut query_wrapper,
+                            self.workers,
+                        )?;
+                        checkpoints.add(&table.get_full_name(), file, snapshot.as_deref())?;
+                    }
+                    (None, None, None) => dump_table(
+                        &self.engine,
+                        &self.indicator,
+                        &mut self.dump_writer,
+                        table,
+                        &mut query_wrapper,
+                        self.workers,
+                    )?,
+                }
+            }
+        }
 
-        let mut count: u64 = 0;
-        if let Some(cfg) = cfg {
-            if let Some(transformed_query) = table.transformed_query_to(Some(cfg), count) {
-                let reader = qw.copy_out(transformed_query.as_str())?;
-                for line in reader.lines() {
-                    self.indicator.inc_pb(1);
+        Ok(())
+    }
 
-                    let row = PgRow::from_string_row(line?, table.clone());
-                    let transformed =
-                        row.transform(&self.engine, cfg.name.as_str())
-                            .map_err(|err| {
-                                warn!("{:#?}", err);
-                                err
-                            })?;
-                    self.dump_writer.write_all(transformed.as_bytes())?;
-                    self.dump_writer.write_all(b"\n")?;
+    fn parallel_data(
+        &mut self,
+        connection: &mut connector::Connection,
+        jobs: usize,
+        connector: &Connector,
+    ) -> Result<()> {
+        let all_tables_count = self.tables.len();
+        let mut tables = Vec::with_capacity(all_tables_count);
+        for (ind, table) in self.tables.iter().enumerate() {
+            if !self.filter_table(table.get_full_name()) {
+                self.debug(format!("[Dumping: {}] --- SKIP ---", table.get_full_name()));
+            } else if self.checkpointed(table) {
+                self.debug(format!("[Dumping: {}] --- DONE ---", table.get_full_name()));
+            } else {
+                tables.push((ind, table));
+            }
+        }
 
-                    count += 1;
-                }
+        let parallel_dump = ParallelDump::new(
+            connector,
+            self.dump_isolation_level,
+            tables,
+            all_tables_count,
+        );
+        let (engine, workers) = (&self.engine, self.workers);
+
+        match (&mut self.archive, &self.target, &mut self.checkpoints) {
+            (Some(archive), _, _) => {
+                let files = archive.data_files();
+                parallel_dump.run(
+                    &mut connection.client,
+                    jobs,
+                    &self.indicator,
+                    |table, qw, indicator| {
+                        dump_table_data(engine, indicator, &files, table, qw, workers)
+                    },
+                    |_, (table, data)| archive.add_table(table, data),
+                )
+            }
+            (None, Some(target), _) => parallel_dump.run(
+                &mut connection.client,
+                jobs,
+                &self.indicator,
+                |table, qw, indicator| load_table(engine, indicator, target, table, qw, workers),
+                |_, _| Ok(()),
+            ),
+            (None, None, Some(checkpoints)) => {
+                let dir = checkpoints.dir().to_path_buf();
+                let mut checked = false;
+                parallel_dump.run(
+                    &mut connection.client,
+                    jobs,
+                    &self.indicator,
+                    |table, qw, indicator| {
+                        let mut file = checkpoint::create_file(&dir)?;
+                        dump_table_to_file(
+                            engine,
+                            indicator,
+                            file.as_file_mut(),
+                            table,
+                            qw,
+                            workers,
+                        )?;
+                        Ok(file)
+                    },
+                    |snapshot, (table, file)| {
+                        if !checked && checkpoints.other_snapshots(Some(snapshot)) {
+                            warn_other_snapshots();
+                        }
+                        checked = true;
+                        checkpoints.add(&table.get_full_name(), file, Some(snapshot))
+                    },
+                )
+            }
+            (None, None, None) => {
+                let w = &mut self.dump_writer;
+                parallel_dump.run(
+                    &mut connection.client,
+                    jobs,
+                    &self.indicator,
+                    |table, qw, indicator| {
+                        let mut file = tempfile::tempfile()?;
+                        dump_table_to_file(engine, indicator, &mut file, table, qw, workers)?;
+                        Ok(file)
+                    },
+                    |_, (_, mut file): (_, File)| {
+                        file.rewind()?;
+                        io::copy(&mut file, w)?;
+                        Ok(())
+                    },
+                )
             }
         }
+    }
 
-        if let Some(untransformed_query) = table.untransformed_query_to(cfg, count) {
-            let reader = qw.copy_out(untransformed_query.as_str())?;
-            for line in reader.lines() {
-                self.indicator.inc_pb(1);
+    fn checkpointed(&self, table: &PgTable) -> bool {
+        self.checkpoints
+            .as_ref()
+            .is_some_and(|c| c.is_done(&table.get_full_name()))
+    }
 
-                self.dump_writer.write_all(line?.as_bytes())?;
-                self.dump_writer.write_all(b"\n")?;
+    /// Opens the checkpoint directory (only the plain dump is checkpointed)
+    fn open_checkpoints(&mut self) -> Result<()> {
+        if self.archive.is_some() || self.target.is_some() {
+            return Ok(());
+        }
 
-                count += 1;
+        if let Some((dir, fingerprint)) = &self.checkpoint_dir {
+            let checkpoints = Checkpoints::open(dir.clone(), fingerprint)?;
+            if checkpoints.done_count() > 0 {
+                // Subset rows would be computed again, so they wouldn't match the dumped rows
+                if self.engine.settings.subset.is_some() {
+                    bail!(
+                        "Can't resume a dump with a subset, remove the checkpoint directory {} to start it again",
+                        dir.display()
+                    );
+                }
+                // Fake values of these tables depend on the values generated before the restart
+                let stateful = self.dumped_tables_where(|t| self.engine.uses_shared_state(t));
+                if !stateful.is_empty() {
+                    bail!(
+                        "Can't resume a dump with consistency domains, unique values or the template store \
+                         (tables: {}), remove the checkpoint directory {} to start it again",
+                        stateful.join(", "),
+                        dir.display()
+                    );
+                }
+                self.debug(format!(
+                    "Resuming the dump: {} tables are already dumped",
+                    checkpoints.done_count()
+                ));
             }
+            self.checkpoints = Some(checkpoints);
         }
 
-        self.dump_writer.write_all(b"\\.\n")?;
-        for seq in &table.sequences {
-            let last_value: i64 = qw.query_one(seq.last_value_query().as_str(), &[])?.get(0);
-            self.dump_writer.write_all(b"\n")?;
-            self.dump_writer
-                .write_all(seq.setval_query(last_value).as_bytes())?;
-            self.dump_writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Names (in the config) of the dumped tables that match the predicate
+    fn dumped_tables_where<F: Fn(&str) -> bool>(&self, f: F) -> Vec<String> {
+        self.tables
+            .iter()
+            .filter(|t| self.filter_table(t.get_full_name()))
+            .filter_map(|t| self.engine.settings.find_table(&t.get_names()))
+            .map(|cfg| cfg.name.clone())
+            .filter(|name| f(name))
+            .collect()
+    }
+
+    /// Writes tables saved to the checkpoint directory
+    fn write_checkpointed_tables(&mut self) -> Result<()> {
+        if let Some(checkpoints) = &self.checkpoints {
+            let tables: Vec<_> = self
+                .tables
+                .iter()
+                .map(|t| t.get_full_name())
+                .filter(|name| self.filter_table(name.clone()))
+                .collect();
+            checkpoints.write_tables(&tables, &mut self.dump_writer)?;
         }
 
-        let finished = started.elapsed();
-        self.indicator
-            .finish_pb(table.get_full_name().as_str(), finished);
+        Ok(())
+    }
+}
+
+fn warn_other_snapshots() {
+    warn!(
+        "The tables dumped before the restart were read with another snapshot, \
+         so their rows can be inconsistent with rows of other tables (e.g. foreign keys)"
+    );
+}
+
+pub(super) fn dump_table<W: Write, I: Indicator>(
+    engine: &Engine,
+    indicator: &I,
+    w: &mut W,
+    table: &PgTable,
+    qw: &mut QueryWrapper,
+    workers: usize,
+) -> Result<()> {
+    write_log(w, format!("Dump table: {}", &table.get_full_name()))?;
+
+    w.write_all(b"\n")?;
+    w.write_all(table.query_from().as_bytes())?;
+    w.write_all(b"\n")?;
+
+    let sequences = dump_rows(engine, indicator, w, table, qw, workers)?;
+
+    w.write_all(b"\\.\n")?;
+    for (seq, last_value) in sequences {
+        w.write_all(b"\n")?;
+        w.write_all(seq.setval_query(last_value).as_bytes())?;
+        w.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
+
+fn dump_table_to_file<I: Indicator>(
+    engine: &Engine,
+    indicator: &I,
+    file: &mut File,
+    table: &PgTable,
+    qw: &mut QueryWrapper,
+    workers: usize,
+) -> Result<()> {
+    let mut w = BufWriter::new(file);
+    dump_table(engine, indicator, &mut w, table, qw, workers)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// Dumps the table data to a compressed file of the archive
+pub(super) fn dump_table_data<I: Indicator>(
+    engine: &Engine,
+    indicator: &I,
+    files: &DataFiles,
+    table: &PgTable,
+    qw: &mut QueryWrapper,
+    workers: usize,
+) -> Result<TableData> {
+    let mut w = files.create()?;
+    let sequences = dump_rows(engine, indicator, &mut w, table, qw, workers)?;
+    w.write_all(END_OF_DATA)?;
+
+    Ok(TableData {
+        file: w.finish()?,
+        sequences,
+    })
+}
 
+/// Copies the table data to the target database
+fn load_table<I: Indicator>(
+    engine: &Engine,
+    indicator: &I,
+    target: &Target,
+    table: &PgTable,
+    qw: &mut QueryWrapper,
+    workers: usize,
+) -> Result<()> {
+    target.with_client(|client| {
+        let mut w = client.copy_in(table.query_from().as_str())?;
+        let sequences = dump_rows(engine, indicator, &mut w, table, qw, workers)?;
+        w.finish()?;
+
+        for (seq, last_value) in sequences {
+            client.batch_execute(seq.setval_query(last_value).as_str())?;
+        }
         Ok(())
+    })
+}
+
+/// Writes rows of the table and returns last values of its sequences
+fn dump_rows<W: Write, I: Indicator>(
+    engine: &Engine,
+    indicator: &I,
+    w: &mut W,
+    table: &PgTable,
+    qw: &mut QueryWrapper,
+    workers: usize,
+) -> Result<Vec<(PgSequence, i64)>> {
+    let started = Instant::now();
+
+    let cfg = engine.settings.find_table(&table.get_names());
+
+    indicator.start_pb(table.count_of_query_to(cfg), &table.get_full_name());
+
+    let mut count: u64 = 0;
+    if let Some(cfg) = cfg {
+        if let Some(transformed_query) = table.transformed_query_to(Some(cfg), count) {
+            let reader = qw.copy_out(transformed_query.as_str())?;
+            count = RowPipeline {
+                engine,
+                table,
+                cfg_table_name: cfg.name.as_str(),
+                workers,
+                batch_size: BATCH_SIZE,
+            }
+            .run(reader.lines(), indicator, w)?;
+        }
+    }
+
+    if let Some(untransformed_query) = table.untransformed_query_to(cfg, count) {
+        let reader = qw.copy_out(untransformed_query.as_str())?;
+        for line in reader.lines() {
+            indicator.inc_pb(1);
+
+            w.write_all(line?.as_bytes())?;
+            w.write_all(b"\n")?;
+        }
+    }
+
+    let mut sequences = Vec::with_capacity(table.sequences.len());
+    for seq in &table.sequences {
+        let last_value: i64 = qw.query_one(seq.last_value_query().as_str(), &[])?.get(0);
+        sequences.push((seq.clone(), last_value));
     }
+
+    let finished = started.elapsed();
+    indicator.finish_pb(table.get_full_name().as_str(), finished);
+
+    Ok(sequences)
+}
+
+fn write_log<W: Write>(w: &mut W, message: String) -> Result<()> {
+    w.write_all(format!("\n---\n--- {}\n---\n", message).as_bytes())
+        .map_err(|e| e.into())
 }
 
 impl<W: 'static + Write + Send, I: 'static + Indicator + Send> Dumper for PgDumper<W, I> {
@@ -147,33 +654,59 @@ impl<W: 'static + Write + Send, I: 'static + Indicator + Send> Dumper for PgDump
 
     // Stage before dumping data. It makes dump schema with any options
     fn pre_data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        if self.strict {
+            let uncovered = self.audit(connection)?;
+            if !uncovered.is_empty() {
+                bail!(
+                    "These columns look like personal data, but have no rules \
+                    (add rules or list them in `audit.ignore`):\n{}",
+                    scan::report(&uncovered)
+                );
+            }
+        }
+
         self.debug("Prepare data scheme...".into());
-        self.run_pg_dump("pre-data", connection.url.as_str())
+        match self.archive_output.clone() {
+            // The archive gets the whole schema (with post-data entries) at once
+            Some((format, path)) => {
+                let schema = self.run_pg_dump(
+                    &["--format", "custom", "--schema-only"],
+                    connection.url.as_str(),
+                )?;
+                self.archive = Some(Archive::new(format, path, &schema)?);
+                Ok(())
+            }
+            None => self.run_pg_dump_section("pre-data", connection.url.as_str()),
+        }
     }
 
     // This stage makes dump data only
     fn data(&mut self, connection: &mut Self::Connection) -> Result<()> {
         self.write_log("Start dumping data".into())?;
-
-        let all_tables_count = self.tables.len();
-
-        let mut query_wrapper =
-            QueryWrapper::with_isolation_level(&mut connection.client, self.dump_isolation_level)?;
-        for (ind, table) in self.tables.clone().iter().enumerate() {
-            self.debug(format!(
-                "[{} / {}] Prepare to dump table: {}",
-                ind + 1,
-                all_tables_count,
-                table.get_full_name(),
-            ));
-
-            if self.filter_table(table.get_full_name()) {
-                self.dump_table(table, &mut query_wrapper)?;
-            } else {
-                self.debug(format!("[Dumping: {}] --- SKIP ---", table.get_full_name()));
+        self.open_checkpoints()?;
+
+        let uses_store = !self
+            .dumped_tables_where(|t| self.engine.uses_store(t))
+            .is_empty();
+        match self.jobs.clone() {
+            // Subset rows are in temporary tables, which other connections can't see
+            Some(_) if self.engine.settings.subset.is_some() => {
+                self.debug("The subset is dumped with one connection".into());
+                self.sequential_data(connection)?
+            }
+            // Tables share the template store, so they are dumped in their order
+            Some(_) if uses_store => {
+                self.debug(
+                    "The tables using the template store are dumped with one connection".into(),
+                );
+                self.sequential_data(connection)?
             }
+            Some((jobs, connector)) => self.parallel_data(connection, jobs, &connector)?,
+            None => self.sequential_data(connection)?,
         }
 
+        self.write_checkpointed_tables()?;
+
         self.write_log("End dumping data".into())?;
         Ok(())
     }
@@ -181,7 +714,19 @@ fn data(&mut self, connection: &mut Self::Connection) -> Result<()> {
     // This stage makes dump foreign keys, indices and other...
     fn post_data(&mut self, connection: &mut Self::Connection) -> Result<()> {
         self.debug("Finishing with indexes...".into());
-        self.run_pg_dump("post-data", connection.url.as_str())
+        match self.archive.take() {
+            Some(archive) => archive.finish(),
+            None => {
+                self.run_pg_dump_section("post-data", connection.url.as_str())?;
+                self.dump_writer.finish()?;
+
+                // The dump is finished, so checkpoints aren't needed anymore
+                match self.checkpoints.take() {
+                    Some(checkpoints) => checkpoints.remove(),
+                    None => Ok(()),
+                }
+            }
+        }
     }
 
     fn schema_inspector(&self) -> Self::SchemaInspector {
@@ -196,14 +741,16 @@ fn settings(&self) -> &Settings {
         &self.engine.settings
     }
 
-    fn filter_mut(&mut self) -> &mut Filter {
-        &mut self.engine.settings.filter
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.engine.settings
     }
 
     fn write_log(&mut self, message: String) -> Result<()> {
-        self.dump_writer
-            .write_all(format!("\n---\n--- {}\n---\n", message).as_bytes())
-            .map_err(|e| e.into())
+        // Archives and the target database have no place for comments
+        if self.archive_output.is_some() || self.target.is_some() {
+            return Ok(());
+        }
+        write_log(&mut self.dump_writer, message)
     }
 
     fn debug(&self, message: String) {
@@ -1,25 +1,16 @@
+use std::borrow::Cow;
+
+/// NULL in the COPY text format
+pub const NULL: &str = r#"\N"#;
+
 /// The escaper for values from transformers.
 /// The character escaping rules for the PostgreSQL COPY command are described here:
 /// https://www.postgresql.org/docs/13/sql-copy.html#id-1.9.3.55.9.2
-/// If we need a NULL value in our database, we must return `\N` from the transformer.
-/// Example:
-/// ```yaml
-/// template:
-///    format: '\N'
-/// ```
-/// If you need the `\N` literal in your database, please return `\\N` from the transformer.
-/// If you need the `\\N` literal - return `\\\N` and so on.
-///
-/// Warning! This behavior can be changed in the future.
+/// NULL values are not escaped: they are written as `\N` by callers.
 pub fn replace_chars(s: &mut String) {
-    if s == r#"\N"# {
-        return;
-    }
-
     let len = s.len();
     let mut new_s = None;
     let mut beginning = 0;
-    let mut slash_count = 0;
 
     for (i, c) in s.char_indices() {
         if let Some(replacement) = match c {
@@ -29,10 +20,7 @@ pub fn replace_chars(s: &mut String) {
             '\r' => Some(r#"\r"#),
             '\t' => Some(r#"\t"#),
             '\x0B' => Some(r#"\v"#),
-            '\\' => {
-                slash_count += 1;
-                Some(r#"\\"#)
-            }
+            '\\' => Some(r#"\\"#),
             _ => None,
         } {
             if new_s.is_none() {
@@ -49,14 +37,6 @@ pub fn replace_chars(s: &mut String) {
     }
 
     if let Some(mut new_s) = new_s {
-        if slash_count == len - 1 && s.ends_with('N') {
-            if slash_count == 2 {
-                return;
-            } else {
-                new_s.truncate((slash_count - 1) * 2);
-            }
-        }
-
         if beginning < len {
             new_s.push_str(&s[beginning..len])
         }
@@ -64,9 +44,62 @@ pub fn replace_chars(s: &mut String) {
     }
 }
 
+/// Decodes a value of the COPY text format (the reverse of [replace_chars]).
+/// Besides the sequences of [replace_chars], octal (`\123`) and hex (`\x4F`) byte values are decoded,
+/// any other escaped character is taken literally. `\N` (NULL) should be checked before decoding.
+pub fn unescape(s: &str) -> Cow<'_, str> {
+    if !s.contains('\\') {
+        return Cow::Borrowed(s);
+    }
+
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' || i + 1 == bytes.len() {
+            decoded.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+
+        i += 1;
+        match bytes[i] {
+            b'b' => decoded.push(b'\x08'),
+            b'f' => decoded.push(b'\x0C'),
+            b'n' => decoded.push(b'\n'),
+            b'r' => decoded.push(b'\r'),
+            b't' => decoded.push(b'\t'),
+            b'v' => decoded.push(b'\x0B'),
+            b'0'..=b'7' => {
+                let len = digits(&bytes[i..], 3, |b| (b'0'..=b'7').contains(&b));
+                decoded.push(u8::from_str_radix(&s[i..i + len], 8).unwrap_or(u8::MAX));
+                i += len - 1;
+            }
+            b'x' if digits(&bytes[i + 1..], 2, |b| b.is_ascii_hexdigit()) > 0 => {
+                let len = digits(&bytes[i + 1..], 2, |b| b.is_ascii_hexdigit());
+                decoded.push(u8::from_str_radix(&s[i + 1..i + 1 + len], 16).unwrap());
+                i += len;
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+
+    Cow::Owned(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+/// Number of leading bytes (at most `max`) matching the predicate
+fn digits(bytes: &[u8], max: usize, predicate: fn(u8) -> bool) -> usize {
+    bytes
+        .iter()
+        .take(max)
+        .take_while(|&&b| predicate(b))
+        .count()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::postgres::escaper::replace_chars;
+    use crate::postgres::escaper::{replace_chars, unescape};
 
     #[test]
     fn replace() {
@@ -173,21 +206,14 @@ mod null_like_sequences {
         fn one_slash() {
             let mut s = String::from(r#"\N"#);
             replace_chars(&mut s);
-            assert_eq!(s, r#"\N"#);
+            assert_eq!(s, r#"\\N"#);
         }
 
         #[test]
         fn two_slashes() {
             let mut s = String::from(r#"\\N"#);
             replace_chars(&mut s);
-            assert_eq!(s, r#"\\N"#);
-        }
-
-        #[test]
-        fn five_slashes() {
-            let mut s = String::from(r#"\\\\\N"#);
-            replace_chars(&mut s);
-            assert_eq!(s, r#"\\\\\\\\N"#);
+            assert_eq!(s, r#"\\\\N"#);
         }
 
         #[test]
@@ -197,4 +223,40 @@ fn null_sequence_inside_string() {
             assert_eq!(s, r#"test\\Nstring"#);
         }
     }
+
+    mod unescape {
+        use super::*;
+        use std::borrow::Cow;
+
+        #[test]
+        fn without_escapes() {
+            assert!(matches!(unescape("abc"), Cow::Borrowed("abc")));
+        }
+
+        #[test]
+        fn sequences() {
+            assert_eq!(unescape(r#"\ta\vb\\c\b\f\r\n"#), "\ta\x0Bb\\c\x08\x0C\r\n");
+        }
+
+        #[test]
+        fn octal_and_hex() {
+            assert_eq!(unescape(r#"\101\x42\x4a\1x\xz"#), "ABJ\x01xxz");
+            assert_eq!(unescape(r#"\320\257"#), "Я");
+        }
+
+        #[test]
+        fn other_chars() {
+            assert_eq!(unescape(r#"\a\.\N"#), "a.N");
+            assert_eq!(unescape(r#"end\"#), r#"end\"#);
+        }
+
+        #[test]
+        fn reverse_of_replace_chars() {
+            for value in ["abc\r\nde\tf", "Я\\", "test\\Nstring", "𡞰x\\", "\\N"] {
+                let mut s = String::from(value);
+                replace_chars(&mut s);
+                assert_eq!(unescape(&s), value);
+            }
+        }
+    }
 }
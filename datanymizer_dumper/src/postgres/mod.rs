@@ -2,14 +2,24 @@
 
 pub mod column;
 pub mod connector;
+pub mod dump_transformer;
 pub mod dumper;
 pub mod foreign_key;
 pub mod row;
+pub mod scanner;
 pub mod schema_inspector;
 pub mod table;
+pub mod validator;
 
+mod archive;
+mod checkpoint;
 mod escaper;
+mod parallel;
+mod pipeline;
 mod query_wrapper;
 mod sequence;
+mod subset;
+mod target;
 
+pub use archive::ArchiveFormat;
 pub use postgres::IsolationLevel;
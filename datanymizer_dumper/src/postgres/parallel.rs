@@ -0,0 +1,209 @@
+use super::{connector::Connector, query_wrapper::QueryWrapper, table::PgTable};
+use crate::{indicator::Indicator, Table};
+use anyhow::{anyhow, Result};
+use postgres::{Client, IsolationLevel};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+        Mutex,
+    },
+    thread,
+};
+
+type Queue<'a> = Mutex<std::iter::Enumerate<std::vec::IntoIter<(usize, &'a PgTable)>>>;
+
+/// Dumps tables with several connections at the same time.
+/// The main connection exports its snapshot, so all jobs see the same data.
+/// Jobs dump tables (e.g. to temporary files), and the results are consumed in the table order.
+pub(super) struct ParallelDump<'a> {
+    connector: &'a Connector,
+    isolation_level: IsolationLevel,
+    queue: Queue<'a>,
+    all_tables_count: usize,
+    failed: AtomicBool,
+}
+
+impl<'a> ParallelDump<'a> {
+    /// `tables` are pairs of the table index (in the list of all tables) and the table
+    pub fn new(
+        connector: &'a Connector,
+        isolation_level: Option<IsolationLevel>,
+        tables: Vec<(usize, &'a PgTable)>,
+        all_tables_count: usize,
+    ) -> Self {
+        // Snapshots can be imported only in REPEATABLE READ or SERIALIZABLE transactions
+        let isolation_level = match isolation_level {
+            Some(IsolationLevel::Serializable) => IsolationLevel::Serializable,
+            _ => IsolationLevel::RepeatableRead,
+        };
+
+        Self {
+            connector,
+            isolation_level,
+            queue: Mutex::new(tables.into_iter().enumerate()),
+            all_tables_count,
+            failed: AtomicBool::new(false),
+        }
+    }
+
+    /// `dump` is called by jobs for every table, and `consume` gets the snapshot id,
+    /// the tables and dump results in the table order
+    pub fn run<I, T, D, C>(
+        &self,
+        client: &mut Client,
+        jobs: usize,
+        indicator: &I,
+        dump: D,
+        mut consume: C,
+    ) -> Result<()>
+    where
+        I: Indicator + Send,
+        T: Send,
+        D: Fn(&PgTable, &mut QueryWrapper, &I) -> Result<T> + Sync,
+        C: FnMut(&str, (&'a PgTable, T)) -> Result<()>,
+    {
+        let mut transaction = client
+            .build_transaction()
+            .isolation_level(self.isolation_level)
+            .read_only(true)
+            .start()?;
+        let snapshot: String = transaction
+            .query_one("SELECT pg_export_snapshot()", &[])?
+            .get(0);
+
+        let (sender, receiver) = mpsc::channel();
+        let result = thread::scope(|s| {
+            for job_indicator in (0..jobs).map(|_| indicator.fork()) {
+                let sender = sender.clone();
+                let snapshot = snapshot.as_str();
+                let dump = &dump;
+                s.spawn(move || {
+                    if let Err(e) = self.run_job(snapshot, job_indicator, dump, &sender) {
+                        self.failed.store(true, Ordering::Relaxed);
+                        let _ = sender.send(Err(e));
+                    }
+                });
+            }
+            drop(sender);
+
+            let result = consume_ordered(receiver, |value| consume(&snapshot, value));
+            if result.is_err() {
+                self.failed.store(true, Ordering::Relaxed);
+            }
+            result
+        });
+
+        transaction.commit()?;
+        result
+    }
+
+    fn run_job<I, T, D>(
+        &self,
+        snapshot: &str,
+        indicator: I,
+        dump: &D,
+        sender: &Sender<Result<(usize, (&'a PgTable, T))>>,
+    ) -> Result<()>
+    where
+        I: Indicator,
+        D: Fn(&PgTable, &mut QueryWrapper, &I) -> Result<T>,
+    {
+        let mut connection = self.connector.connect()?;
+        let transaction = connection
+            .client
+            .build_transaction()
+            .isolation_level(self.isolation_level)
+            .read_only(true)
+            .start()?;
+        let mut qw = QueryWrapper::WithTransaction(transaction);
+        qw.batch_execute(format!("SET TRANSACTION SNAPSHOT '{}'", snapshot).as_str())?;
+
+        while !self.failed.load(Ordering::Relaxed) {
+            let next = self.queue.lock().map_err(|_| anyhow!("Sync error"))?.next();
+            let (position, (ind, table)) = match next {
+                Some(next) => next,
+                None => break,
+            };
+
+            indicator.debug_msg(&format!(
+                "[{} / {}] Prepare to dump table: {}",
+                ind + 1,
+                self.all_tables_count,
+                table.get_full_name(),
+            ));
+
+            let result = dump(table, &mut qw, &indicator)?;
+            if sender.send(Ok((position, (table, result)))).is_err() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Consumes dump results in the order of their positions
+fn consume_ordered<T, R, C>(results: R, mut consume: C) -> Result<()>
+where
+    R: IntoIterator<Item = Result<(usize, T)>>,
+    C: FnMut(T) -> Result<()>,
+{
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+
+    for result in results {
+        let (position, value) = result?;
+        pending.insert(position, value);
+
+        while let Some(value) = pending.remove(&next) {
+            consume(value)?;
+            next += 1;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consume(results: Vec<Result<(usize, &str)>>) -> (Result<()>, Vec<&str>) {
+        let mut consumed = vec![];
+        let result = consume_ordered(results, |value| {
+            consumed.push(value);
+            Ok(())
+        });
+        (result, consumed)
+    }
+
+    #[test]
+    fn ordered() {
+        let results = vec![
+            Ok((2, "table3")),
+            Ok((0, "table1")),
+            Ok((3, "table4")),
+            Ok((1, "table2")),
+        ];
+
+        let (result, consumed) = consume(results);
+        assert!(result.is_ok());
+        assert_eq!(consumed, vec!["table1", "table2", "table3", "table4"]);
+    }
+
+    #[test]
+    fn error() {
+        let results = vec![
+            Ok((1, "table2")),
+            Ok((0, "table1")),
+            Err(anyhow!("some error")),
+            Ok((2, "table3")),
+        ];
+
+        let (result, consumed) = consume(results);
+        assert_eq!(result.unwrap_err().to_string(), "some error");
+        assert_eq!(consumed, vec!["table1", "table2"]);
+    }
+}
@@ -0,0 +1,343 @@
+use super::{row::PgRow, table::PgTable};
+use crate::indicator::Indicator;
+use anyhow::{anyhow, Result};
+use datanymizer_engine::Engine;
+use log::warn;
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Mutex},
+    thread,
+};
+
+/// Rows are sent to workers in batches of this size
+pub(super) const BATCH_SIZE: usize = 1000;
+
+/// Transforms rows and writes them to `w`. Returns the number of rows.
+///
+/// With several workers, the current thread reads and writes rows, and worker threads transform
+/// them in batches. Rows are written in the original order, but they can be transformed in any order,
+/// so tables whose rules use the template store are transformed sequentially.
+pub(super) struct RowPipeline<'a> {
+    pub engine: &'a Engine,
+    pub table: &'a PgTable,
+    pub cfg_table_name: &'a str,
+    pub workers: usize,
+    pub batch_size: usize,
+}
+
+impl RowPipeline<'_> {
+    pub fn run<L, I, W>(&self, lines: L, indicator: &I, w: &mut W) -> Result<u64>
+    where
+        L: Iterator<Item = io::Result<String>>,
+        I: Indicator,
+        W: Write,
+    {
+        if self.workers > 1 && !self.engine.uses_store(self.cfg_table_name) {
+            self.run_parallel(lines, indicator, w)
+        } else {
+            self.run_sequential(lines, indicator, w)
+        }
+    }
+
+    fn run_sequential<L, I, W>(&self, lines: L, indicator: &I, w: &mut W) -> Result<u64>
+    where
+        L: Iterator<Item = io::Result<String>>,
+        I: Indicator,
+        W: Write,
+    {
+        let mut count = 0;
+        for line in lines {
+            indicator.inc_pb(1);
+            write_row(w, &self.transform(line?)?)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+
+    fn run_parallel<L, I, W>(&self, lines: L, indicator: &I, w: &mut W) -> Result<u64>
+    where
+        L: Iterator<Item = io::Result<String>>,
+        I: Indicator,
+        W: Write,
+    {
+        // The COPY reader must not be read after the end
+        let mut lines = lines.fuse();
+        let (batch_sender, batch_receiver) =
+            mpsc::sync_channel::<(usize, Vec<String>)>(self.workers);
+        let batch_receiver = Mutex::new(batch_receiver);
+        let (result_sender, result_receiver) = mpsc::channel();
+
+        thread::scope(|s| {
+            for _ in 0..self.workers {
+                let batch_receiver = &batch_receiver;
+                let result_sender = result_sender.clone();
+                s.spawn(move || loop {
+                    let batch = match batch_receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    // The channel is closed: there are no more rows (or the dump has failed)
+                    let (index, lines) = match batch {
+                        Ok(batch) => batch,
+                        Err(_) => break,
+                    };
+                    // A panic must not leave the writer waiting for this batch forever
+                    // (only in debug builds, release builds abort on panics)
+                    let rows = panic::catch_unwind(AssertUnwindSafe(|| {
+                        lines
+                            .into_iter()
+                            .map(|line| self.transform(line))
+                            .collect::<Result<Vec<_>>>()
+                    }))
+                    .unwrap_or_else(|_| Err(anyhow!("Row transformation has panicked")));
+                    if result_sender.send((index, rows)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(result_sender);
+
+            let mut writer = OrderedWriter::new(w);
+            let max_batches_in_flight = self.workers * 2;
+            let mut sent = 0;
+            let mut count = 0;
+
+            loop {
+                let batch = lines
+                    .by_ref()
+                    .take(self.batch_size)
+                    .collect::<io::Result<Vec<_>>>()?;
+                if batch.is_empty() {
+                    break;
+                }
+                count += batch.len() as u64;
+
+                while sent - writer.next >= max_batches_in_flight {
+                    writer.receive(&result_receiver, indicator)?;
+                }
+                batch_sender
+                    .send((sent, batch))
+                    .map_err(|_| anyhow!("Row workers have stopped"))?;
+                sent += 1;
+            }
+            drop(batch_sender);
+
+            while writer.next < sent {
+                writer.receive(&result_receiver, indicator)?;
+            }
+
+            Ok(count)
+        })
+    }
+
+    fn transform(&self, line: String) -> Result<String> {
+        PgRow::from_string_row(line, self.table.clone())
+            .transform(self.engine, self.cfg_table_name)
+            .map_err(|err| {
+                warn!("{:#?}", err);
+                err
+            })
+    }
+}
+
+/// Writes transformed batches in the order of their indexes
+struct OrderedWriter<'w, W: Write> {
+    w: &'w mut W,
+    next: usize,
+    pending: BTreeMap<usize, Vec<String>>,
+}
+
+impl<'w, W: Write> OrderedWriter<'w, W> {
+    fn new(w: &'w mut W) -> Self {
+        Self {
+            w,
+            next: 0,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    fn receive<I: Indicator>(
+        &mut self,
+        receiver: &mpsc::Receiver<(usize, Result<Vec<String>>)>,
+        indicator: &I,
+    ) -> Result<()> {
+        let (index, rows) = receiver
+            .recv()
+            .map_err(|_| anyhow!("Row workers have stopped"))?;
+        self.pending.insert(index, rows?);
+
+        while let Some(rows) = self.pending.remove(&self.next) {
+            for row in &rows {
+                write_row(self.w, row)?;
+            }
+            indicator.inc_pb(rows.len() as u64);
+            self.next += 1;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_row<W: Write>(w: &mut W, row: &str) -> Result<()> {
+    w.write_all(row.as_bytes())?;
+    w.write_all(b"\n")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{indicator::SilentIndicator, postgres::column::PgColumn};
+    use datanymizer_engine::Settings;
+
+    fn engine() -> Engine {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  capitalize: ~
+                email:
+                  template:
+                    format: "user{{ prev.id }}@example.com"
+        "#;
+        Engine::new(Settings::from_yaml(config).unwrap())
+    }
+
+    fn table() -> PgTable {
+        let mut table = PgTable::new("users".to_string(), "public".to_string());
+        let columns = ["id", "name", "email"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| PgColumn {
+                position: i as i32 + 1,
+                name: name.to_string(),
+                data_type: String::new(),
+                inner_type: Some(0),
+                column_type: None,
+            })
+            .collect();
+        table.set_columns(columns);
+        table
+    }
+
+    // Like the COPY reader, it must not be read after the end
+    struct Lines {
+        rows: usize,
+        current: usize,
+    }
+
+    impl Iterator for Lines {
+        type Item = io::Result<String>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            assert!(self.current <= self.rows, "read after the end");
+            let i = self.current;
+            self.current += 1;
+            if i < self.rows {
+                Some(Ok(format!("{}\tname{}\temail{}", i, i, i)))
+            } else {
+                None
+            }
+        }
+    }
+
+    fn run(workers: usize, batch_size: usize, rows: usize) -> (u64, Vec<String>) {
+        let engine = engine();
+        let table = table();
+        let pipeline = RowPipeline {
+            engine: &engine,
+            table: &table,
+            cfg_table_name: "users",
+            workers,
+            batch_size,
+        };
+        let lines = Lines { rows, current: 0 };
+
+        let mut w = Vec::new();
+        let count = pipeline.run(lines, &SilentIndicator, &mut w).unwrap();
+        let output = String::from_utf8(w)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        (count, output)
+    }
+
+    fn expected(rows: usize) -> Vec<String> {
+        (0..rows)
+            .map(|i| format!("{}\tName{}\tuser{}@example.com", i, i, i))
+            .collect()
+    }
+
+    #[test]
+    fn sequential() {
+        assert_eq!(run(1, 3, 100), (100, expected(100)));
+    }
+
+    #[test]
+    fn parallel() {
+        assert_eq!(run(4, 3, 100), (100, expected(100)));
+        assert_eq!(run(4, 1000, 10), (10, expected(10)));
+        assert_eq!(run(4, 3, 0), (0, vec![]));
+        assert_eq!(run(4, 5, 100), (100, expected(100)));
+    }
+
+    #[test]
+    fn store() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ store_inc(key='n', value=1) }}{{ store_read(key='n') }}"
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+        let table = table();
+        let pipeline = RowPipeline {
+            engine: &engine,
+            table: &table,
+            cfg_table_name: "users",
+            workers: 4,
+            batch_size: 3,
+        };
+        let lines = (0..100).map(|i| Ok(format!("{}\tname{}\temail{}", i, i, i)));
+
+        let mut w = Vec::new();
+        pipeline.run(lines, &SilentIndicator, &mut w).unwrap();
+        let expected: String = (0..100)
+            .map(|i| format!("{}\t{}\temail{}\n", i, i + 1, i))
+            .collect();
+        assert_eq!(String::from_utf8(w).unwrap(), expected);
+    }
+
+    #[test]
+    fn error() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                email:
+                  template:
+                    format: "{{ prev.unknown_column }}"
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+        let table = table();
+        let pipeline = RowPipeline {
+            engine: &engine,
+            table: &table,
+            cfg_table_name: "users",
+            workers: 4,
+            batch_size: 3,
+        };
+        let lines = (0..100).map(|i| Ok(format!("{}\tname{}\temail{}", i, i, i)));
+
+        let mut w = Vec::new();
+        assert!(pipeline.run(lines, &SilentIndicator, &mut w).is_err());
+    }
+}
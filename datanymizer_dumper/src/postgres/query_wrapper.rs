@@ -29,6 +29,24 @@ pub fn copy_out<T>(&mut self, query: &T) -> Result<CopyOutReader<'_>, postgres::
         }
     }
 
+    pub fn batch_execute(&mut self, query: &str) -> Result<(), postgres::Error> {
+        match self {
+            Self::WithTransaction(t) => t.batch_execute(query),
+            Self::WithoutTransaction(c) => c.batch_execute(query),
+        }
+    }
+
+    /// Returns the number of affected rows
+    pub fn execute<T>(&mut self, query: &T) -> Result<u64, postgres::Error>
+    where
+        T: ?Sized + ToStatement,
+    {
+        match self {
+            Self::WithTransaction(t) => t.execute(query, &[]),
+            Self::WithoutTransaction(c) => c.execute(query, &[]),
+        }
+    }
+
     pub fn query_one<T>(
         &mut self,
         query: &T,
@@ -1,4 +1,4 @@
-use super::escaper;
+use super::escaper::{self, NULL};
 use crate::Table;
 use anyhow::Result;
 use datanymizer_engine::Engine;
@@ -29,19 +29,29 @@ pub fn from_string_row(source: String, parent_table: T) -> Self {
     /// Returns a new StringRecord for store in the dump
     pub fn transform(&self, engine: &Engine, cfg_tbl_name: &str) -> Result<String> {
         let split_char: char = char::from_u32(0x0009).unwrap();
-        let values: Vec<_> = self.source.split(split_char).collect();
-        let mut transformed_values = engine.process_row(
+        let values: Vec<_> = self
+            .source
+            .split(split_char)
+            .map(|v| if v == NULL { None } else { Some(v) })
+            .collect();
+        let transformed_values = engine.process_row(
             String::from(cfg_tbl_name),
             self.table.get_column_indexes(),
             &values,
         )?;
-        for v in &mut transformed_values {
-            if let Cow::Owned(ref mut s) = v {
-                escaper::replace_chars(s);
-            }
-        }
+        let encoded: Vec<Cow<str>> = transformed_values
+            .into_iter()
+            .map(|v| match v {
+                Some(Cow::Owned(mut s)) => {
+                    escaper::replace_chars(&mut s);
+                    Cow::Owned(s)
+                }
+                Some(v) => v,
+                None => Cow::Borrowed(NULL),
+            })
+            .collect();
 
-        Ok(transformed_values.join("\t"))
+        Ok(encoded.join("\t"))
     }
 }
 
@@ -57,6 +67,7 @@ fn new_column(position: i32, name: &str) -> PgColumn {
             name: String::from(name),
             data_type: String::new(),
             inner_type: Some(0),
+            column_type: None,
         }
     }
 
@@ -0,0 +1,119 @@
+use super::{
+    column::PgColumn, connector::Connection, schema_inspector::PgSchemaInspector, table::PgTable,
+};
+use crate::{
+    scan::{self, TableScan},
+    SchemaInspector, Table,
+};
+use anyhow::Result;
+use datanymizer_engine::Settings;
+
+/// Detects PII in all tables of the database (see [crate::scan])
+pub struct PgScanner {
+    /// Number of rows sampled from each table
+    sample_size: u32,
+}
+
+impl PgScanner {
+    pub fn new(sample_size: u32) -> Self {
+        Self { sample_size }
+    }
+
+    pub fn scan(&self, connection: &mut Connection) -> Result<Vec<TableScan>> {
+        let mut tables = PgSchemaInspector.get_tables(connection)?;
+        tables.sort_by_key(|t| t.get_full_name());
+
+        tables
+            .iter()
+            .map(|table| self.scan_table(connection, table, |_| true))
+            .collect()
+    }
+
+    /// Detects PII in columns without rules (only in the dumped tables, `filter` should be loaded).
+    /// Columns from `audit.ignore` are skipped.
+    /// Returns only tables with such columns.
+    pub fn uncovered(
+        &self,
+        connection: &mut Connection,
+        tables: &[PgTable],
+        settings: &Settings,
+    ) -> Result<Vec<TableScan>> {
+        let mut uncovered = vec![];
+        for table in tables {
+            if !settings.filter.filter_table(&table.get_full_name()) {
+                continue;
+            }
+
+            let names = table.get_names();
+            let rules = settings.find_table(&names).map(|t| &t.rules);
+            let scanned = self.scan_table(connection, table, |column| {
+                !rules.is_some_and(|rules| rules.contains_key(&column.name))
+                    && !settings.audit.is_ignored(&names, &column.name)
+            })?;
+            if !scanned.columns.is_empty() {
+                uncovered.push(scanned);
+            }
+        }
+
+        Ok(uncovered)
+    }
+
+    fn scan_table<F>(&self, connection: &mut Connection, table: &PgTable, f: F) -> Result<TableScan>
+    where
+        F: Fn(&PgColumn) -> bool,
+    {
+        let mut columns: Vec<&PgColumn> = table
+            .columns
+            .iter()
+            .filter(|c| scan::is_scannable(&c.data_type) && f(c))
+            .collect();
+        columns.sort();
+
+        let samples = self.sample(connection, table, &columns)?;
+        let columns = columns
+            .iter()
+            .zip(samples)
+            .filter_map(|(column, samples)| {
+                scan::scan_column(&column.name, &column.data_type, &samples)
+            })
+            .collect();
+
+        Ok(TableScan {
+            name: table.get_full_name(),
+            columns,
+        })
+    }
+
+    /// Returns not null values of every column
+    fn sample(
+        &self,
+        connection: &mut Connection,
+        table: &PgTable,
+        columns: &[&PgColumn],
+    ) -> Result<Vec<Vec<String>>> {
+        let mut samples = vec![vec![]; columns.len()];
+        if columns.is_empty() || self.sample_size == 0 {
+            return Ok(samples);
+        }
+
+        let query = format!(
+            "SELECT {} FROM {} LIMIT {}",
+            columns
+                .iter()
+                .map(|c| format!("{}::text", PgTable::quote_identifier(&c.name)))
+                .collect::<Vec<_>>()
+                .join(", "),
+            table.quoted_full_name(),
+            self.sample_size
+        );
+        for row in connection.client.query(query.as_str(), &[])? {
+            for (i, values) in samples.iter_mut().enumerate() {
+                if let Some(value) = row.get::<_, Option<String>>(i) {
+                    values.push(value);
+                }
+            }
+        }
+
+        Ok(samples)
+    }
+}
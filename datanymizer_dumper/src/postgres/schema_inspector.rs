@@ -9,21 +9,28 @@
     FROM pg_catalog.pg_tables
     WHERE schemaname != 'pg_catalog' AND schemaname != 'information_schema'";
 
-const TABLE_FK_QUERY: &str = "SELECT tc.table_schema,
-        tc.constraint_name,
-        tc.table_name,
-        kcu.column_name,
-        ccu.table_schema AS foreign_table_schema,
-        ccu.table_name AS foreign_table_name,
-        ccu.column_name AS foreign_column_name
-    FROM information_schema.table_constraints AS tc
-    JOIN information_schema.key_column_usage AS kcu
-    ON tc.constraint_name = kcu.constraint_name AND  tc.table_schema = kcu.table_schema
-    JOIN information_schema.constraint_column_usage AS ccu
-    ON ccu.constraint_name = tc.constraint_name AND ccu.table_schema = tc.table_schema
-    WHERE tc.constraint_type = 'FOREIGN KEY' AND tc.table_schema = $1 AND tc.table_name = $2";
-
-const TABLE_COLUMNS_QUERY: &str = "SELECT cc.column_name, cc.ordinal_position, cc.data_type, pt.oid
+// Columns of multicolumn foreign keys are paired by their positions in the constraint
+const TABLE_FK_QUERY: &str = "SELECT ns.nspname AS table_schema,
+        con.conname AS constraint_name,
+        cl.relname AS table_name,
+        att.attname AS column_name,
+        fns.nspname AS foreign_table_schema,
+        fcl.relname AS foreign_table_name,
+        fatt.attname AS foreign_column_name
+    FROM pg_catalog.pg_constraint AS con
+    CROSS JOIN LATERAL unnest(con.conkey, con.confkey) WITH ORDINALITY AS k(attnum, fattnum, position)
+    JOIN pg_catalog.pg_class AS cl ON cl.oid = con.conrelid
+    JOIN pg_catalog.pg_namespace AS ns ON ns.oid = cl.relnamespace
+    JOIN pg_catalog.pg_attribute AS att ON att.attrelid = con.conrelid AND att.attnum = k.attnum
+    JOIN pg_catalog.pg_class AS fcl ON fcl.oid = con.confrelid
+    JOIN pg_catalog.pg_namespace AS fns ON fns.oid = fcl.relnamespace
+    JOIN pg_catalog.pg_attribute AS fatt ON fatt.attrelid = con.confrelid AND fatt.attnum = k.fattnum
+    WHERE con.contype = 'f' AND ns.nspname = $1 AND cl.relname = $2
+    ORDER BY con.conname, k.position";
+
+const TABLE_COLUMNS_QUERY: &str =
+    "SELECT cc.column_name, cc.ordinal_position, cc.data_type, pt.oid,
+        cc.character_maximum_length, cc.numeric_precision, cc.numeric_scale, cc.is_nullable
     FROM information_schema.columns as cc
     JOIN pg_catalog.pg_namespace as pn
     ON cc.udt_schema = pn.nspname
@@ -14,4 +14,50 @@ pub fn setval_query(&self, last_value: i64) -> String {
     pub fn last_value_query(&self) -> String {
         format!("SELECT last_value FROM {}", self.full_name)
     }
+
+    /// Returns the unquoted schema and name (`full_name` is qualified and quoted when needed)
+    pub fn schema_and_name(&self) -> (String, String) {
+        let mut parts = vec![String::new()];
+        let mut quoted = false;
+        let mut chars = self.full_name.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    parts.last_mut().unwrap().push('"');
+                }
+                '"' => quoted = !quoted,
+                '.' if !quoted => parts.push(String::new()),
+                _ => parts.last_mut().unwrap().push(ch),
+            }
+        }
+
+        let name = parts.pop().unwrap_or_default();
+        (parts.pop().unwrap_or_default(), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_and_name() {
+        let names = |full_name: &str| {
+            PgSequence {
+                full_name: String::from(full_name),
+            }
+            .schema_and_name()
+        };
+
+        assert_eq!(
+            names("public.users_id_seq"),
+            (String::from("public"), String::from("users_id_seq"))
+        );
+        assert_eq!(
+            names(r#""Some.Schema"."Seq""Name""#),
+            (String::from("Some.Schema"), String::from(r#"Seq"Name"#))
+        );
+        assert_eq!(names("seq"), (String::new(), String::from("seq")));
+    }
 }
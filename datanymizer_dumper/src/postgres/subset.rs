@@ -0,0 +1,419 @@
+//! Referentially complete subsets.
+//!
+//! Rows of root tables are selected by conditions or sampled, then the foreign key graph is followed:
+//! referencing rows are added (if `children` is enabled), and then all referenced rows are added.
+//! Row ids (`ctid`) of the subset are kept in temporary tables of the dump transaction.
+//! Tables that are connected to root tables by foreign keys are dumped with their subset rows only
+//! (so they can be empty), other tables are dumped as usual. Tables whose data isn't dumped
+//! (see `filter`) are skipped.
+
+use super::{query_wrapper::QueryWrapper, table::PgTable};
+use crate::Table;
+use anyhow::{bail, Result};
+use datanymizer_engine::{Settings, Subset, SubsetRoot};
+use std::collections::{HashMap, VecDeque};
+
+/// Subset rows of the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubsetRows {
+    /// The temporary table with row ids
+    pub table: String,
+    pub count: u64,
+}
+
+impl SubsetRows {
+    /// SQL condition for rows of the table
+    pub fn condition(&self) -> String {
+        format!("ctid IN (SELECT row_id FROM {})", self.table)
+    }
+}
+
+/// The foreign key (all columns of the constraint) between tables (indexes in the table list)
+#[derive(Debug, PartialEq, Eq)]
+struct Reference {
+    table: usize,
+    columns: Vec<String>,
+    parent: usize,
+    parent_columns: Vec<String>,
+}
+
+/// Computes the subset with `qw` (it must be the transaction that dumps tables)
+/// and sets subset rows of tables
+pub(super) fn apply<F: Fn(String)>(
+    cfg: &Subset,
+    settings: &Settings,
+    tables: &mut [PgTable],
+    qw: &mut QueryWrapper,
+    debug: F,
+) -> Result<()> {
+    let roots: Vec<(usize, &SubsetRoot)> = tables
+        .iter()
+        .enumerate()
+        .filter_map(|(i, t)| cfg.root(&t.get_names()).map(|root| (i, root)))
+        .collect();
+    for root in &cfg.roots {
+        if !roots.iter().any(|(_, r)| r.table == root.table) {
+            bail!("The subset root table `{}` doesn't exist", root.table);
+        }
+    }
+
+    // Tables without dumped data don't affect the subset
+    let dumped: Vec<bool> = tables
+        .iter()
+        .map(|t| settings.filter.filter_table(&t.get_full_name()))
+        .collect();
+    let roots: Vec<_> = roots.into_iter().filter(|(i, _)| dumped[*i]).collect();
+    let mut references = references(tables);
+    references.retain(|r| dumped[r.table] && dumped[r.parent]);
+
+    let included = connected(tables.len(), roots.iter().map(|(i, _)| *i), &references);
+    for &i in &included {
+        let query = settings
+            .find_table(&tables[i].get_names())
+            .and_then(|t| t.query.as_ref());
+        if query.is_some_and(|q| q.limit.is_some() || q.dump_condition.is_some()) {
+            bail!(
+                "The `{}` table is in the subset, so it can't have `limit` or `dump_condition` \
+                (use `subset.roots` instead)",
+                tables[i].get_full_name()
+            );
+        }
+    }
+
+    let rows: HashMap<usize, String> = included
+        .iter()
+        .map(|&i| (i, format!("pg_temp.datanymizer_subset_{}", i)))
+        .collect();
+    for table in rows.values() {
+        qw.batch_execute(&format!(
+            "CREATE TEMPORARY TABLE {} (row_id tid PRIMARY KEY)",
+            table
+        ))?;
+    }
+
+    for (i, root) in &roots {
+        qw.execute(&root_query(&tables[*i], root, &rows[i]))?;
+    }
+
+    let references: Vec<_> = references
+        .into_iter()
+        .filter(|r| rows.contains_key(&r.table))
+        .collect();
+    if cfg.children {
+        let queries: Vec<_> = references
+            .iter()
+            .map(|r| {
+                reference_query(
+                    (&tables[r.table], &r.columns, &rows[&r.table]),
+                    (&tables[r.parent], &r.parent_columns, &rows[&r.parent]),
+                )
+            })
+            .collect();
+        follow(qw, &queries)?;
+    }
+    let queries: Vec<_> = references
+        .iter()
+        .map(|r| {
+            reference_query(
+                (&tables[r.parent], &r.parent_columns, &rows[&r.parent]),
+                (&tables[r.table], &r.columns, &rows[&r.table]),
+            )
+        })
+        .collect();
+    follow(qw, &queries)?;
+
+    for i in included {
+        let table = rows[&i].clone();
+        let count: i64 = qw
+            .query_one(format!("SELECT count(*) FROM {}", table).as_str(), &[])?
+            .get(0);
+        debug(format!(
+            "[Subset: {}] {} rows",
+            tables[i].get_full_name(),
+            count
+        ));
+        tables[i].subset = Some(SubsetRows {
+            table,
+            count: count as u64,
+        });
+    }
+
+    Ok(())
+}
+
+/// Foreign keys grouped by constraints (references to other schemas or filtered tables are skipped)
+fn references(tables: &[PgTable]) -> Vec<Reference> {
+    let indexes: HashMap<String, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, t)| (t.get_full_name(), i))
+        .collect();
+
+    let mut references = vec![];
+    for (i, table) in tables.iter().enumerate() {
+        let mut constraints: Vec<(&String, Reference)> = vec![];
+        for fk in &table.foreign_keys {
+            let parent = format!("{}.{}", fk.foreign_table_schema, fk.foreign_table_name);
+            let parent = match indexes.get(&parent) {
+                Some(&parent) => parent,
+                None => continue,
+            };
+
+            let position = constraints
+                .iter()
+                .position(|(name, _)| *name == &fk.constraint_name);
+            let reference = match position {
+                Some(position) => &mut constraints[position].1,
+                None => {
+                    constraints.push((
+                        &fk.constraint_name,
+                        Reference {
+                            table: i,
+                            columns: vec![],
+                            parent,
+                            parent_columns: vec![],
+                        },
+                    ));
+                    &mut constraints.last_mut().unwrap().1
+                }
+            };
+            reference.columns.push(fk.column_name.clone());
+            reference
+                .parent_columns
+                .push(fk.foreign_column_name.clone());
+        }
+        references.extend(constraints.into_iter().map(|(_, r)| r));
+    }
+
+    references
+}
+
+/// Tables that are connected to roots (in any direction), in the table order
+fn connected<I>(count: usize, roots: I, references: &[Reference]) -> Vec<usize>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut included = vec![false; count];
+    let mut queue: VecDeque<usize> = roots.into_iter().collect();
+    while let Some(i) = queue.pop_front() {
+        if included[i] {
+            continue;
+        }
+        included[i] = true;
+        for r in references {
+            if r.table == i {
+                queue.push_back(r.parent);
+            } else if r.parent == i {
+                queue.push_back(r.table);
+            }
+        }
+    }
+
+    (0..count).filter(|&i| included[i]).collect()
+}
+
+/// Runs queries until they add no rows (references can be cyclic)
+fn follow(qw: &mut QueryWrapper, queries: &[String]) -> Result<()> {
+    let mut added = 1;
+    while added > 0 {
+        added = 0;
+        for query in queries {
+            added += qw.execute(query.as_str())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn root_query(table: &PgTable, root: &SubsetRoot, rows: &str) -> String {
+    let sample = root.percent.map_or(String::new(), |percent| {
+        format!(" TABLESAMPLE BERNOULLI ({}) REPEATABLE (0)", percent)
+    });
+    let condition = root
+        .condition
+        .as_ref()
+        .map_or(String::new(), |c| format!(" WHERE ({})", c));
+
+    format!(
+        "INSERT INTO {} SELECT ctid FROM {}{}{} ON CONFLICT DO NOTHING",
+        rows,
+        table.quoted_full_name(),
+        sample,
+        condition
+    )
+}
+
+/// Adds rows of `to` that match subset rows of `from` by the columns
+fn reference_query(
+    (to, to_columns, to_rows): (&PgTable, &[String], &str),
+    (from, from_columns, from_rows): (&PgTable, &[String], &str),
+) -> String {
+    let columns = |alias: &str, columns: &[String]| {
+        columns
+            .iter()
+            .map(|c| format!("{}.{}", alias, PgTable::quote_identifier(c)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    format!(
+        "INSERT INTO {} SELECT t.ctid FROM {} AS t WHERE ({}) IN \
+        (SELECT {} FROM {} AS f WHERE f.ctid IN (SELECT row_id FROM {})) \
+        ON CONFLICT DO NOTHING",
+        to_rows,
+        to.quoted_full_name(),
+        columns("t", to_columns),
+        columns("f", from_columns),
+        from.quoted_full_name(),
+        from_rows
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgres::foreign_key::PgForeignKey;
+
+    fn fk(
+        table: &str,
+        constraint: &str,
+        column: &str,
+        parent: &str,
+        parent_column: &str,
+    ) -> PgForeignKey {
+        PgForeignKey {
+            table_schema: String::from("public"),
+            table_name: String::from(table),
+            constraint_name: String::from(constraint),
+            column_name: String::from(column),
+            foreign_table_schema: String::from("public"),
+            foreign_table_name: String::from(parent),
+            foreign_column_name: String::from(parent_column),
+        }
+    }
+
+    fn table(name: &str, foreign_keys: Vec<PgForeignKey>) -> PgTable {
+        let mut table = PgTable::new(String::from(name), String::from("public"));
+        table.set_foreign_keys(foreign_keys);
+        table
+    }
+
+    fn tables() -> Vec<PgTable> {
+        vec![
+            table("countries", vec![]),
+            table(
+                "users",
+                vec![fk(
+                    "users",
+                    "users_country_fkey",
+                    "country_id",
+                    "countries",
+                    "id",
+                )],
+            ),
+            table(
+                "orders",
+                vec![
+                    fk("orders", "orders_user_fkey", "user_id", "users", "id"),
+                    fk("orders", "orders_item_fkey", "item_id", "items", "id"),
+                    fk(
+                        "orders",
+                        "orders_item_fkey",
+                        "item_version",
+                        "items",
+                        "version",
+                    ),
+                    fk(
+                        "orders",
+                        "orders_archive_fkey",
+                        "archive_id",
+                        "archive",
+                        "id",
+                    ),
+                ],
+            ),
+            table("items", vec![]),
+            table("settings", vec![]),
+        ]
+    }
+
+    #[test]
+    fn grouped_references() {
+        assert_eq!(
+            references(&tables()),
+            vec![
+                Reference {
+                    table: 1,
+                    columns: vec![String::from("country_id")],
+                    parent: 0,
+                    parent_columns: vec![String::from("id")],
+                },
+                Reference {
+                    table: 2,
+                    columns: vec![String::from("user_id")],
+                    parent: 1,
+                    parent_columns: vec![String::from("id")],
+                },
+                Reference {
+                    table: 2,
+                    columns: vec![String::from("item_id"), String::from("item_version")],
+                    parent: 3,
+                    parent_columns: vec![String::from("id"), String::from("version")],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn connected_tables() {
+        let tables = tables();
+        let references = references(&tables);
+
+        assert_eq!(connected(tables.len(), [1], &references), vec![0, 1, 2, 3]);
+        assert_eq!(connected(tables.len(), [3], &references), vec![0, 1, 2, 3]);
+        assert_eq!(connected(tables.len(), [4], &references), vec![4]);
+    }
+
+    #[test]
+    fn root_queries() {
+        let table = table("users", vec![]);
+        let rows = "pg_temp.datanymizer_subset_1";
+
+        assert_eq!(
+            root_query(&table, &SubsetRoot::new("users"), rows),
+            "INSERT INTO pg_temp.datanymizer_subset_1 SELECT ctid FROM \"public\".\"users\" \
+            ON CONFLICT DO NOTHING"
+        );
+        assert_eq!(
+            root_query(
+                &table,
+                &SubsetRoot::new("users")
+                    .with_percent(2.5)
+                    .with_condition("id > 10"),
+                rows
+            ),
+            "INSERT INTO pg_temp.datanymizer_subset_1 SELECT ctid FROM \"public\".\"users\" \
+            TABLESAMPLE BERNOULLI (2.5) REPEATABLE (0) WHERE (id > 10) ON CONFLICT DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn reference_queries() {
+        let orders = table("orders", vec![]);
+        let items = table("items", vec![]);
+        let columns = vec![String::from("item_id"), String::from("item_version")];
+        let parent_columns = vec![String::from("id"), String::from("version")];
+
+        assert_eq!(
+            reference_query(
+                (&items, &parent_columns, "pg_temp.datanymizer_subset_3"),
+                (&orders, &columns, "pg_temp.datanymizer_subset_2"),
+            ),
+            "INSERT INTO pg_temp.datanymizer_subset_3 SELECT t.ctid FROM \"public\".\"items\" AS t \
+            WHERE (t.\"id\", t.\"version\") IN (SELECT f.\"item_id\", f.\"item_version\" \
+            FROM \"public\".\"orders\" AS f \
+            WHERE f.ctid IN (SELECT row_id FROM pg_temp.datanymizer_subset_2)) \
+            ON CONFLICT DO NOTHING"
+        );
+    }
+}
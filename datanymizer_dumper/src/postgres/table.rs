@@ -1,4 +1,7 @@
-use super::{column::PgColumn, foreign_key::PgForeignKey, row::PgRow, sequence::PgSequence};
+use super::{
+    column::PgColumn, foreign_key::PgForeignKey, row::PgRow, sequence::PgSequence,
+    subset::SubsetRows,
+};
 use crate::Table;
 use anyhow::{anyhow, Result};
 use datanymizer_engine::{Query as QueryCfg, Table as TableCfg};
@@ -17,6 +20,8 @@ pub struct PgTable {
     pub foreign_keys: Vec<PgForeignKey>,
     column_indexes: HashMap<String, usize>,
     pub size: i64,
+    /// Only these rows are dumped (if the table is in the subset)
+    pub subset: Option<SubsetRows>,
 }
 
 impl PartialEq for PgTable {
@@ -72,6 +77,19 @@ fn get_dep_table_names(&self) -> Vec<String> {
             .map(|fk| format!("{}.{}", fk.foreign_table_schema, fk.foreign_table_name))
             .collect()
     }
+
+    fn get_foreign_key_columns(&self) -> Vec<(String, String, String)> {
+        self.foreign_keys
+            .iter()
+            .map(|fk| {
+                (
+                    fk.column_name.clone(),
+                    format!("{}.{}", fk.foreign_table_schema, fk.foreign_table_name),
+                    fk.foreign_column_name.clone(),
+                )
+            })
+            .collect()
+    }
 }
 
 impl PgTable {
@@ -84,6 +102,7 @@ pub fn new(tablename: String, schemaname: String) -> Self {
             foreign_keys: vec![],
             column_indexes: HashMap::new(),
             size: 0,
+            subset: None,
         }
     }
 
@@ -96,8 +115,17 @@ pub fn quote_table_name(name: &str) -> Result<String> {
         }
     }
 
+    /// Quotes the identifier (e.g. a column name), quotes inside it are doubled
+    pub fn quote_identifier(name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
     pub fn quoted_full_name(&self) -> String {
-        format!(r#""{}"."{}""#, self.schemaname, self.tablename)
+        format!(
+            "{}.{}",
+            Self::quote_identifier(&self.schemaname),
+            Self::quote_identifier(&self.tablename)
+        )
     }
 
     pub fn set_columns(&mut self, columns: Vec<PgColumn>) {
@@ -152,8 +180,25 @@ pub fn untransformed_query_to(
         }
     }
 
+    /// Query for the first `limit` rows that are transformed in the dump
+    pub fn preview_query_to(&self, cfg: &TableCfg, limit: u64) -> String {
+        match &cfg.query {
+            Some(q) => self.query_with_select(
+                vec![
+                    q.dump_condition.as_ref().map(|c| format!("({})", c)),
+                    q.transform_condition.as_ref().map(|c| format!("({})", c)),
+                ],
+                Some(q.limit.map_or(limit, |l| limit.min(l as u64))),
+            ),
+            None => self.query_with_select(vec![], Some(limit)),
+        }
+    }
+
     pub fn count_of_query_to(&self, cfg: Option<&TableCfg>) -> u64 {
-        let number = self.get_size() as u64;
+        let number = match &self.subset {
+            Some(rows) => rows.count,
+            None => self.get_size() as u64,
+        };
 
         cfg.and_then(|c| c.query.as_ref())
             .and_then(|q| q.limit)
@@ -199,7 +244,9 @@ fn query_unless_already_dumped(
     }
 
     fn default_query(&self) -> String {
-        if !self.quoted_columns().is_empty() {
+        if self.subset.is_some() {
+            self.query_with_select(vec![], None)
+        } else if !self.quoted_columns().is_empty() {
             format!(
                 "COPY {}({}) TO STDOUT",
                 self.quoted_full_name(),
@@ -210,7 +257,8 @@ fn default_query(&self) -> String {
         }
     }
 
-    fn query_with_select(&self, cs: Vec<Option<String>>, limit: Option<u64>) -> String {
+    fn query_with_select(&self, mut cs: Vec<Option<String>>, limit: Option<u64>) -> String {
+        cs.insert(0, self.subset.as_ref().map(|rows| rows.condition()));
         format!(
             "COPY (SELECT * FROM {}{}{}) TO STDOUT",
             self.quoted_full_name(),
@@ -235,7 +283,7 @@ fn sql_limit(limit: Option<u64>) -> String {
     fn quoted_columns(&self) -> Vec<String> {
         self.get_columns_names()
             .into_iter()
-            .map(|x| format!("\"{}\"", x))
+            .map(|x| Self::quote_identifier(&x))
             .collect()
     }
 }
@@ -257,6 +305,7 @@ fn new_column(position: i32, name: &str) -> PgColumn {
             name: String::from(name),
             data_type: String::new(),
             inner_type: Some(0),
+            column_type: None,
         }
     }
 
@@ -282,7 +331,16 @@ fn quote_table_name() {
     #[test]
     fn quoted_full_name() {
         let table = PgTable::new(String::from("name"), String::from("public2"));
-        assert_eq!(table.quoted_full_name(), r#""public2"."name""#)
+        assert_eq!(table.quoted_full_name(), r#""public2"."name""#);
+
+        let table = PgTable::new(String::from(r#"na"me"#), String::from(r#"pub"lic"#));
+        assert_eq!(table.quoted_full_name(), r#""pub""lic"."na""me""#)
+    }
+
+    #[test]
+    fn quote_identifier() {
+        assert_eq!(PgTable::quote_identifier("name"), r#""name""#);
+        assert_eq!(PgTable::quote_identifier(r#"na"me"#), r#""na""me""#);
     }
 
     #[test]
@@ -375,6 +433,38 @@ fn no_query() {
             assert_eq!(table().count_of_query_to(Some(&cfg)), 1000);
         }
 
+        #[test]
+        fn subset() {
+            let mut table = table();
+            table.subset = Some(SubsetRows {
+                table: String::from("pg_temp.datanymizer_subset_0"),
+                count: 10,
+            });
+            let subset = "ctid IN (SELECT row_id FROM pg_temp.datanymizer_subset_0)";
+
+            assert_eq!(
+                table.untransformed_query_to(None, 0).unwrap(),
+                format!(
+                    "COPY (SELECT * FROM \"public\".\"some_table\" WHERE {}) TO STDOUT",
+                    subset
+                )
+            );
+            assert_eq!(table.count_of_query_to(None), 10);
+
+            let cfg = cfg(Some(QueryCfg {
+                limit: None,
+                dump_condition: None,
+                transform_condition: Some("col1 = 'value'".to_string()),
+            }));
+            assert_eq!(
+                table.transformed_query_to(Some(&cfg), 0).unwrap(),
+                format!(
+                    "COPY (SELECT * FROM \"public\".\"some_table\" WHERE {} AND (col1 = 'value')) TO STDOUT",
+                    subset
+                )
+            );
+        }
+
         #[test]
         fn only_limit() {
             let cfg = cfg(Some(QueryCfg {
@@ -447,6 +537,25 @@ fn all_query_params() {
             assert_eq!(table().count_of_query_to(Some(&cfg)), 500);
         }
 
+        #[test]
+        fn preview() {
+            assert_eq!(
+                table().preview_query_to(&cfg(None), 20),
+                "COPY (SELECT * FROM \"public\".\"some_table\" LIMIT 20) TO STDOUT"
+            );
+
+            let cfg = cfg(Some(QueryCfg {
+                limit: Some(10),
+                dump_condition: Some("col1 = 'value'".to_string()),
+                transform_condition: Some("col2 <> 'other_value'".to_string()),
+            }));
+            assert_eq!(
+                table().preview_query_to(&cfg, 20),
+                "COPY (SELECT * FROM \"public\".\"some_table\" \
+                WHERE (col1 = 'value') AND (col2 <> 'other_value') LIMIT 10) TO STDOUT"
+            );
+        }
+
         mod already_dumped {
             use super::*;
 
@@ -0,0 +1,81 @@
+use super::connector::Connector;
+use anyhow::{anyhow, Result};
+use postgres::Client;
+use std::sync::Mutex;
+
+/// psql meta-commands that `pg_dump` writes around the plain SQL
+const META_COMMANDS: [&str; 2] = ["\\restrict ", "\\unrestrict "];
+
+/// The target database: the schema and the table data are loaded to it directly
+pub(super) struct Target {
+    connector: Connector,
+    /// Idle connections (there can be several of them with parallel jobs)
+    clients: Mutex<Vec<Client>>,
+}
+
+impl Target {
+    pub fn new(connector: Connector) -> Self {
+        Self {
+            connector,
+            clients: Mutex::new(vec![]),
+        }
+    }
+
+    /// Executes the plain SQL from `pg_dump` in a separate connection
+    /// (the schema SQL changes session settings, e.g. `search_path`)
+    pub fn execute_schema(&self, output: &[u8]) -> Result<()> {
+        let sql = schema_sql(output)?;
+        self.connector.connect()?.client.batch_execute(&sql)?;
+        Ok(())
+    }
+
+    /// Runs `f` with an idle connection (or with a new one).
+    /// The connection is reused only if `f` succeeds.
+    pub fn with_client<T>(&self, f: impl FnOnce(&mut Client) -> Result<T>) -> Result<T> {
+        let client = self.lock()?.pop();
+        let mut client = match client {
+            Some(client) => client,
+            None => self.connector.connect()?.client,
+        };
+
+        let result = f(&mut client)?;
+        self.lock()?.push(client);
+        Ok(result)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Vec<Client>>> {
+        self.clients.lock().map_err(|_| anyhow!("Sync error"))
+    }
+}
+
+fn schema_sql(output: &[u8]) -> Result<String> {
+    let output = std::str::from_utf8(output)?;
+    Ok(output
+        .lines()
+        .filter(|line| !META_COMMANDS.iter().any(|cmd| line.starts_with(cmd)))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_meta_commands() {
+        let output = "\\restrict abc\n\
+            SET client_encoding = 'UTF8';\n\
+            CREATE FUNCTION f() RETURNS text AS $$\n\
+            \\restricted\n\
+            $$ LANGUAGE sql;\n\
+            \\unrestrict abc\n";
+
+        assert_eq!(
+            schema_sql(output.as_bytes()).unwrap(),
+            "SET client_encoding = 'UTF8';\n\
+            CREATE FUNCTION f() RETURNS text AS $$\n\
+            \\restricted\n\
+            $$ LANGUAGE sql;"
+        );
+    }
+}
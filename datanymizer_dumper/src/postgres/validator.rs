@@ -0,0 +1,102 @@
+use super::{connector::Connection, schema_inspector::PgSchemaInspector, table::PgTable};
+use crate::{SchemaInspector, Table};
+use anyhow::Result;
+use datanymizer_engine::validation::{ColumnTypes, ConfigPath, Problem, Schema, Validation};
+
+/// Checks the config against the database (see [datanymizer_engine::validation])
+pub struct PgValidator;
+
+impl PgValidator {
+    /// Columns of all tables of the database
+    pub fn schema(&self, connection: &mut Connection) -> Result<Schema> {
+        let tables = PgSchemaInspector.get_tables(connection)?;
+        Ok(Self::schema_of(&tables))
+    }
+
+    /// Checks tables, columns and SQL conditions of the config (and warns about column types)
+    pub fn validate(&self, connection: &mut Connection, validation: &mut Validation) -> Result<()> {
+        let tables = PgSchemaInspector.get_tables(connection)?;
+        validation.check_schema(&Self::schema_of(&tables));
+        validation.check_types(&Self::types_of(&tables));
+
+        let problems = self.check_conditions(connection, validation, &tables)?;
+        for problem in problems {
+            validation.add(problem);
+        }
+
+        Ok(())
+    }
+
+    fn schema_of(tables: &[PgTable]) -> Schema {
+        tables
+            .iter()
+            .map(|table| (table.get_full_name(), table.get_columns_names()))
+            .collect()
+    }
+
+    fn types_of(tables: &[PgTable]) -> ColumnTypes {
+        tables
+            .iter()
+            .map(|table| (table.get_full_name(), table.get_column_types()))
+            .collect()
+    }
+
+    /// Plans a query with every `dump_condition` and `transform_condition`
+    fn check_conditions(
+        &self,
+        connection: &mut Connection,
+        validation: &Validation,
+        tables: &[PgTable],
+    ) -> Result<Vec<Problem>> {
+        let settings = match &validation.settings {
+            Some(settings) => settings,
+            None => return Ok(vec![]),
+        };
+
+        let mut problems = vec![];
+        for (i, table_cfg) in settings.tables.iter().enumerate() {
+            let query = match &table_cfg.query {
+                Some(query) => query,
+                None => continue,
+            };
+            // Unknown tables are reported by the schema check
+            let table = match tables
+                .iter()
+                .find(|t| t.get_names().contains(&table_cfg.name))
+            {
+                Some(table) => table,
+                None => continue,
+            };
+
+            let conditions = [
+                ("dump_condition", &query.dump_condition),
+                ("transform_condition", &query.transform_condition),
+            ];
+            for (key, condition) in conditions {
+                if let Some(condition) = condition {
+                    let sql = format!(
+                        "EXPLAIN SELECT * FROM {} WHERE ({})",
+                        table.quoted_full_name(),
+                        condition
+                    );
+                    if let Err(e) = connection.client.execute(sql.as_str(), &[]) {
+                        let message = match e.as_db_error() {
+                            Some(db_error) => db_error.message().to_string(),
+                            None => return Err(e.into()),
+                        };
+                        problems.push(Problem::new(
+                            ConfigPath::root()
+                                .key("tables")
+                                .index(i)
+                                .key("query")
+                                .key(key),
+                            format!("Invalid SQL condition: {}", message),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(problems)
+    }
+}
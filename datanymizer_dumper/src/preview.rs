@@ -0,0 +1,146 @@
+//! Samples of original and transformed values of columns with rules (nothing is dumped).
+
+/// Longer values are truncated in the table
+const MAX_WIDTH: usize = 40;
+/// Shown instead of NULL values
+const NULL_MARKER: &str = "<null>";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Preview {
+    /// Full table name
+    pub table: String,
+    /// Columns with rules (in the table order)
+    pub columns: Vec<String>,
+    /// Original and transformed values of the columns in every row (`None` is NULL)
+    pub rows: Vec<Vec<(Option<String>, Option<String>)>>,
+}
+
+impl Preview {
+    /// Renders a table with original and transformed values side by side
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!("{} ({} rows)", self.table, self.rows.len())];
+        if self.columns.is_empty() {
+            return lines.remove(0);
+        }
+
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .flat_map(|c| [c.clone(), format!("{} (transformed)", c)])
+            .collect();
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .flat_map(|(original, transformed)| [cell(original), cell(transformed)])
+                    .collect()
+            })
+            .collect();
+
+        let widths: Vec<usize> = (0..header.len())
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([header[i].chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let line = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join(" | ")
+                .trim_end()
+                .to_string()
+        };
+
+        lines.push(String::new());
+        lines.push(line(&header));
+        lines.push(
+            widths
+                .iter()
+                .map(|&width| "-".repeat(width))
+                .collect::<Vec<_>>()
+                .join("-+-"),
+        );
+        lines.extend(rows.iter().map(|row| line(row)));
+
+        lines.join("\n")
+    }
+}
+
+/// Control chars (e.g. line breaks) are escaped, so every row takes one line
+fn cell(value: &Option<String>) -> String {
+    let value: String = match value {
+        Some(value) => value
+            .chars()
+            .map(|c| {
+                if c.is_control() {
+                    c.escape_default().to_string()
+                } else {
+                    c.to_string()
+                }
+            })
+            .collect(),
+        None => return String::from(NULL_MARKER),
+    };
+    if value.chars().count() <= MAX_WIDTH {
+        return value;
+    }
+    let mut truncated: String = value.chars().take(MAX_WIDTH - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(values: &[(&str, Option<&str>)]) -> Vec<(Option<String>, Option<String>)> {
+        values
+            .iter()
+            .map(|(o, t)| (Some(String::from(*o)), t.map(String::from)))
+            .collect()
+    }
+
+    #[test]
+    fn render() {
+        let preview = Preview {
+            table: String::from("public.users"),
+            columns: vec![String::from("name"), String::from("email")],
+            rows: vec![
+                row(&[
+                    ("John", Some("Fausto")),
+                    ("john@example.com", Some("fausto@mail.org")),
+                ]),
+                row(&[("Ann\nLee", Some("Alice")), (&"a".repeat(50), None)]),
+            ],
+        };
+
+        let expected = format!(
+            "public.users (2 rows)\n\
+            \n\
+            name     | name (transformed) | email                                    | email (transformed)\n\
+            ---------+--------------------+------------------------------------------+--------------------\n\
+            John     | Fausto             | john@example.com                         | fausto@mail.org\n\
+            Ann\\nLee | Alice              | {}… | <null>",
+            "a".repeat(39)
+        );
+        assert_eq!(preview.render(), expected);
+    }
+
+    #[test]
+    fn render_without_columns() {
+        let preview = Preview {
+            table: String::from("public.users"),
+            columns: vec![],
+            rows: vec![vec![], vec![]],
+        };
+
+        assert_eq!(preview.render(), "public.users (2 rows)");
+    }
+}
@@ -0,0 +1,729 @@
+//! Detection of columns with personal data (PII) and drafting of the config for them.
+//! The detection is heuristic: it uses column names, types and samples of values.
+
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt, net::IpAddr};
+
+/// The part of sampled values that should look like PII
+const VALUES_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pii {
+    Email,
+    Phone,
+    FirstName,
+    LastName,
+    PersonName,
+    Ip,
+    CardNumber,
+}
+
+impl Pii {
+    /// Checked in this order by column names
+    const ALL: [Pii; 7] = [
+        Pii::Email,
+        Pii::Phone,
+        Pii::FirstName,
+        Pii::LastName,
+        Pii::PersonName,
+        Pii::Ip,
+        Pii::CardNumber,
+    ];
+
+    /// These kinds can be detected by values only
+    const BY_VALUES: [Pii; 4] = [Pii::Email, Pii::Ip, Pii::CardNumber, Pii::Phone];
+
+    /// The suggested rule (YAML lines)
+    fn rule(self) -> &'static [&'static str] {
+        match self {
+            Self::Email => &["email: {}"],
+            Self::Phone => &["phone: {}"],
+            Self::FirstName => &["first_name: {}"],
+            Self::LastName => &["last_name: {}"],
+            Self::PersonName => &["person_name: {}"],
+            Self::Ip => &["ip: {}"],
+            Self::CardNumber => &["credit_card: {}"],
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Email => "emails",
+            Self::Phone => "phones",
+            Self::FirstName => "first names",
+            Self::LastName => "last names",
+            Self::PersonName => "person names",
+            Self::Ip => "IP addresses",
+            Self::CardNumber => "card numbers",
+        }
+    }
+
+    fn name_matches(self, name: &str) -> bool {
+        let words = words(name);
+        let has = |w: &str| words.iter().any(|word| word == w);
+        let joined = words.concat();
+
+        match self {
+            Self::Email => has("email") || has("mail") || joined.contains("email"),
+            Self::Phone => {
+                [
+                    "phone",
+                    "mobile",
+                    "cell",
+                    "tel",
+                    "telephone",
+                    "msisdn",
+                    "fax",
+                ]
+                .iter()
+                .any(|w| has(w))
+                    || joined.ends_with("phone")
+            }
+            Self::FirstName => {
+                ["firstname", "givenname", "forename", "fname"].contains(&joined.as_str())
+                    || joined.ends_with("firstname")
+            }
+            Self::LastName => {
+                ["lastname", "surname", "familyname", "lname"].contains(&joined.as_str())
+                    || joined.ends_with("lastname")
+            }
+            Self::PersonName => {
+                has("name")
+                    && words.len() > 1
+                    && [
+                        "full", "person", "contact", "customer", "client", "owner", "patient",
+                        "employee", "holder",
+                    ]
+                    .iter()
+                    .any(|w| has(w))
+                    || joined == "fullname"
+            }
+            Self::Ip => has("ip") || joined.ends_with("ipaddress"),
+            Self::CardNumber => {
+                has("pan")
+                    || joined.contains("cardnumber")
+                    || joined.contains("creditcard")
+                    || joined.contains("ccnumber")
+                    || has("card") && (has("no") || has("num"))
+            }
+        }
+    }
+
+    /// `by_name`: the name is already detected, so values can be checked not so strictly
+    fn value_matches(self, value: &str, by_name: bool) -> bool {
+        let value = value.trim();
+        match self {
+            Self::Email => is_email(value),
+            Self::Phone => is_phone(value, !by_name),
+            Self::FirstName | Self::LastName => is_name(value, 1),
+            Self::PersonName => is_name(value, 3),
+            Self::Ip => is_ip(value),
+            Self::CardNumber => is_card_number(value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        };
+        f.write_str(s)
+    }
+}
+
+/// The detected kind of PII with an explanation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Detection {
+    pub pii: Pii,
+    pub confidence: Confidence,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonField {
+    pub selector: String,
+    pub detection: Detection,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnRule {
+    Pii(Detection),
+    /// PII in fields of JSON values
+    Json(Vec<JsonField>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnScan {
+    pub name: String,
+    pub rule: ColumnRule,
+}
+
+impl ColumnScan {
+    pub fn confidence(&self) -> Confidence {
+        match &self.rule {
+            ColumnRule::Pii(d) => d.confidence,
+            ColumnRule::Json(fields) => fields
+                .iter()
+                .map(|f| f.detection.confidence)
+                .max()
+                .unwrap_or(Confidence::Low),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableScan {
+    /// The full table name (with the schema)
+    pub name: String,
+    pub columns: Vec<ColumnScan>,
+}
+
+/// Whether values of the column type are worth sampling
+pub fn is_scannable(data_type: &str) -> bool {
+    matches!(
+        data_type,
+        "text" | "character varying" | "character" | "USER-DEFINED" | "json" | "jsonb" | "inet"
+    )
+}
+
+/// Detects PII in the column by its name, type and sampled values (not null)
+pub fn scan_column(name: &str, data_type: &str, samples: &[String]) -> Option<ColumnScan> {
+    if !is_scannable(data_type) {
+        return None;
+    }
+
+    let is_json = matches!(data_type, "json" | "jsonb")
+        || !samples.is_empty() && samples.iter().all(|s| is_json_document(s));
+    let rule = if is_json {
+        let fields = scan_json(samples);
+        if fields.is_empty() {
+            return None;
+        }
+        ColumnRule::Json(fields)
+    } else {
+        ColumnRule::Pii(detect(name, samples)?)
+    };
+
+    Some(ColumnScan {
+        name: String::from(name),
+        rule,
+    })
+}
+
+/// Detects PII by the name (of a column or a JSON field) and by values
+pub fn detect(name: &str, samples: &[String]) -> Option<Detection> {
+    let samples: Vec<_> = samples.iter().filter(|s| !s.trim().is_empty()).collect();
+    let share = |pii: Pii, by_name: bool| {
+        if samples.is_empty() {
+            None
+        } else {
+            let matched = samples
+                .iter()
+                .filter(|s| pii.value_matches(s, by_name))
+                .count();
+            Some(matched as f64 / samples.len() as f64)
+        }
+    };
+    let sampled = |share: f64| format!("{:.0}% of {} sampled values", share * 100.0, samples.len());
+
+    if let Some(pii) = Pii::ALL.into_iter().find(|pii| pii.name_matches(name)) {
+        let (confidence, reason) = match share(pii, true) {
+            Some(share) if share >= VALUES_THRESHOLD => (
+                Confidence::High,
+                format!(
+                    "the name and {} look like {}",
+                    sampled(share),
+                    pii.description()
+                ),
+            ),
+            Some(share) => (
+                Confidence::Low,
+                format!(
+                    "the name looks like {}, but only {} do",
+                    pii.description(),
+                    sampled(share)
+                ),
+            ),
+            None => (
+                Confidence::Medium,
+                format!("the name looks like {} (no values)", pii.description()),
+            ),
+        };
+        return Some(Detection {
+            pii,
+            confidence,
+            reason,
+        });
+    }
+
+    Pii::BY_VALUES
+        .into_iter()
+        .find_map(|pii| match share(pii, false) {
+            Some(share) if share >= VALUES_THRESHOLD => Some(Detection {
+                pii,
+                confidence: Confidence::Medium,
+                reason: format!("{} look like {}", sampled(share), pii.description()),
+            }),
+            _ => None,
+        })
+}
+
+/// Detects PII in string fields of JSON documents
+fn scan_json(samples: &[String]) -> Vec<JsonField> {
+    // (selector, field name) -> values
+    let mut fields: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for sample in samples {
+        if let Ok(value) = serde_json::from_str::<Value>(sample) {
+            collect_json_fields(&value, String::from("$"), "", &mut fields);
+        }
+    }
+
+    fields
+        .into_iter()
+        .filter_map(|((selector, name), values)| {
+            detect(&name, &values).map(|detection| JsonField {
+                selector,
+                detection,
+            })
+        })
+        .collect()
+}
+
+fn collect_json_fields(
+    value: &Value,
+    path: String,
+    name: &str,
+    fields: &mut BTreeMap<(String, String), Vec<String>>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                collect_json_fields(value, json_path(&path, key), key, fields);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_json_fields(item, format!("{}[*]", path), name, fields);
+            }
+        }
+        Value::String(s) if !name.is_empty() => fields
+            .entry((path, String::from(name)))
+            .or_default()
+            .push(s.clone()),
+        _ => {}
+    }
+}
+
+fn json_path(path: &str, key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}['{}']", path, key.replace('\'', "\\'"))
+    }
+}
+
+/// Makes a draft config (YAML) with a rule for every detected column.
+/// Every rule is commented with the confidence and the reason.
+pub fn draft_config(tables: &[TableScan]) -> String {
+    let mut lines = vec![
+        String::from("# A draft config made by `pg_datanymizer scan`."),
+        String::from("# The detection is heuristic: review every rule before using the config."),
+        String::from("tables:"),
+    ];
+
+    for table in tables.iter().filter(|t| !t.columns.is_empty()) {
+        lines.push(format!("  - name: {}", yaml_str(&table.name)));
+        lines.push(String::from("    rules:"));
+        for column in &table.columns {
+            match &column.rule {
+                ColumnRule::Pii(detection) => {
+                    lines.push(format!("      {}", comment(detection)));
+                    lines.push(format!("      {}:", yaml_str(&column.name)));
+                    push_rule(&mut lines, detection.pii, 8);
+                }
+                ColumnRule::Json(fields) => {
+                    lines.push(format!(
+                        "      # confidence: {} (PII in JSON fields)",
+                        column.confidence()
+                    ));
+                    lines.push(format!("      {}:", yaml_str(&column.name)));
+                    lines.push(String::from("        json:"));
+                    lines.push(String::from("          fields:"));
+                    for (i, field) in fields.iter().enumerate() {
+                        lines.push(format!("            {}", comment(&field.detection)));
+                        lines.push(format!("            - name: \"field_{}\"", i + 1));
+                        lines.push(format!(
+                            "              selector: {}",
+                            serde_json::Value::from(field.selector.as_str())
+                        ));
+                        lines.push(String::from("              quote: true"));
+                        lines.push(String::from("              rule:"));
+                        push_rule(&mut lines, field.detection.pii, 16);
+                    }
+                }
+            }
+        }
+    }
+
+    if tables.iter().all(|t| t.columns.is_empty()) {
+        lines.push(String::from("  # No personal data was detected"));
+        lines.push(String::from("  []"));
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Describes every detected column (one line per column)
+pub fn report(tables: &[TableScan]) -> String {
+    let mut lines = vec![];
+    for table in tables {
+        for column in &table.columns {
+            let details = match &column.rule {
+                ColumnRule::Pii(d) => format!("{}, {}", d.pii.description(), d.reason),
+                ColumnRule::Json(fields) => format!(
+                    "JSON fields: {}",
+                    fields
+                        .iter()
+                        .map(|f| format!("{} ({})", f.selector, f.detection.pii.description()))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+            lines.push(format!(
+                "{}.{}: confidence {} ({})",
+                table.name,
+                column.name,
+                column.confidence(),
+                details
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+fn comment(detection: &Detection) -> String {
+    format!(
+        "# confidence: {} ({})",
+        detection.confidence, detection.reason
+    )
+}
+
+fn push_rule(lines: &mut Vec<String>, pii: Pii, indent: usize) {
+    for line in pii.rule() {
+        lines.push(format!("{:indent$}{}", "", line, indent = indent));
+    }
+}
+
+/// Plain YAML scalar if it is safe, a quoted one otherwise
+fn yaml_str(s: &str) -> String {
+    let plain = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if plain {
+        String::from(s)
+    } else {
+        Value::from(s).to_string()
+    }
+}
+
+/// Splits `snake_case` and `camelCase` names into lowercase words
+fn words(name: &str) -> Vec<String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        word.extend(c.to_lowercase());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn is_json_document(s: &str) -> bool {
+    let s = s.trim_start();
+    (s.starts_with('{') || s.starts_with('[')) && serde_json::from_str::<Value>(s).is_ok()
+}
+
+fn is_email(s: &str) -> bool {
+    match s.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !local.contains(char::is_whitespace)
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && domain
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '.' || c == '-')
+        }
+        None => false,
+    }
+}
+
+/// A `formatted` phone has a leading `+` or separators (otherwise it can't be told from a number)
+fn is_phone(s: &str, formatted: bool) -> bool {
+    let digits = s.chars().filter(|c| c.is_ascii_digit()).count();
+    (!formatted || s.starts_with('+') || s.contains([' ', '-', '(', '.']))
+        && (7..=15).contains(&digits)
+        && s.chars()
+            .enumerate()
+            .all(|(i, c)| c.is_ascii_digit() || " -().".contains(c) || c == '+' && i == 0)
+}
+
+fn is_name(s: &str, max_words: usize) -> bool {
+    let words: Vec<_> = s.split_whitespace().collect();
+    (1..=max_words).contains(&words.len())
+        && (max_words == 1 || words.len() > 1)
+        && words.iter().all(|w| {
+            let mut chars = w.chars();
+            chars.next().is_some_and(char::is_uppercase)
+                && chars.all(|c| c.is_lowercase() || c == '-' || c == '\'')
+        })
+}
+
+fn is_ip(s: &str) -> bool {
+    let addr = s.split_once('/').map_or(s, |(addr, _)| addr);
+    addr.parse::<IpAddr>().is_ok()
+}
+
+/// 13-19 digits (maybe with separators) with the valid Luhn checksum
+fn is_card_number(s: &str) -> bool {
+    if !s
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == ' ' || c == '-')
+    {
+        return false;
+    }
+    let digits: Vec<u32> = s.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, d2) if d2 > 9 => d2 - 9,
+            (_, d2) => d2,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datanymizer_engine::Settings;
+
+    fn samples(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| String::from(*v)).collect()
+    }
+
+    #[test]
+    fn words_of_names() {
+        assert_eq!(words("first_name"), vec!["first", "name"]);
+        assert_eq!(words("contactEmail"), vec!["contact", "email"]);
+        assert_eq!(words("IP"), vec!["ip"]);
+        assert_eq!(words("address2"), vec!["address2"]);
+    }
+
+    #[test]
+    fn by_names() {
+        let pii = |name: &str| detect(name, &[]).map(|d| d.pii);
+
+        assert_eq!(pii("email"), Some(Pii::Email));
+        assert_eq!(pii("contactEmail"), Some(Pii::Email));
+        assert_eq!(pii("mobile_phone"), Some(Pii::Phone));
+        assert_eq!(pii("first_name"), Some(Pii::FirstName));
+        assert_eq!(pii("surname"), Some(Pii::LastName));
+        assert_eq!(pii("full_name"), Some(Pii::PersonName));
+        assert_eq!(pii("last_login_ip"), Some(Pii::Ip));
+        assert_eq!(pii("card_number"), Some(Pii::CardNumber));
+
+        assert_eq!(pii("name"), None);
+        assert_eq!(pii("category_name"), None);
+        assert_eq!(pii("cancelled_at"), None);
+        assert_eq!(pii("description"), None);
+    }
+
+    #[test]
+    fn confidence() {
+        let emails = samples(&["john@example.com", "mary@example.org", ""]);
+        let detection = detect("email", &emails).unwrap();
+        assert_eq!(detection.confidence, Confidence::High);
+        assert_eq!(
+            detection.reason,
+            "the name and 100% of 2 sampled values look like emails"
+        );
+
+        let detection = detect("email", &[]).unwrap();
+        assert_eq!(detection.confidence, Confidence::Medium);
+
+        let detection = detect("email", &samples(&["unknown", "john@example.com"])).unwrap();
+        assert_eq!(detection.confidence, Confidence::Low);
+
+        let detection = detect("contact", &emails).unwrap();
+        assert_eq!(detection.pii, Pii::Email);
+        assert_eq!(detection.confidence, Confidence::Medium);
+        assert_eq!(
+            detection.reason,
+            "100% of 2 sampled values look like emails"
+        );
+    }
+
+    #[test]
+    fn by_values() {
+        let pii = |values: &[&str]| detect("value", &samples(values)).map(|d| d.pii);
+
+        assert_eq!(pii(&["192.168.0.1", "::1", "10.0.0.0/8"]), Some(Pii::Ip));
+        assert_eq!(
+            pii(&["4111 1111 1111 1111", "5500-0000-0000-0004"]),
+            Some(Pii::CardNumber)
+        );
+        assert_eq!(
+            pii(&["+1 (555) 123-4567", "+79001234567"]),
+            Some(Pii::Phone)
+        );
+
+        assert_eq!(pii(&["4111111111111112"]), None);
+        assert_eq!(pii(&["12345678", "1000000"]), None);
+        assert_eq!(
+            detect("phone", &samples(&["12345678", "1000000"]))
+                .unwrap()
+                .confidence,
+            Confidence::High
+        );
+        assert_eq!(pii(&["John Smith", "Mary Jane"]), None);
+        assert_eq!(pii(&["Action", "Comedy"]), None);
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(scan_column("email", "integer", &[]), None);
+        assert_eq!(scan_column("comment", "text", &samples(&["Hello"])), None);
+
+        let column = scan_column("email", "character varying", &[]).unwrap();
+        assert!(matches!(column.rule, ColumnRule::Pii(d) if d.pii == Pii::Email));
+    }
+
+    #[test]
+    fn json() {
+        let values = samples(&[
+            r#"{"user": {"email": "john@example.com", "age": 20}, "tags": ["a"]}"#,
+            r#"{"user": {"email": "mary@example.com"}, "contacts": [{"phone": "+1 555 1234567"}]}"#,
+        ]);
+
+        let column = scan_column("data", "text", &values).unwrap();
+        assert_eq!(column.confidence(), Confidence::High);
+        let fields = match column.rule {
+            ColumnRule::Json(fields) => fields,
+            rule => panic!("unexpected rule {:?}", rule),
+        };
+        let selectors: Vec<_> = fields
+            .iter()
+            .map(|f| (f.selector.as_str(), f.detection.pii))
+            .collect();
+        assert_eq!(
+            selectors,
+            vec![
+                ("$.contacts[*].phone", Pii::Phone),
+                ("$.user.email", Pii::Email)
+            ]
+        );
+
+        assert_eq!(json_path("$", "first name"), "$['first name']");
+    }
+
+    #[test]
+    fn draft() {
+        let tables = vec![
+            TableScan {
+                name: String::from("public.users"),
+                columns: vec![
+                    scan_column(
+                        "email",
+                        "text",
+                        &samples(&["john@example.com", "mary@example.com"]),
+                    )
+                    .unwrap(),
+                    scan_column("Card No", "text", &samples(&["4111111111111111"])).unwrap(),
+                    scan_column("profile", "jsonb", &samples(&[r#"{"first_name": "John"}"#]))
+                        .unwrap(),
+                ],
+            },
+            TableScan {
+                name: String::from("public.categories"),
+                columns: vec![],
+            },
+        ];
+
+        let config = draft_config(&tables);
+        assert_eq!(
+            config,
+            r#"# A draft config made by `pg_datanymizer scan`.
+# The detection is heuristic: review every rule before using the config.
+tables:
+  - name: public.users
+    rules:
+      # confidence: high (the name and 100% of 2 sampled values look like emails)
+      email:
+        email: {}
+      # confidence: high (the name and 100% of 1 sampled values look like card numbers)
+      "Card No":
+        credit_card: {}
+      # confidence: high (PII in JSON fields)
+      profile:
+        json:
+          fields:
+            # confidence: high (the name and 100% of 1 sampled values look like first names)
+            - name: "field_1"
+              selector: "$.first_name"
+              quote: true
+              rule:
+                first_name: {}
+"#
+        );
+
+        let settings = Settings::from_yaml(&config).unwrap();
+        assert_eq!(settings.tables.len(), 1);
+        assert_eq!(settings.tables[0].rules.len(), 3);
+
+        assert_eq!(
+            report(&tables),
+            "public.users.email: confidence high (emails, the name and 100% of 2 sampled values look like emails)\n\
+            public.users.Card No: confidence high (card numbers, the name and 100% of 1 sampled values look like card numbers)\n\
+            public.users.profile: confidence high (JSON fields: $.first_name (first names))"
+        );
+
+        let empty = draft_config(&tables[1..]);
+        assert!(Settings::from_yaml(&empty).unwrap().tables.is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::ColumnData;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Eq)]
+pub struct SqliteColumn {
+    /// Ordinal position of column (starting from 0)
+    pub position: u32,
+    /// Column name
+    pub name: String,
+    /// Declared column type (can be empty)
+    pub data_type: String,
+}
+
+impl PartialEq for SqliteColumn {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+    }
+}
+
+impl PartialOrd for SqliteColumn {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SqliteColumn {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.position.cmp(&other.position)
+    }
+}
+
+impl From<(u32, String, String)> for SqliteColumn {
+    fn from((position, name, data_type): (u32, String, String)) -> Self {
+        Self {
+            position,
+            name,
+            data_type,
+        }
+    }
+}
+
+impl ColumnData<String> for SqliteColumn {
+    fn position(&self) -> usize {
+        self.position as usize
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    fn inner_kind(&self) -> Option<String> {
+        Some(self.data_type.clone())
+    }
+}
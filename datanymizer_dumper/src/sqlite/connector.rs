@@ -0,0 +1,34 @@
+use anyhow::Result;
+use rusqlite::OpenFlags;
+use std::path::PathBuf;
+
+pub struct Connection {
+    pub conn: rusqlite::Connection,
+    pub path: PathBuf,
+}
+
+impl Connection {
+    pub fn new(conn: rusqlite::Connection, path: PathBuf) -> Self {
+        Self { conn, path }
+    }
+}
+
+#[derive(Clone)]
+pub struct Connector {
+    path: PathBuf,
+}
+
+impl Connector {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The source database is opened read-only
+    pub fn connect(&self) -> Result<Connection> {
+        let conn = rusqlite::Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        Ok(Connection::new(conn, self.path.clone()))
+    }
+}
@@ -0,0 +1,382 @@
+use super::{
+    connector, row::SqliteRow, schema_inspector::SqliteSchemaInspector, table::SqliteTable,
+};
+use crate::{indicator::Indicator, Dumper, SchemaInspector, Table};
+use anyhow::{bail, Result};
+use datanymizer_engine::{Engine, Settings};
+use log::warn;
+use rusqlite::types::Value;
+use std::{io::prelude::*, path::PathBuf, time::Instant};
+
+/// Number of rows in one `INSERT` statement
+const ROWS_IN_INSERT: usize = 100;
+
+// Tables are created before the data, indexes, views and triggers - after it
+const TABLES_QUERY: &str = "SELECT name, sql FROM sqlite_schema
+    WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL
+    ORDER BY rowid";
+
+const OBJECTS_QUERY: &str = "SELECT tbl_name, sql FROM sqlite_schema
+    WHERE type IN ('index', 'view', 'trigger') AND sql IS NOT NULL
+    ORDER BY CASE type WHEN 'index' THEN 0 WHEN 'view' THEN 1 ELSE 2 END, rowid";
+
+const SEQUENCES_QUERY: &str = "SELECT name, seq FROM sqlite_sequence";
+
+pub struct SqliteDumper<W: Write + Send, I: Indicator + Send> {
+    schema_inspector: SqliteSchemaInspector,
+    engine: Engine,
+    output: Output<W>,
+    indicator: I,
+    tables: Vec<SqliteTable>,
+}
+
+impl<W: 'static + Write + Send, I: 'static + Indicator + Send> SqliteDumper<W, I> {
+    pub fn new(engine: Engine, dump_writer: W, indicator: I) -> Result<Self> {
+        Ok(Self {
+            engine,
+            output: Output::Script(dump_writer),
+            indicator,
+            schema_inspector: SqliteSchemaInspector {},
+            tables: Vec::new(),
+        })
+    }
+
+    /// Writes a new database file instead of the SQL script (the writer isn't used)
+    pub fn with_target(mut self, path: PathBuf) -> Result<Self> {
+        if path.exists() {
+            bail!("The target database already exists: {}", path.display());
+        }
+        self.output = Output::Database(rusqlite::Connection::open(path)?);
+        Ok(self)
+    }
+
+    fn dump_table(
+        &mut self,
+        connection: &mut connector::Connection,
+        table: &SqliteTable,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let engine = &self.engine;
+        let cfg = engine.settings.find_table(&table.get_names());
+
+        self.indicator
+            .start_pb(table.count_of_query_to(cfg), &table.get_full_name());
+
+        let mut inserts = InsertWriter::new(&mut self.output, table.insert_prefix());
+        let column_count = table.columns.len();
+        let mut count: u64 = 0;
+        if let Some(cfg) = cfg {
+            if let Some(transformed_query) = table.transformed_query_to(Some(cfg)) {
+                let mut statement = connection.conn.prepare(&transformed_query)?;
+                let mut rows = statement.query([])?;
+                while let Some(row) = rows.next()? {
+                    let row = SqliteRow::new(values(row, column_count)?);
+                    let values =
+                        row.transform(engine, table, cfg.name.as_str())
+                            .map_err(|err| {
+                                warn!("{:#?}", err);
+                                err
+                            })?;
+                    inserts.write(values)?;
+                    self.indicator.inc_pb(1);
+                    count += 1;
+                }
+            }
+        }
+
+        if let Some(untransformed_query) = table.untransformed_query_to(cfg, count) {
+            let mut statement = connection.conn.prepare(&untransformed_query)?;
+            let mut rows = statement.query([])?;
+            while let Some(row) = rows.next()? {
+                inserts.write(SqliteRow::new(values(row, column_count)?).untransformed())?;
+                self.indicator.inc_pb(1);
+            }
+        }
+        inserts.finish()?;
+
+        let finished = started.elapsed();
+        self.indicator
+            .finish_pb(table.get_full_name().as_str(), finished);
+
+        Ok(())
+    }
+
+    /// Copies `AUTOINCREMENT` counters of the dumped tables
+    fn dump_sequences(&mut self, connection: &mut connector::Connection) -> Result<()> {
+        let has_sequences: bool = connection.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_schema WHERE name = 'sqlite_sequence')",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_sequences {
+            return Ok(());
+        }
+
+        let mut sequences = connection
+            .conn
+            .prepare(SEQUENCES_QUERY)?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<(String, i64)>>>()?;
+        sequences.retain(|(name, _)| self.engine.settings.filter.filter_table(name));
+        if sequences.is_empty() {
+            return Ok(());
+        }
+
+        // Counters are already updated by inserted rows (the same as in `sqlite3 .dump`)
+        self.output.execute(r#"DELETE FROM "sqlite_sequence""#)?;
+        let mut inserts = InsertWriter::new(
+            &mut self.output,
+            String::from(r#"INSERT INTO "sqlite_sequence" ("name", "seq") VALUES"#),
+        );
+        for (name, seq) in sequences {
+            inserts.write(
+                SqliteRow::new(vec![Value::Text(name), Value::Integer(seq)]).untransformed(),
+            )?;
+        }
+        inserts.finish()
+    }
+
+    /// Whether the schema object (e.g. an index) of the table is dumped
+    fn filter_object(&self, table: &str) -> bool {
+        !self.tables.iter().any(|t| t.tablename == table)
+            || self.engine.settings.filter.filter_schema(table)
+    }
+}
+
+/// Where the dump goes
+enum Output<W: Write> {
+    /// An SQL script (e.g. for `sqlite3 new.db < dump.sql`)
+    Script(W),
+    /// A new database file
+    Database(rusqlite::Connection),
+}
+
+impl<W: Write> Output<W> {
+    fn execute(&mut self, sql: &str) -> Result<()> {
+        match self {
+            Self::Script(w) => {
+                w.write_all(sql.as_bytes())?;
+                w.write_all(b";\n")?;
+            }
+            Self::Database(conn) => conn.execute_batch(sql)?,
+        }
+        Ok(())
+    }
+
+    fn comment(&mut self, message: &str) -> Result<()> {
+        if let Self::Script(w) = self {
+            w.write_all(format!("\n--\n-- {}\n--\n", message).as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Self::Script(w) = self {
+            w.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes rows with batched `INSERT` statements
+struct InsertWriter<'o, W: Write> {
+    output: &'o mut Output<W>,
+    prefix: String,
+    statement: String,
+    rows: usize,
+}
+
+impl<'o, W: Write> InsertWriter<'o, W> {
+    fn new(output: &'o mut Output<W>, prefix: String) -> Self {
+        Self {
+            output,
+            prefix,
+            statement: String::new(),
+            rows: 0,
+        }
+    }
+
+    fn write(&mut self, values: String) -> Result<()> {
+        if self.rows == 0 {
+            self.statement.push_str(&self.prefix);
+            self.statement.push('\n');
+        } else {
+            self.statement.push_str(",\n");
+        }
+        self.statement.push_str(&values);
+
+        self.rows += 1;
+        if self.rows == ROWS_IN_INSERT {
+            self.finish()?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.rows > 0 {
+            self.output.execute(&self.statement)?;
+            self.statement.clear();
+            self.rows = 0;
+        }
+        Ok(())
+    }
+}
+
+fn values(row: &rusqlite::Row, count: usize) -> Result<Vec<Value>> {
+    Ok((0..count)
+        .map(|i| row.get(i))
+        .collect::<rusqlite::Result<_>>()?)
+}
+
+/// The `sql` column of `sqlite_schema` for the table and other objects
+fn schema_objects(
+    connection: &connector::Connection,
+    query: &str,
+) -> Result<Vec<(String, String)>> {
+    Ok(connection
+        .conn
+        .prepare(query)?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?)
+}
+
+impl<W: 'static + Write + Send, I: 'static + Indicator + Send> Dumper for SqliteDumper<W, I> {
+    type Connection = connector::Connection;
+    type SchemaInspector = SqliteSchemaInspector;
+
+    // Stage before dumping data. It makes dump schema with any options
+    fn pre_data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        if self.engine.settings.subset.is_some() {
+            bail!("The `subset` section is supported only by PostgreSQL");
+        }
+
+        self.debug("Prepare data scheme...".into());
+        // All tables are read from the same snapshot
+        connection.conn.execute_batch("BEGIN")?;
+
+        self.output.execute("PRAGMA foreign_keys = OFF")?;
+        self.output.execute("BEGIN TRANSACTION")?;
+        for (name, sql) in schema_objects(connection, TABLES_QUERY)? {
+            if self.engine.settings.filter.filter_schema(&name) {
+                self.output.execute(&sql)?;
+            }
+        }
+        Ok(())
+    }
+
+    // This stage makes dump data only
+    fn data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        self.write_log("Start dumping data".into())?;
+
+        let tables = self.tables.clone();
+        let all_tables_count = tables.len();
+        for (ind, table) in tables.iter().enumerate() {
+            self.debug(format!(
+                "[{} / {}] Prepare to dump table: {}",
+                ind + 1,
+                all_tables_count,
+                table.get_full_name(),
+            ));
+
+            if self.filter_table(table.get_full_name()) {
+                self.dump_table(connection, table)?;
+            } else {
+                self.debug(format!("[Dumping: {}] --- SKIP ---", table.get_full_name()));
+            }
+        }
+        self.dump_sequences(connection)?;
+
+        self.write_log("End dumping data".into())?;
+        Ok(())
+    }
+
+    // This stage makes dump indexes, views and triggers
+    fn post_data(&mut self, connection: &mut Self::Connection) -> Result<()> {
+        self.debug("Finishing with indexes, views and triggers...".into());
+        for (table, sql) in schema_objects(connection, OBJECTS_QUERY)? {
+            if self.filter_object(&table) {
+                self.output.execute(&sql)?;
+            }
+        }
+        self.output.execute("COMMIT")?;
+        self.output.flush()?;
+
+        connection.conn.execute_batch("COMMIT")?;
+        Ok(())
+    }
+
+    fn schema_inspector(&self) -> Self::SchemaInspector {
+        self.schema_inspector.clone()
+    }
+
+    fn set_tables(&mut self, tables: Vec<<Self::SchemaInspector as SchemaInspector>::Table>) {
+        self.tables = tables;
+    }
+
+    fn settings(&self) -> &Settings {
+        &self.engine.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.engine.settings
+    }
+
+    fn write_log(&mut self, message: String) -> Result<()> {
+        self.output.comment(&message)
+    }
+
+    fn debug(&self, message: String) {
+        self.indicator.debug_msg(message.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_writer() {
+        let mut output = Output::Script(vec![]);
+        let mut inserts =
+            InsertWriter::new(&mut output, String::from(r#"INSERT INTO "t" ("a") VALUES"#));
+        for i in 0..ROWS_IN_INSERT + 1 {
+            inserts.write(format!("({})", i)).unwrap();
+        }
+        inserts.finish().unwrap();
+        inserts.finish().unwrap();
+
+        let dump = match output {
+            Output::Script(w) => String::from_utf8(w).unwrap(),
+            Output::Database(_) => unreachable!(),
+        };
+        assert_eq!(dump.matches("INSERT INTO").count(), 2);
+        assert!(dump.starts_with("INSERT INTO \"t\" (\"a\") VALUES\n(0),\n(1),\n"));
+        assert!(dump.ends_with("(99);\nINSERT INTO \"t\" (\"a\") VALUES\n(100);\n"));
+    }
+
+    #[test]
+    fn database_output() {
+        let mut output: Output<Vec<u8>> =
+            Output::Database(rusqlite::Connection::open_in_memory().unwrap());
+        output.execute("CREATE TABLE t (a)").unwrap();
+        output.comment("Only for scripts").unwrap();
+
+        let mut inserts = InsertWriter::new(&mut output, String::from("INSERT INTO t (a) VALUES"));
+        inserts.write(String::from("(X'00FF')")).unwrap();
+        inserts.write(String::from("(NULL)")).unwrap();
+        inserts.finish().unwrap();
+
+        let conn = match &output {
+            Output::Database(conn) => conn,
+            Output::Script(_) => unreachable!(),
+        };
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM t WHERE a IS NULL OR a = X'00FF'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+}
@@ -0,0 +1,11 @@
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SqliteForeignKey {
+    // Source
+    pub table_name: String,
+    pub column_name: String,
+
+    // Reference
+    pub foreign_table_name: String,
+    /// The primary key column if it is omitted in the foreign key definition
+    pub foreign_column_name: String,
+}
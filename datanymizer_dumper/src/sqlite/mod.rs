@@ -0,0 +1,9 @@
+//! The SQLite backend: reads a database file and writes an SQL script or a new database file.
+
+pub mod column;
+pub mod connector;
+pub mod dumper;
+pub mod foreign_key;
+pub mod row;
+pub mod schema_inspector;
+pub mod table;
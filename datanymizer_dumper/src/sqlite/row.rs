@@ -0,0 +1,167 @@
+use crate::Table;
+use anyhow::Result;
+use datanymizer_engine::Engine;
+use rusqlite::types::Value;
+use std::borrow::Cow;
+
+#[derive(Debug)]
+pub struct SqliteRow {
+    values: Vec<Value>,
+}
+
+impl SqliteRow {
+    pub fn new(values: Vec<Value>) -> Self {
+        Self { values }
+    }
+
+    /// Applies the transform engine to every column in the row.
+    /// Returns the row for the `INSERT` statement, e.g. `(1, 'value')`.
+    pub fn transform<T: Table<String>>(
+        &self,
+        engine: &Engine,
+        table: &T,
+        cfg_tbl_name: &str,
+    ) -> Result<String> {
+        let texts: Vec<Option<Cow<str>>> = self.values.iter().map(text).collect();
+        let values: Vec<Option<&str>> = texts.iter().map(|t| t.as_deref()).collect();
+        let transformed_values = engine.process_row(
+            String::from(cfg_tbl_name),
+            table.get_column_indexes(),
+            &values,
+        )?;
+
+        let literals: Vec<String> = self
+            .values
+            .iter()
+            .zip(transformed_values)
+            .map(|(value, transformed)| match transformed {
+                // Untouched values keep their storage classes (e.g. blobs)
+                Some(Cow::Borrowed(_)) => literal(value),
+                Some(Cow::Owned(s)) => quote(&s),
+                None => String::from("NULL"),
+            })
+            .collect();
+        Ok(format!("({})", literals.join(", ")))
+    }
+
+    /// The row for the `INSERT` statement without transformations
+    pub fn untransformed(&self) -> String {
+        let literals: Vec<String> = self.values.iter().map(literal).collect();
+        format!("({})", literals.join(", "))
+    }
+}
+
+/// The value for transformers
+fn text(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::Null => None,
+        Value::Integer(i) => Some(Cow::Owned(i.to_string())),
+        Value::Real(f) => Some(Cow::Owned(f.to_string())),
+        Value::Text(s) => Some(Cow::Borrowed(s)),
+        Value::Blob(bytes) => Some(String::from_utf8_lossy(bytes)),
+    }
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("NULL"),
+        Value::Integer(i) => i.to_string(),
+        // The same as in `sqlite3 .dump`
+        Value::Real(f) if f.is_infinite() => {
+            String::from(if *f > 0.0 { "9e999" } else { "-9e999" })
+        }
+        Value::Real(f) => format!("{:?}", f),
+        Value::Text(s) => quote(s),
+        Value::Blob(bytes) => {
+            let hex: String = bytes.iter().map(|b| format!("{:02X}", b)).collect();
+            format!("X'{}'", hex)
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{column::SqliteColumn, table::SqliteTable};
+    use datanymizer_engine::Settings;
+
+    fn table() -> SqliteTable {
+        let mut table = SqliteTable::new(String::from("users"));
+        let columns = ["id", "name", "comment", "avatar", "rating"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| SqliteColumn {
+                position: i as u32,
+                name: String::from(*name),
+                data_type: String::new(),
+            })
+            .collect();
+        table.set_columns(columns);
+        table
+    }
+
+    fn row() -> SqliteRow {
+        SqliteRow::new(vec![
+            Value::Integer(1),
+            Value::Text(String::from("O'Neil")),
+            Value::Null,
+            Value::Blob(vec![0xFF, 0x00]),
+            Value::Real(4.0),
+        ])
+    }
+
+    #[test]
+    fn transform() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ _0 }}\nJr."
+                comment:
+                  template:
+                    format: "{{ prev.name }}"
+                  on_null: transform
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+
+        assert_eq!(
+            row().transform(&engine, &table(), "users").unwrap(),
+            "(1, 'O''Neil\nJr.', 'O''Neil', X'FF00', 4.0)"
+        );
+    }
+
+    #[test]
+    fn transform_to_null() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  set_null: ~
+                comment:
+                  first_name: {}
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+
+        assert_eq!(
+            row().transform(&engine, &table(), "users").unwrap(),
+            "(1, NULL, NULL, X'FF00', 4.0)"
+        );
+    }
+
+    #[test]
+    fn untransformed() {
+        assert_eq!(row().untransformed(), "(1, 'O''Neil', NULL, X'FF00', 4.0)");
+        assert_eq!(
+            SqliteRow::new(vec![Value::Real(f64::NEG_INFINITY), Value::Blob(vec![])])
+                .untransformed(),
+            "(-9e999, X'')"
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use super::{column::SqliteColumn, connector, foreign_key::SqliteForeignKey, table::SqliteTable};
+use crate::SchemaInspector;
+use anyhow::Result;
+
+// Internal tables (e.g. `sqlite_sequence`) are dumped with the data
+const TABLES_QUERY: &str = "SELECT name FROM sqlite_schema
+    WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+    ORDER BY rowid";
+
+const TABLE_FK_QUERY: &str = r#"SELECT "from", "table", "to" FROM pragma_foreign_key_list(?1)"#;
+
+// Generated columns are hidden in `table_info` (they can't be inserted)
+const TABLE_COLUMNS_QUERY: &str = "SELECT name, type FROM pragma_table_info(?1) ORDER BY cid";
+
+const PRIMARY_KEY_QUERY: &str = "SELECT name FROM pragma_table_info(?1) WHERE pk > 0 ORDER BY pk";
+
+#[derive(Clone)]
+pub struct SqliteSchemaInspector;
+
+impl SchemaInspector for SqliteSchemaInspector {
+    type Type = String;
+    type Connection = connector::Connection;
+    type Table = SqliteTable;
+    type Column = SqliteColumn;
+    type ForeignKey = SqliteForeignKey;
+
+    // Get all tables in the database
+    fn get_tables(&self, connection: &mut Self::Connection) -> Result<Vec<Self::Table>> {
+        let names = connection
+            .conn
+            .prepare(TABLES_QUERY)?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()?;
+
+        let mut tables = Vec::with_capacity(names.len());
+        for name in names {
+            let mut table = SqliteTable::new(name);
+            table.set_columns(self.get_columns(connection, &table)?);
+            table.set_foreign_keys(self.get_foreign_keys(connection, &table)?);
+            table.size = self.get_table_size(connection, &table)?;
+            tables.push(table);
+        }
+        Ok(tables)
+    }
+
+    /// Get table size
+    fn get_table_size(
+        &self,
+        connection: &mut Self::Connection,
+        table: &Self::Table,
+    ) -> Result<i64> {
+        let query = format!("SELECT COUNT(*) FROM {}", table.quoted_name());
+        Ok(connection.conn.query_row(&query, [], |row| row.get(0))?)
+    }
+
+    /// Get foreign keys for table
+    fn get_foreign_keys(
+        &self,
+        connection: &mut Self::Connection,
+        table: &Self::Table,
+    ) -> Result<Vec<Self::ForeignKey>> {
+        let rows = connection
+            .conn
+            .prepare(TABLE_FK_QUERY)?
+            .query_map([&table.tablename], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<(String, String, Option<String>)>>>()?;
+
+        let mut foreign_keys = Vec::with_capacity(rows.len());
+        for (column_name, foreign_table_name, foreign_column_name) in rows {
+            let foreign_column_name = match foreign_column_name {
+                Some(name) => name,
+                None => match primary_key(connection, &foreign_table_name)? {
+                    Some(name) => name,
+                    None => continue,
+                },
+            };
+            foreign_keys.push(SqliteForeignKey {
+                table_name: table.tablename.clone(),
+                column_name,
+                foreign_table_name,
+                foreign_column_name,
+            });
+        }
+        Ok(foreign_keys)
+    }
+
+    /// Get columns for table
+    fn get_columns(
+        &self,
+        connection: &mut Self::Connection,
+        table: &Self::Table,
+    ) -> Result<Vec<Self::Column>> {
+        let rows = connection
+            .conn
+            .prepare(TABLE_COLUMNS_QUERY)?
+            .query_map([&table.tablename], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+        Ok(rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, data_type))| SqliteColumn::from((i as u32, name, data_type)))
+            .collect())
+    }
+}
+
+/// The column of a single-column primary key
+fn primary_key(connection: &connector::Connection, table: &str) -> Result<Option<String>> {
+    let columns = connection
+        .conn
+        .prepare(PRIMARY_KEY_QUERY)?
+        .query_map([table], |row| row.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+
+    Ok(match columns.as_slice() {
+        [column] => Some(column.clone()),
+        _ => None,
+    })
+}
@@ -0,0 +1,289 @@
+use super::{column::SqliteColumn, foreign_key::SqliteForeignKey, row::SqliteRow};
+use crate::Table;
+use datanymizer_engine::{Query as QueryCfg, Table as TableCfg};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+};
+
+/// SQLite has no schemas, so the full table name is the same as the short one
+#[derive(Debug, Clone, Eq)]
+pub struct SqliteTable {
+    pub tablename: String,
+    pub columns: Vec<SqliteColumn>,
+    pub foreign_keys: Vec<SqliteForeignKey>,
+    column_indexes: HashMap<String, usize>,
+    pub size: i64,
+}
+
+impl PartialEq for SqliteTable {
+    fn eq(&self, other: &SqliteTable) -> bool {
+        self.tablename == other.tablename
+    }
+}
+
+impl Hash for SqliteTable {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tablename.hash(state);
+    }
+}
+
+impl Table<String> for SqliteTable {
+    type Column = SqliteColumn;
+    type Row = SqliteRow;
+
+    // Returns table name
+    fn get_name(&self) -> String {
+        self.tablename.clone()
+    }
+
+    // Returns table name
+    fn get_full_name(&self) -> String {
+        self.get_name()
+    }
+
+    fn get_names(&self) -> Vec<String> {
+        vec![self.get_name()]
+    }
+
+    fn get_columns(&self) -> Vec<Self::Column> {
+        self.columns.clone()
+    }
+
+    fn get_columns_names(&self) -> Vec<String> {
+        self.get_columns().into_iter().map(|c| c.name).collect()
+    }
+
+    fn get_size(&self) -> i64 {
+        self.size
+    }
+
+    fn get_column_indexes(&self) -> &HashMap<String, usize> {
+        &self.column_indexes
+    }
+
+    fn get_dep_table_names(&self) -> Vec<String> {
+        self.foreign_keys
+            .iter()
+            .map(|fk| fk.foreign_table_name.clone())
+            .collect()
+    }
+
+    fn get_foreign_key_columns(&self) -> Vec<(String, String, String)> {
+        self.foreign_keys
+            .iter()
+            .map(|fk| {
+                (
+                    fk.column_name.clone(),
+                    fk.foreign_table_name.clone(),
+                    fk.foreign_column_name.clone(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl SqliteTable {
+    pub fn new(tablename: String) -> Self {
+        Self {
+            tablename,
+            columns: vec![],
+            foreign_keys: vec![],
+            column_indexes: HashMap::new(),
+            size: 0,
+        }
+    }
+
+    pub fn quote_identifier(name: &str) -> String {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+
+    pub fn quoted_name(&self) -> String {
+        Self::quote_identifier(&self.tablename)
+    }
+
+    pub fn set_columns(&mut self, mut columns: Vec<SqliteColumn>) {
+        columns.sort();
+        self.column_indexes = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| (column.name.clone(), i))
+            .collect();
+        self.columns = columns;
+    }
+
+    pub fn set_foreign_keys(&mut self, foreign_keys: Vec<SqliteForeignKey>) {
+        self.foreign_keys = foreign_keys;
+    }
+
+    pub fn transformed_query_to(&self, cfg: Option<&TableCfg>) -> Option<String> {
+        cfg.map(|c| match &c.query {
+            Some(q) => self.query_with_conditions(q, |s| format!("({})", s), 0),
+            None => self.query_with_select(vec![], None),
+        })
+    }
+
+    pub fn untransformed_query_to(
+        &self,
+        cfg: Option<&TableCfg>,
+        already_dumped: u64,
+    ) -> Option<String> {
+        match cfg {
+            Some(c) => c.query.as_ref().and_then(|q| {
+                if q.transform_condition.is_some()
+                    && q.limit.is_none_or(|limit| limit as u64 > already_dumped)
+                {
+                    Some(self.query_with_conditions(
+                        q,
+                        |s| format!("((NOT ({})) OR (({}) IS NULL))", s, s),
+                        already_dumped,
+                    ))
+                } else {
+                    None
+                }
+            }),
+            None => Some(self.query_with_select(vec![], None)),
+        }
+    }
+
+    pub fn count_of_query_to(&self, cfg: Option<&TableCfg>) -> u64 {
+        let number = self.get_size() as u64;
+
+        cfg.and_then(|c| c.query.as_ref())
+            .and_then(|q| q.limit)
+            .map_or(number, |limit| number.min(limit as u64))
+    }
+
+    /// The beginning of `INSERT` statements for the table rows
+    pub fn insert_prefix(&self) -> String {
+        format!(
+            "INSERT INTO {} ({}) VALUES",
+            self.quoted_name(),
+            self.quoted_columns().join(", ")
+        )
+    }
+
+    fn query_with_conditions(
+        &self,
+        q: &QueryCfg,
+        tr_fmt: fn(s: &String) -> String,
+        already_dumped: u64,
+    ) -> String {
+        self.query_with_select(
+            vec![
+                q.dump_condition.as_ref().map(|c| format!("({})", c)),
+                q.transform_condition.as_ref().map(tr_fmt),
+            ],
+            q.limit.map(|limit| limit as u64 - already_dumped),
+        )
+    }
+
+    fn query_with_select(&self, cs: Vec<Option<String>>, limit: Option<u64>) -> String {
+        let conditions: Vec<String> = cs.into_iter().flatten().collect();
+        let mut query = format!(
+            "SELECT {} FROM {}",
+            self.quoted_columns().join(", "),
+            self.quoted_name()
+        );
+        if !conditions.is_empty() {
+            query.push_str(&format!(" WHERE {}", conditions.join(" AND ")));
+        }
+        if let Some(limit) = limit {
+            query.push_str(&format!(" LIMIT {}", limit));
+        }
+        query
+    }
+
+    fn quoted_columns(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|c| Self::quote_identifier(&c.name))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_column(position: u32, name: &str) -> SqliteColumn {
+        SqliteColumn {
+            position,
+            name: String::from(name),
+            data_type: String::from("TEXT"),
+        }
+    }
+
+    fn table() -> SqliteTable {
+        let mut table = SqliteTable::new(String::from("users"));
+        table.set_columns(vec![new_column(1, "na\"me"), new_column(0, "id")]);
+        table.size = 1000;
+        table
+    }
+
+    fn cfg(query: Option<QueryCfg>) -> TableCfg {
+        TableCfg {
+            name: String::from("users"),
+            rules: HashMap::new(),
+            rule_order: None,
+            query,
+        }
+    }
+
+    #[test]
+    fn names() {
+        let table = table();
+
+        assert_eq!(table.get_full_name(), "users");
+        assert_eq!(table.get_names(), vec!["users"]);
+        assert_eq!(table.quoted_name(), "\"users\"");
+        assert_eq!(table.get_columns_names(), vec!["id", "na\"me"]);
+        assert_eq!(table.get_column_indexes()["na\"me"], 1);
+        assert_eq!(
+            table.insert_prefix(),
+            r#"INSERT INTO "users" ("id", "na""me") VALUES"#
+        );
+    }
+
+    #[test]
+    fn no_table() {
+        assert_eq!(table().transformed_query_to(None), None);
+        assert_eq!(
+            table().untransformed_query_to(None, 0).unwrap(),
+            r#"SELECT "id", "na""me" FROM "users""#
+        );
+        assert_eq!(table().count_of_query_to(None), 1000);
+    }
+
+    #[test]
+    fn no_query() {
+        let cfg = cfg(None);
+
+        assert_eq!(
+            table().transformed_query_to(Some(&cfg)).unwrap(),
+            r#"SELECT "id", "na""me" FROM "users""#
+        );
+        assert_eq!(table().untransformed_query_to(Some(&cfg), 0), None);
+    }
+
+    #[test]
+    fn all_query_params() {
+        let cfg = cfg(Some(QueryCfg {
+            limit: Some(500),
+            dump_condition: Some("id > 10".to_string()),
+            transform_condition: Some("id < 100".to_string()),
+        }));
+
+        assert_eq!(
+            table().transformed_query_to(Some(&cfg)).unwrap(),
+            r#"SELECT "id", "na""me" FROM "users" WHERE (id > 10) AND (id < 100) LIMIT 500"#
+        );
+        assert_eq!(
+            table().untransformed_query_to(Some(&cfg), 200).unwrap(),
+            "SELECT \"id\", \"na\"\"me\" FROM \"users\" \
+            WHERE (id > 10) AND ((NOT (id < 100)) OR ((id < 100) IS NULL)) LIMIT 300"
+        );
+        assert_eq!(table().untransformed_query_to(Some(&cfg), 500), None);
+        assert_eq!(table().count_of_query_to(Some(&cfg)), 500);
+    }
+}
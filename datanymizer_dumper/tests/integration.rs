@@ -1 +1,3 @@
+mod mysql;
 mod postgres;
+mod sqlite;
@@ -0,0 +1,80 @@
+use super::helpers;
+
+use datanymizer_dumper::{indicator::SilentIndicator, mysql::dumper::MySqlDumper, Dumper};
+use datanymizer_engine::{Engine, Settings};
+use mysql::{prelude::Queryable, Value};
+use std::fs::File;
+
+fn dump(name: &str) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("dump.sql");
+
+    let cfg_filename = format!("tests/mysql/configs/{}.yml", name);
+    let settings = Settings::new(cfg_filename).unwrap();
+    let mut dumper = MySqlDumper::new(
+        Engine::new(settings),
+        helpers::mysqldump_path(),
+        File::create(&path).unwrap(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap();
+    let mut connection = helpers::src_connection();
+    dumper.dump(&mut connection).unwrap();
+    drop(dumper);
+
+    helpers::restore(name, &path);
+}
+
+fn query<T: mysql::prelude::FromRow>(connection: &mut mysql::Conn, query: &str) -> Vec<T> {
+    connection.query(query).unwrap()
+}
+
+#[test]
+fn simple_dump() {
+    dump("simple");
+
+    let mut src = helpers::src_connection();
+    let mut dst = helpers::dst_connection("simple");
+
+    let names_query =
+        "SELECT actor_id, first_name, last_name, full_name FROM actor ORDER BY actor_id";
+    let src_names: Vec<(u32, String, String, String)> = query(&mut src.conn, names_query);
+    let dst_names: Vec<(u32, String, String, String)> = query(&mut dst.conn, names_query);
+    assert_eq!(src_names.len(), dst_names.len());
+    for (src_row, dst_row) in src_names.iter().zip(&dst_names) {
+        assert_eq!(src_row.0, dst_row.0);
+        assert_ne!(src_row.1, dst_row.1);
+        assert_eq!(dst_row.2, format!("{}'s \"last\" name", dst_row.1));
+        assert_eq!(dst_row.3, format!("{} {}", dst_row.1, dst_row.2));
+    }
+
+    // Only the rows with `transform_condition` are transformed, other values are the same
+    let films_query =
+        "SELECT film_id, title, description, poster, rating FROM film ORDER BY film_id";
+    let src_films: Vec<(u32, String, Value, Value, Value)> = query(&mut src.conn, films_query);
+    let dst_films: Vec<(u32, String, Value, Value, Value)> = query(&mut dst.conn, films_query);
+    assert_eq!(dst_films.len(), 3);
+    assert_eq!(dst_films[0].1, "Film 1");
+    assert_eq!(dst_films[1].1, "Film 2");
+    assert_eq!(dst_films[2], src_films[2]);
+    for (src_film, dst_film) in src_films.iter().zip(&dst_films) {
+        assert_eq!(src_film.2, dst_film.2);
+        assert_eq!(src_film.3, dst_film.3);
+        assert_eq!(src_film.4, dst_film.4);
+    }
+
+    let links_query = "SELECT actor_id, film_id FROM film_actor ORDER BY actor_id, film_id";
+    let src_links: Vec<(u32, u32)> = query(&mut src.conn, links_query);
+    let dst_links: Vec<(u32, u32)> = query(&mut dst.conn, links_query);
+    assert_eq!(src_links, dst_links);
+
+    // The trigger is created after the data, so the log isn't filled by the dump
+    let log: Vec<u64> = query(&mut dst.conn, "SELECT COUNT(*) FROM film_log");
+    assert_eq!(log, vec![0]);
+    let triggers: Vec<String> = query(
+        &mut dst.conn,
+        "SELECT TRIGGER_NAME FROM information_schema.TRIGGERS WHERE TRIGGER_SCHEMA = DATABASE()",
+    );
+    assert_eq!(triggers, vec!["film_insert"]);
+}
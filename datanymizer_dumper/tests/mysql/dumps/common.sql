@@ -0,0 +1,49 @@
+CREATE TABLE actor (
+  actor_id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+  first_name VARCHAR(45) NOT NULL,
+  last_name VARCHAR(45) NOT NULL,
+  full_name VARCHAR(91) GENERATED ALWAYS AS (CONCAT(first_name, ' ', last_name)) VIRTUAL,
+  last_update TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  PRIMARY KEY (actor_id),
+  KEY idx_actor_last_name (last_name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE film (
+  film_id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+  title VARCHAR(128) NOT NULL,
+  description TEXT,
+  poster BLOB,
+  rating DECIMAL(4, 2),
+  PRIMARY KEY (film_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE film_actor (
+  actor_id INT UNSIGNED NOT NULL,
+  film_id INT UNSIGNED NOT NULL,
+  PRIMARY KEY (actor_id, film_id),
+  CONSTRAINT fk_film_actor_actor FOREIGN KEY (actor_id) REFERENCES actor (actor_id),
+  CONSTRAINT fk_film_actor_film FOREIGN KEY (film_id) REFERENCES film (film_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE film_log (
+  film_id INT UNSIGNED NOT NULL,
+  title VARCHAR(128) NOT NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TRIGGER film_insert AFTER INSERT ON film
+  FOR EACH ROW INSERT INTO film_log (film_id, title) VALUES (NEW.film_id, NEW.title);
+
+INSERT INTO actor (actor_id, first_name, last_name) VALUES
+  (1, 'Penelope', 'Guiness'),
+  (2, 'Nick', 'Wahlberg'),
+  (3, 'Ed', 'Chase'),
+  (4, 'Jennifer', 'Davis'),
+  (5, 'Johnny', 'Lollobrigida');
+
+INSERT INTO film (film_id, title, description, poster, rating) VALUES
+  (1, 'Academy Dinosaur', 'A "Epic" Drama\nof a Feminist\tand a Mad Scientist', 0x89504E470D0A1A0A00FF, 6.5),
+  (2, 'Ace Goldfinger', 'It''s a story with a backslash \\', NULL, NULL),
+  (3, 'Adaptation Holes', NULL, '', 7.25);
+
+INSERT INTO film_actor (actor_id, film_id) VALUES
+  (1, 1), (1, 2), (2, 1), (3, 3), (4, 2), (5, 3);
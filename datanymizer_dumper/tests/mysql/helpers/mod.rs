@@ -0,0 +1,95 @@
+use datanymizer_dumper::mysql::connector::{ClientOptions, Connection, Connector};
+use mysql::prelude::Queryable;
+use std::{
+    env,
+    fs::File,
+    path::Path,
+    process::{Command, Stdio},
+    sync::Once,
+};
+use url::Url;
+
+static CREATE_SRC_DB: Once = Once::new();
+
+const DB_ENV_KEY: &str = "DATANYMIZER_TEST_MYSQL_DB";
+const MYSQLDUMP_PATH_KEY: &str = "DATANYMIZER_TEST_MYSQLDUMP_PATH";
+const MYSQL_PATH_KEY: &str = "DATANYMIZER_TEST_MYSQL_PATH";
+const SRC_DUMP_PATH: &str = "tests/mysql/dumps/common.sql";
+
+pub fn mysqldump_path() -> String {
+    env::var(MYSQLDUMP_PATH_KEY).unwrap_or("mysqldump".to_string())
+}
+
+pub fn src_database_url() -> Url {
+    Url::parse(
+        env::var(DB_ENV_KEY)
+            .unwrap_or_else(|_| panic!("No {} environment variable", DB_ENV_KEY))
+            .as_str(),
+    )
+    .expect("Invalid database URL")
+}
+
+pub fn dst_database_url(name: &str) -> Url {
+    let mut database_url = src_database_url();
+    database_url.set_path(format!("{}_fake_{}", database_url.path(), name).as_str());
+
+    database_url
+}
+
+pub fn create_src_db() {
+    CREATE_SRC_DB.call_once(|| {
+        let database_url = src_database_url();
+        create_db(&database_url);
+        load(&database_url, Path::new(SRC_DUMP_PATH));
+    });
+}
+
+pub fn src_connection() -> Connection {
+    create_src_db();
+    Connector::new(src_database_url()).connect().unwrap()
+}
+
+pub fn dst_connection(name: &str) -> Connection {
+    Connector::new(dst_database_url(name)).connect().unwrap()
+}
+
+/// Loads the dump to the new destination database
+pub fn restore(name: &str, path: &Path) {
+    let dst_url = dst_database_url(name);
+    create_db(&dst_url);
+    load(&dst_url, path);
+}
+
+fn create_db(url: &Url) {
+    let db_name = ClientOptions::from_url(url).unwrap().database;
+
+    let mut server_url = url.clone();
+    server_url.set_path("");
+    let mut connection = Connector::new(server_url).connect().unwrap();
+    connection
+        .conn
+        .query_drop(format!("DROP DATABASE IF EXISTS `{}`", db_name))
+        .unwrap();
+    connection
+        .conn
+        .query_drop(format!("CREATE DATABASE `{}`", db_name))
+        .unwrap();
+}
+
+fn load(url: &Url, path: &Path) {
+    let options = ClientOptions::from_url(url).unwrap();
+
+    let mysql_path = env::var(MYSQL_PATH_KEY).unwrap_or("mysql".to_string());
+    let mut command = Command::new(mysql_path);
+    command
+        .args(&options.args)
+        .arg(&options.database)
+        .stdin(File::open(path).unwrap())
+        .stdout(Stdio::null());
+    if let Some(password) = &options.password {
+        command.env("MYSQL_PWD", password);
+    }
+
+    let status = command.status().expect("Error when running mysql");
+    assert!(status.success(), "mysql has failed to load {:?}", path);
+}
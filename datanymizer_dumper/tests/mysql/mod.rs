@@ -0,0 +1,6 @@
+#![cfg(feature = "mysql_db_tests")]
+
+mod helpers;
+
+mod dumper;
+mod schema_inspector;
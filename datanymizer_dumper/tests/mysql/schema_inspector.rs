@@ -0,0 +1,40 @@
+use super::helpers;
+
+use datanymizer_dumper::{mysql::schema_inspector::MySqlSchemaInspector, SchemaInspector, Table};
+
+#[test]
+fn get_tables() {
+    let mut connection = helpers::src_connection();
+    let mut tables = MySqlSchemaInspector.get_tables(&mut connection).unwrap();
+    tables.sort_by_key(|t| t.get_name());
+
+    let names: Vec<_> = tables.iter().map(|t| t.get_name()).collect();
+    assert_eq!(names, vec!["actor", "film", "film_actor", "film_log"]);
+
+    // Generated columns are skipped
+    assert_eq!(
+        tables[0].get_columns_names(),
+        vec!["actor_id", "first_name", "last_name", "last_update"]
+    );
+    assert_eq!(tables[0].get_column_indexes()["last_update"], 3);
+
+    let mut deps = tables[2].get_dep_table_names();
+    deps.sort();
+    let database = tables[0].schemaname.clone();
+    assert_eq!(
+        deps,
+        vec![format!("{}.actor", database), format!("{}.film", database)]
+    );
+}
+
+#[test]
+fn ordered_tables() {
+    let mut connection = helpers::src_connection();
+    let tables = MySqlSchemaInspector
+        .ordered_tables(&mut connection)
+        .unwrap();
+
+    let weight = |name: &str| tables.iter().find(|(t, _)| t.get_name() == name).unwrap().1;
+    assert!(weight("actor") > weight("film_actor"));
+    assert!(weight("film") > weight("film_actor"));
+}
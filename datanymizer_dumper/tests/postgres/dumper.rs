@@ -2,12 +2,21 @@
 
 use datanymizer_dumper::{
     indicator::SilentIndicator,
-    postgres::{connector::Connection, dumper::PgDumper},
+    postgres::{
+        connector::{Connection, Connector},
+        dumper::PgDumper,
+        ArchiveFormat,
+    },
     Dumper,
 };
 use datanymizer_engine::{Engine, Settings};
+use std::io;
 
 fn dump(name: &str) {
+    dump_with(name, 1, 1);
+}
+
+fn dump_with(name: &str, jobs: usize, workers: usize) {
     let mut dst = helpers::dst_wrapper(name);
 
     let cfg_filename = format!("tests/postgres/configs/{}.yml", name);
@@ -21,19 +30,268 @@ fn dump(name: &str) {
         SilentIndicator,
         vec![],
     )
-    .unwrap();
+    .unwrap()
+    .with_jobs(
+        jobs,
+        Connector::new(helpers::src_database_url(), false, false),
+    )
+    .with_workers(workers);
     let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
     dumper.dump(&mut connection).unwrap();
+    drop(dumper);
 
     dst.close();
 }
 
+fn dump_archive(name: &str, format: ArchiveFormat, jobs: usize) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(name);
+
+    let cfg_filename = format!("tests/postgres/configs/{}.yml", name);
+    let settings = Settings::new(cfg_filename).unwrap();
+    let engine = Engine::new(settings);
+    let mut dumper = PgDumper::new(
+        engine,
+        None,
+        helpers::pg_dump_path(),
+        io::sink(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap()
+    .with_jobs(
+        jobs,
+        Connector::new(helpers::src_database_url(), false, false),
+    )
+    .with_archive(format, path.clone());
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    dumper.dump(&mut connection).unwrap();
+
+    helpers::restore_archive(name, &path);
+}
+
 #[test]
 fn simple_dump() {
     dump("simple");
+    check_simple_dump("simple");
+}
 
+#[test]
+fn workers_dump() {
+    dump_with("workers", 1, 4);
+    check_simple_dump("workers");
+}
+
+#[test]
+fn custom_format_dump() {
+    dump_archive("custom_format", ArchiveFormat::Custom, 1);
+    check_simple_dump("custom_format");
+}
+
+#[test]
+fn directory_format_dump() {
+    dump_archive("directory_format", ArchiveFormat::Directory, 2);
+    check_simple_dump("directory_format");
+}
+
+#[test]
+fn target_dump() {
+    let name = "target";
+    let dst_url = helpers::create_dst_db(name);
+
+    let cfg_filename = format!("tests/postgres/configs/{}.yml", name);
+    let settings = Settings::new(cfg_filename).unwrap();
+    let engine = Engine::new(settings);
+    let mut dumper = PgDumper::new(
+        engine,
+        None,
+        helpers::pg_dump_path(),
+        io::sink(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap()
+    .with_jobs(2, Connector::new(helpers::src_database_url(), false, false))
+    .with_target(Connector::new(dst_url, false, false));
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    dumper.dump(&mut connection).unwrap();
+
+    check_simple_dump(name);
+
+    let mut dst_client = helpers::dst_client(name);
+    let indexes_query = "SELECT COUNT(*) FROM pg_indexes WHERE tablename = 'actor'";
+    let indexes: i64 = dst_client.query_one(indexes_query, &[]).unwrap().get(0);
+    assert!(indexes > 0);
+}
+
+#[test]
+fn resumed_dump() {
+    let dir = tempfile::tempdir().unwrap();
+    let checkpoint_dir = dir.path().join("checkpoints");
+
+    let dumper = |cfg_name: &str, w| {
+        let cfg_filename = format!("tests/postgres/configs/{}.yml", cfg_name);
+        let settings = Settings::new(cfg_filename).unwrap();
+        PgDumper::new(
+            Engine::new(settings),
+            None,
+            helpers::pg_dump_path(),
+            w,
+            SilentIndicator,
+            vec![],
+        )
+        .unwrap()
+        .with_checkpoints(checkpoint_dir.clone(), String::from("fingerprint"))
+    };
+
+    // The category table can't be transformed, but the actor table is saved
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    let result = dumper(
+        "checkpoint_failed",
+        Box::new(io::sink()) as Box<dyn io::Write + Send>,
+    )
+    .dump(&mut connection);
+    assert!(result.is_err());
+    assert!(checkpoint_dir.join("manifest.json").exists());
+
+    let mut dst = helpers::dst_wrapper("checkpoint");
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    let mut resumed = dumper("checkpoint", Box::new(dst.io()));
+    resumed.dump(&mut connection).unwrap();
+    drop(resumed);
+    dst.close();
+
+    assert!(!checkpoint_dir.exists());
+    check_simple_dump("checkpoint");
+
+    let mut dst_client = helpers::dst_client("checkpoint");
+    let names: Vec<String> = dst_client
+        .query(
+            "SELECT name FROM category ORDER BY category_id LIMIT 2",
+            &[],
+        )
+        .unwrap()
+        .iter()
+        .map(|r| r.get(0))
+        .collect();
+    assert_eq!(names, vec!["Category 1", "Category 2"]);
+}
+
+#[test]
+fn resumed_subset_dump() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = r#"{
+      "fingerprint": "fingerprint",
+      "tables": [{ "name": "public.actor", "file": "0.sql", "snapshot": null }]
+    }"#;
+    std::fs::write(dir.path().join("manifest.json"), manifest).unwrap();
+
+    let settings = Settings::new(String::from("tests/postgres/configs/subset.yml")).unwrap();
+    let mut dumper = PgDumper::new(
+        Engine::new(settings),
+        None,
+        helpers::pg_dump_path(),
+        io::sink(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap()
+    .with_checkpoints(dir.path().to_path_buf(), String::from("fingerprint"));
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+
+    let err = dumper.dump(&mut connection).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Can't resume a dump with a subset"));
+}
+
+#[test]
+fn resumed_stateful_dump() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = r#"{
+      "fingerprint": "fingerprint",
+      "tables": [{ "name": "public.actor", "file": "0.sql", "snapshot": null }]
+    }"#;
+    std::fs::write(dir.path().join("manifest.json"), manifest).unwrap();
+
+    let settings =
+        Settings::new(String::from("tests/postgres/configs/checkpoint_uniq.yml")).unwrap();
+    let mut dumper = PgDumper::new(
+        Engine::new(settings),
+        None,
+        helpers::pg_dump_path(),
+        io::sink(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap()
+    .with_checkpoints(dir.path().to_path_buf(), String::from("fingerprint"));
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+
+    let err = dumper.dump(&mut connection).unwrap_err().to_string();
+    assert!(err.starts_with("Can't resume a dump with consistency domains"));
+    assert!(err.contains("(tables: actor, category)"));
+}
+
+#[test]
+fn strict_dump() {
+    let settings = Settings::new(String::from("tests/postgres/configs/strict.yml")).unwrap();
+    let mut dumper = PgDumper::new(
+        Engine::new(settings),
+        None,
+        helpers::pg_dump_path(),
+        io::sink(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap()
+    .with_strict();
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+
+    dumper.prepare(&mut connection).unwrap();
+    let uncovered = dumper.audit(&mut connection).unwrap();
+    assert_eq!(uncovered.len(), 1);
+    assert_eq!(uncovered[0].name, "public.customer");
+    let columns: Vec<_> = uncovered[0].columns.iter().map(|c| &c.name).collect();
+    assert_eq!(columns, vec!["first_name"]);
+
+    let err = dumper.dump(&mut connection).unwrap_err().to_string();
+    assert!(err.contains("public.customer.first_name: confidence high"));
+}
+
+#[test]
+fn preview() {
+    let settings = Settings::new(String::from("tests/postgres/configs/preview.yml")).unwrap();
+    let mut dumper = PgDumper::new(
+        Engine::new(settings),
+        None,
+        helpers::pg_dump_path(),
+        io::sink(),
+        SilentIndicator,
+        vec![],
+    )
+    .unwrap();
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    dumper.prepare(&mut connection).unwrap();
+
+    let preview = dumper.preview(&mut connection, "actor", 2).unwrap();
+    assert_eq!(preview.table, "public.actor");
+    assert_eq!(preview.columns, vec!["first_name", "last_name"]);
+    assert_eq!(preview.rows.len(), 2);
+    for row in &preview.rows {
+        let (first_name, last_name) = (row[0].1.as_ref().unwrap(), row[1].1.as_ref().unwrap());
+        let actor_id: i32 = first_name.strip_prefix("Actor ").unwrap().parse().unwrap();
+        assert!(actor_id > 10);
+        assert_eq!(last_name, &format!("{} Jr.", first_name));
+        assert_ne!(row[0].0.as_ref(), Some(first_name));
+    }
+
+    assert!(dumper.preview(&mut connection, "unknown", 2).is_err());
+}
+
+fn check_simple_dump(name: &str) {
     let mut src_client = helpers::src_client();
-    let mut dst_client = helpers::dst_client("simple");
+    let mut dst_client = helpers::dst_client(name);
 
     let count_query = "SELECT COUNT(*) FROM actor";
     let src_count: i64 = src_client.query_one(count_query, &[]).unwrap().get(0);
@@ -60,3 +318,95 @@ fn simple_dump() {
         );
     }
 }
+
+#[test]
+fn parallel_dump() {
+    dump_with("parallel", 3, 2);
+
+    let mut src_client = helpers::src_client();
+    let mut dst_client = helpers::dst_client("parallel");
+
+    for table in [
+        "actor",
+        "category",
+        "film",
+        "film_actor",
+        "film_category",
+        "language",
+    ] {
+        let count_query = format!("SELECT COUNT(*) FROM {}", table);
+        let src_count: i64 = src_client.query_one(&count_query, &[]).unwrap().get(0);
+        let dst_count: i64 = dst_client.query_one(&count_query, &[]).unwrap().get(0);
+        assert_eq!(src_count, dst_count, "{}", table);
+    }
+
+    let names_query = "SELECT first_name || ' ' || last_name FROM actor ORDER BY actor_id";
+    let src_names: Vec<String> = src_client
+        .query(names_query, &[])
+        .unwrap()
+        .iter()
+        .map(|r| r.get(0))
+        .collect();
+    let dst_names: Vec<String> = dst_client
+        .query(names_query, &[])
+        .unwrap()
+        .iter()
+        .map(|r| r.get(0))
+        .collect();
+    assert_ne!(src_names, dst_names);
+}
+
+#[test]
+fn parallel_store_dump() {
+    dump_with("parallel_store", 3, 1);
+
+    let mut dst_client = helpers::dst_client("parallel_store");
+    let names: Vec<String> = dst_client
+        .query("SELECT name FROM category ORDER BY category_id", &[])
+        .unwrap()
+        .iter()
+        .map(|r| r.get(0))
+        .collect();
+    assert!(!names.is_empty());
+    for (i, name) in names.iter().enumerate() {
+        assert_eq!(name, &format!("Actor {}", i + 185));
+    }
+}
+
+#[test]
+fn subset_dump() {
+    dump_with("subset", 2, 1);
+
+    let mut src_client = helpers::src_client();
+    let mut dst_client = helpers::dst_client("subset");
+    let count = |client: &mut postgres::Client, query: &str| -> i64 {
+        client.query_one(query, &[]).unwrap().get(0)
+    };
+
+    assert_eq!(count(&mut dst_client, "SELECT COUNT(*) FROM film"), 10);
+    for query in [
+        "SELECT COUNT(*) FROM film_actor WHERE film_id <= 10",
+        "SELECT COUNT(*) FROM film_category WHERE film_id <= 10",
+        "SELECT COUNT(DISTINCT actor_id) FROM film_actor WHERE film_id <= 10",
+        "SELECT COUNT(DISTINCT language_id) FROM film WHERE film_id <= 10",
+    ] {
+        assert_eq!(
+            count(&mut src_client, query),
+            count(&mut dst_client, query),
+            "{}",
+            query
+        );
+    }
+    assert_eq!(
+        count(&mut dst_client, "SELECT COUNT(*) FROM actor"),
+        count(
+            &mut src_client,
+            "SELECT COUNT(DISTINCT actor_id) FROM film_actor WHERE film_id <= 10"
+        )
+    );
+
+    // All foreign keys are restored, so the subset is referentially complete
+    let fk_query = "SELECT COUNT(*) FROM pg_constraint WHERE contype = 'f' \
+        AND conrelid::regclass::text IN ('film', 'film_actor', 'film_category')";
+    assert_eq!(count(&mut dst_client, fk_query), 5);
+}
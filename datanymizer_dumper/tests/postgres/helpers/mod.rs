@@ -1,6 +1,7 @@
 use postgres::{Client, NoTls};
 use std::{
     env,
+    path::Path,
     process::{Child, ChildStdin, Command, Stdio},
     sync::Once,
 };
@@ -21,8 +22,9 @@ pub fn io(&mut self) -> ChildStdin {
         self.0.stdin.take().unwrap()
     }
 
+    // psql exits when the dumper closes its stdin
     pub fn close(&mut self) {
-        self.0.kill().unwrap();
+        self.0.wait().unwrap();
     }
 }
 
@@ -83,6 +85,27 @@ pub fn dst_wrapper(name: &str) -> DstWrapper {
     )
 }
 
+/// Creates an empty destination database
+pub fn create_dst_db(name: &str) -> Url {
+    create_src_db();
+
+    let dst_url = dst_database_url(name);
+    create_db(&dst_url);
+    dst_url
+}
+
+/// Restores a `pg_dump` archive to the destination database
+pub fn restore_archive(name: &str, path: &Path) {
+    let dst_url = create_dst_db(name);
+
+    let status = pg_restore_command()
+        .args(["--exit-on-error", "-j", "2", "-d", dst_url.as_str()])
+        .arg(path)
+        .status()
+        .expect("Error when restoring the archive");
+    assert!(status.success(), "pg_restore has failed");
+}
+
 fn create_db(url: &Url) {
     let db_name = url.path_segments().unwrap().next().unwrap().to_string();
 
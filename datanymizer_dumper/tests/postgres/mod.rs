@@ -4,4 +4,6 @@
 
 mod connector;
 mod dumper;
+mod scanner;
 mod schema_inspector;
+mod validator;
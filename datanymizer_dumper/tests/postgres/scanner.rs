@@ -0,0 +1,45 @@
+use super::helpers;
+
+use datanymizer_dumper::{
+    postgres::{connector::Connection, scanner::PgScanner},
+    scan::{ColumnRule, Confidence, Pii, TableScan},
+};
+use datanymizer_engine::Settings;
+
+fn detected(tables: &[TableScan], full_name: &str) -> Vec<(String, Pii, Confidence)> {
+    tables
+        .iter()
+        .find(|t| t.name == full_name)
+        .unwrap()
+        .columns
+        .iter()
+        .map(|c| match &c.rule {
+            ColumnRule::Pii(d) => (c.name.clone(), d.pii, d.confidence),
+            rule => panic!("unexpected rule {:?}", rule),
+        })
+        .collect()
+}
+
+#[test]
+fn scan() {
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    let tables = PgScanner::new(50).scan(&mut connection).unwrap();
+
+    assert_eq!(
+        detected(&tables, "public.customer"),
+        vec![
+            (String::from("first_name"), Pii::FirstName, Confidence::High),
+            (String::from("last_name"), Pii::LastName, Confidence::High),
+            (String::from("email"), Pii::Email, Confidence::High),
+        ]
+    );
+    assert_eq!(
+        detected(&tables, "public.address"),
+        vec![(String::from("phone"), Pii::Phone, Confidence::High)]
+    );
+    assert!(detected(&tables, "public.category").is_empty());
+
+    let config = datanymizer_dumper::scan::draft_config(&tables);
+    let settings = Settings::from_yaml(&config).unwrap();
+    assert!(settings.get_table("public.customer").is_some());
+}
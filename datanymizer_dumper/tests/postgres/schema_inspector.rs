@@ -4,6 +4,7 @@
     postgres::{connector::Connection, schema_inspector::PgSchemaInspector, table::PgTable},
     SchemaInspector, Table,
 };
+use datanymizer_engine::ColumnType;
 
 fn find_table<'a>(tables: &'a [PgTable], full_name: &str) -> &'a PgTable {
     tables
@@ -82,3 +83,28 @@ fn get_tables() {
         vec!["public.customer", "public.rental", "public.staff"],
     );
 }
+
+#[test]
+fn column_types() {
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    let tables = PgSchemaInspector.get_tables(&mut connection).unwrap();
+
+    let types = find_table(&tables, "public.address").get_column_types();
+    assert_eq!(
+        types["district"],
+        ColumnType::new("character varying")
+            .with_length(20)
+            .not_null()
+    );
+    assert_eq!(
+        types["postal_code"],
+        ColumnType::new("character varying").with_length(10)
+    );
+    assert_eq!(types["city_id"], ColumnType::new("smallint").not_null());
+
+    let types = find_table(&tables, "public.payment").get_column_types();
+    assert_eq!(
+        types["amount"],
+        ColumnType::new("numeric").with_precision(5, 2).not_null()
+    );
+}
@@ -0,0 +1,64 @@
+use super::helpers;
+
+use datanymizer_dumper::postgres::{connector::Connection, validator::PgValidator};
+use datanymizer_engine::validation::Validation;
+use std::fs;
+
+#[test]
+fn validate() {
+    let config = fs::read_to_string("tests/postgres/configs/invalid.yml").unwrap();
+    let mut validation = Validation::new(&config);
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    PgValidator
+        .validate(&mut connection, &mut validation)
+        .unwrap();
+
+    assert_eq!(
+        validation.report("invalid.yml"),
+        "invalid.yml:7:7: tables[0].rules.frist_name: \
+        The column `frist_name` doesn't exist in the table `actor`\n\
+        invalid.yml:11:5: tables[1].name: The table `public.unknown` doesn't exist\n\
+        invalid.yml:4:7: tables[0].query.dump_condition: \
+        Invalid SQL condition: column \"last_nam\" does not exist"
+    );
+}
+
+#[test]
+fn schema() {
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    let schema = PgValidator.schema(&mut connection).unwrap();
+
+    assert!(schema["public.actor"].contains(&String::from("first_name")));
+}
+
+#[test]
+fn type_warnings() {
+    let config = r#"tables:
+  - name: actor
+    rules:
+      first_name:
+        template:
+          format: "A first name that is longer than forty-five characters"
+  - name: address
+    rules:
+      address2:
+        set_null: ~
+      phone:
+        set_null: ~
+"#;
+    let mut validation = Validation::new(config);
+    let mut connection = Connection::new(helpers::src_client(), helpers::src_database_url());
+    PgValidator
+        .validate(&mut connection, &mut validation)
+        .unwrap();
+
+    assert!(validation.is_valid());
+    assert_eq!(
+        validation.report_warnings("types.yml"),
+        "types.yml:4:7: tables[0].rules.first_name: warning: The rule `actor.first_name` may produce \
+        values that don't fit the column type `character varying(45)`: the value is longer than 45 characters \
+        (see `on_overflow`)\n\
+        types.yml:11:7: tables[1].rules.phone: warning: The rule `address.phone` may produce NULL values, \
+        but the column is NOT NULL"
+    );
+}
@@ -0,0 +1,117 @@
+use super::helpers;
+
+#[test]
+fn dump_to_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let src_path = helpers::src_database(dir.path());
+    let src = rusqlite::Connection::open(&src_path).unwrap();
+    let dst = helpers::dump_database("simple", &src_path, &dir.path().join("dst.sqlite"));
+
+    let users_query = "SELECT id, name, email, avatar, rating, name_upper FROM users ORDER BY id";
+    let src_users = helpers::rows(&src, users_query);
+    let dst_users = helpers::rows(&dst, users_query);
+    assert_eq!(dst_users.len(), 4);
+    for (src_user, dst_user) in src_users.iter().zip(&dst_users).take(3) {
+        assert_eq!(dst_user[0], src_user[0]);
+        assert_eq!(dst_user[1], format!("User {}", src_user[0]));
+        assert_ne!(dst_user[2], src_user[2]);
+        // Untouched values keep their storage classes
+        assert_eq!(dst_user[3], src_user[3]);
+        assert_eq!(dst_user[4], "NULL");
+        // Generated columns are computed again
+        assert_eq!(dst_user[5], dst_user[1].to_uppercase());
+    }
+    // Rows that don't match `transform_condition` are the same
+    assert_eq!(dst_users[3], src_users[3]);
+
+    // Linked foreign keys are transformed the same way as the referenced columns
+    let orders_query = "SELECT orders.id, orders.note, users.id
+        FROM orders LEFT JOIN users ON users.email = orders.user_email ORDER BY orders.id";
+    let dst_orders = helpers::rows(&dst, orders_query);
+    assert_eq!(dst_orders, helpers::rows(&src, orders_query));
+    assert_eq!(dst_orders[0][1], "Multi-line\nnote");
+
+    // The data of the filtered table isn't dumped, the trigger is created after the data
+    assert_eq!(
+        helpers::rows(&dst, r#"SELECT COUNT(*) FROM "audit log""#),
+        vec![vec!["0"]]
+    );
+    assert_eq!(
+        helpers::rows(
+            &dst,
+            "SELECT type, name FROM sqlite_schema WHERE type IN ('index', 'view', 'trigger') AND sql IS NOT NULL ORDER BY name"
+        ),
+        vec![
+            vec!["trigger", "orders_insert"],
+            vec!["index", "orders_user_id"],
+            vec!["view", "user_orders"],
+        ]
+    );
+    assert_eq!(
+        helpers::rows(&dst, "SELECT name, seq FROM sqlite_sequence"),
+        vec![vec!["users", "4"]]
+    );
+}
+
+#[test]
+fn dump_to_script() {
+    let dir = tempfile::tempdir().unwrap();
+    let src_path = helpers::src_database(dir.path());
+    let script = helpers::dump_script("simple", &src_path, &dir.path().join("dump.sql"));
+
+    assert!(
+        script.starts_with("PRAGMA foreign_keys = OFF;\nBEGIN TRANSACTION;\nCREATE TABLE users")
+    );
+    assert!(script.contains("\n--\n-- Start dumping data\n--\n"));
+    assert!(script.ends_with("COMMIT;\n"));
+
+    let script_db = rusqlite::Connection::open_in_memory().unwrap();
+    script_db.execute_batch(&script).unwrap();
+    let dst = helpers::dump_database("simple", &src_path, &dir.path().join("dst.sqlite"));
+
+    // Transformations are random, so only untouched columns are compared
+    for query in [
+        "SELECT id, avatar, rating FROM users ORDER BY id",
+        "SELECT id, user_id, note FROM orders ORDER BY id",
+        "SELECT type, name, sql FROM sqlite_schema ORDER BY name",
+    ] {
+        assert_eq!(helpers::rows(&script_db, query), helpers::rows(&dst, query));
+    }
+}
+
+#[test]
+fn filter_and_query() {
+    let dir = tempfile::tempdir().unwrap();
+    let src_path = helpers::src_database(dir.path());
+    let dst = helpers::dump_database("filter", &src_path, &dir.path().join("dst.sqlite"));
+
+    assert_eq!(
+        helpers::rows(&dst, "SELECT id FROM users ORDER BY id"),
+        vec![vec!["2"], vec!["3"]]
+    );
+    // Indexes and triggers of filtered tables are skipped
+    assert_eq!(
+        helpers::rows(
+            &dst,
+            "SELECT type, name FROM sqlite_schema WHERE name NOT LIKE 'sqlite_%' ORDER BY name"
+        ),
+        vec![vec!["view", "user_orders"], vec!["table", "users"]]
+    );
+}
+
+#[test]
+fn existing_target() {
+    let dir = tempfile::tempdir().unwrap();
+    let src_path = helpers::src_database(dir.path());
+
+    let result = datanymizer_dumper::sqlite::dumper::SqliteDumper::new(
+        datanymizer_engine::Engine::new(
+            datanymizer_engine::Settings::from_yaml("tables: []").unwrap(),
+        ),
+        std::io::sink(),
+        datanymizer_dumper::indicator::SilentIndicator,
+    )
+    .unwrap()
+    .with_target(src_path);
+    assert!(result.is_err());
+}
@@ -0,0 +1,43 @@
+CREATE TABLE users (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  email TEXT NOT NULL UNIQUE,
+  avatar BLOB,
+  rating REAL,
+  name_upper TEXT GENERATED ALWAYS AS (upper(name)) VIRTUAL
+);
+
+CREATE TABLE orders (
+  id INTEGER PRIMARY KEY,
+  user_id INTEGER NOT NULL REFERENCES users,
+  user_email TEXT NOT NULL REFERENCES users (email),
+  note TEXT
+);
+
+CREATE TABLE "audit log" (
+  user_id INTEGER,
+  message TEXT
+);
+
+CREATE INDEX orders_user_id ON orders (user_id);
+
+CREATE VIEW user_orders AS
+  SELECT users.name, orders.note FROM users JOIN orders ON orders.user_id = users.id;
+
+CREATE TRIGGER orders_insert AFTER INSERT ON orders
+  BEGIN
+    INSERT INTO "audit log" (user_id, message) VALUES (NEW.user_id, 'new order');
+  END;
+
+INSERT INTO users (id, name, email, avatar, rating) VALUES
+  (1, 'John O''Neil', 'john@example.com', X'89504E470D0A1A0A00FF', 4.5),
+  (2, 'Ann', 'ann@example.com', NULL, NULL),
+  (3, 'Emma', 'emma@example.com', X'', 3.0),
+  (4, 'Nick', 'nick@example.com', NULL, 1e100);
+
+INSERT INTO orders (id, user_id, user_email, note) VALUES
+  (1, 1, 'john@example.com', 'Multi-line
+note'),
+  (2, 2, 'ann@example.com', NULL),
+  (3, 3, 'emma@example.com', 'Order 3');
+
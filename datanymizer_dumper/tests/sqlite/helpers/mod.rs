@@ -0,0 +1,79 @@
+use datanymizer_dumper::{
+    indicator::SilentIndicator,
+    sqlite::{
+        connector::{Connection, Connector},
+        dumper::SqliteDumper,
+    },
+    Dumper,
+};
+use datanymizer_engine::{Engine, Settings};
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+
+const SRC_DUMP_PATH: &str = "tests/sqlite/dumps/common.sql";
+
+/// Creates the source database in the directory
+pub fn src_database(dir: &Path) -> PathBuf {
+    let path = dir.join("src.sqlite");
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(&fs::read_to_string(SRC_DUMP_PATH).unwrap())
+        .unwrap();
+    path
+}
+
+pub fn src_connection(path: &Path) -> Connection {
+    Connector::new(path.to_path_buf()).connect().unwrap()
+}
+
+fn engine(config: &str) -> Engine {
+    let settings = Settings::new(format!("tests/sqlite/configs/{}.yml", config)).unwrap();
+    Engine::new(settings)
+}
+
+/// Dumps the source database to the SQL script
+pub fn dump_script(config: &str, src: &Path, dst: &Path) -> String {
+    let file = File::create(dst).unwrap();
+    let mut dumper = SqliteDumper::new(engine(config), file, SilentIndicator).unwrap();
+    dumper.dump(&mut src_connection(src)).unwrap();
+    drop(dumper);
+
+    fs::read_to_string(dst).unwrap()
+}
+
+/// Dumps the source database to a new database file
+pub fn dump_database(config: &str, src: &Path, dst: &Path) -> rusqlite::Connection {
+    let mut dumper = SqliteDumper::new(engine(config), io::sink(), SilentIndicator)
+        .unwrap()
+        .with_target(dst.to_path_buf())
+        .unwrap();
+    dumper.dump(&mut src_connection(src)).unwrap();
+    drop(dumper);
+
+    rusqlite::Connection::open(dst).unwrap()
+}
+
+/// Rows of the query as strings (`NULL` for NULL values, hex for blobs)
+pub fn rows(conn: &rusqlite::Connection, query: &str) -> Vec<Vec<String>> {
+    let mut statement = conn.prepare(query).unwrap();
+    let count = statement.column_count();
+    statement
+        .query_map([], |row| {
+            (0..count)
+                .map(|i| {
+                    Ok(match row.get::<_, rusqlite::types::Value>(i)? {
+                        rusqlite::types::Value::Null => String::from("NULL"),
+                        rusqlite::types::Value::Integer(v) => v.to_string(),
+                        rusqlite::types::Value::Real(v) => format!("{:?}", v),
+                        rusqlite::types::Value::Text(v) => v,
+                        rusqlite::types::Value::Blob(v) => format!("X{:02X?}", v),
+                    })
+                })
+                .collect()
+        })
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap()
+}
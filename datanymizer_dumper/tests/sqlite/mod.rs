@@ -0,0 +1,6 @@
+#![cfg(feature = "sqlite")]
+
+mod helpers;
+
+mod dumper;
+mod schema_inspector;
@@ -0,0 +1,56 @@
+use super::helpers;
+
+use datanymizer_dumper::{
+    sqlite::schema_inspector::SqliteSchemaInspector, ColumnData, SchemaInspector, Table,
+};
+
+#[test]
+fn get_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut connection = helpers::src_connection(&helpers::src_database(dir.path()));
+    let tables = SqliteSchemaInspector.get_tables(&mut connection).unwrap();
+
+    let names: Vec<_> = tables.iter().map(|t| t.get_name()).collect();
+    assert_eq!(names, vec!["users", "orders", "audit log"]);
+
+    // Generated columns are skipped
+    let users = &tables[0];
+    assert_eq!(
+        users.get_columns_names(),
+        vec!["id", "name", "email", "avatar", "rating"]
+    );
+    assert_eq!(users.get_columns()[3].inner_kind().unwrap(), "BLOB");
+    assert_eq!(users.get_size(), 4);
+
+    let orders = &tables[1];
+    let mut foreign_keys = orders.get_foreign_key_columns();
+    foreign_keys.sort();
+    assert_eq!(
+        foreign_keys,
+        vec![
+            (
+                String::from("user_email"),
+                String::from("users"),
+                String::from("email")
+            ),
+            // The primary key is referenced implicitly
+            (
+                String::from("user_id"),
+                String::from("users"),
+                String::from("id")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn ordered_tables() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut connection = helpers::src_connection(&helpers::src_database(dir.path()));
+    let tables = SqliteSchemaInspector
+        .ordered_tables(&mut connection)
+        .unwrap();
+
+    let weight = |name: &str| tables.iter().find(|(t, _)| t.get_name() == name).unwrap().1;
+    assert!(weight("users") > weight("orders"));
+}
@@ -0,0 +1,59 @@
+//! Anonymizes rows in a Rust application: `cargo run --example anonymize_rows`
+
+use datanymizer_engine::{
+    transformers::{EmailTransformer, FirstNameTransformer},
+    Engine, Rule, Settings, Transformers,
+};
+use serde_json::json;
+use std::collections::HashMap;
+
+fn main() -> anyhow::Result<()> {
+    let settings = Settings::builder()
+        .rule(
+            "users",
+            "email",
+            Rule::from(Transformers::Email(EmailTransformer::default())).with_domain("email"),
+        )
+        .rule(
+            "users",
+            "first_name",
+            Transformers::FirstName(FirstNameTransformer::default()),
+        )
+        .rule(
+            "orders",
+            "customer_email",
+            Rule::from(Transformers::Email(EmailTransformer::default())).with_domain("email"),
+        )
+        .build()?;
+    // The same config can be loaded from a file: `Settings::new(String::from("config.yml"))?`
+    let engine = Engine::new(settings);
+
+    // Rows with the same columns
+    let users = engine.table_transformer("users", ["id", "first_name", "email"])?;
+    for row in [
+        [Some("1"), Some("John"), Some("john@example.com")],
+        [Some("2"), Some("Ann"), None],
+    ] {
+        println!("{:?}", users.transform(&row)?);
+    }
+
+    // Named values (`None` is NULL)
+    let order = HashMap::from([
+        ("id", Some("10")),
+        ("customer_email", Some("john@example.com")),
+    ]);
+    let order: HashMap<String, Option<String>> = engine
+        .transform_named("orders", order)?
+        .into_iter()
+        .collect();
+    println!("{:?}", order);
+
+    // JSON objects
+    let user = engine.transform_json(
+        "users",
+        &json!({"id": 3, "first_name": "Kate", "email": "kate@example.com"}),
+    )?;
+    println!("{}", user);
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+};
+
+/// Metadata of the column type (dumpers set it with [crate::Settings::set_column_types])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnType {
+    /// The type name (e.g. `smallint` or `character varying`), MySQL unsigned integers have the `unsigned` suffix
+    pub name: String,
+    /// Maximum length of character types (in characters)
+    pub length: Option<u32>,
+    /// Precision of `numeric` (`decimal`) types
+    pub precision: Option<u32>,
+    /// Scale of `numeric` (`decimal`) types
+    pub scale: Option<u32>,
+    pub nullable: bool,
+}
+
+impl ColumnType {
+    /// A nullable type without limits
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            length: None,
+            precision: None,
+            scale: None,
+            nullable: true,
+        }
+    }
+
+    pub fn with_length(mut self, length: u32) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    pub fn with_precision(mut self, precision: u32, scale: u32) -> Self {
+        self.precision = Some(precision);
+        self.scale = Some(scale);
+        self
+    }
+
+    pub fn not_null(mut self) -> Self {
+        self.nullable = false;
+        self
+    }
+
+    /// Checks that the value can be written to the column. Non-numeric values of numeric types
+    /// are not checked (the database reports them).
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        if let Some(length) = self.length {
+            if value.chars().count() > length as usize {
+                return Err(format!("the value is longer than {} characters", length));
+            }
+        }
+        if let Some((min, max)) = self.range() {
+            if let Some(number) = parse_number(value) {
+                if number < min || number > max {
+                    return Err(format!("the value is out of range of `{}`", self));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Truncates strings and clamps numbers, so they can be written to the column
+    pub fn fit<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        if let Some(length) = self.length {
+            if let Some((i, _)) = value.char_indices().nth(length as usize) {
+                return Cow::Owned(value[..i].to_string());
+            }
+        }
+
+        let (limits, number) = match self.limits().zip(parse_number(value)) {
+            Some(limits_and_number) => limits_and_number,
+            None => return Cow::Borrowed(value),
+        };
+        match limits {
+            Limits::Integer(min, _) if number < min as f64 => Cow::Owned(min.to_string()),
+            Limits::Integer(_, max) if number > max as f64 => Cow::Owned(max.to_string()),
+            Limits::Decimal(min, max, scale) if number < min || number > max => {
+                let bound = if number < min { min } else { max };
+                Cow::Owned(format!("{:.*}", scale, bound))
+            }
+            _ => Cow::Borrowed(value),
+        }
+    }
+
+    /// Minimum and maximum values of numeric types with limits
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.limits().map(|limits| match limits {
+            Limits::Integer(min, max) => (min as f64, max as f64),
+            Limits::Decimal(min, max, _) => (min, max),
+        })
+    }
+
+    fn limits(&self) -> Option<Limits> {
+        let name = self.name.to_lowercase();
+        let (name, unsigned) = match name.strip_suffix(" unsigned") {
+            Some(name) => (name, true),
+            None => (name.as_str(), false),
+        };
+        let bits = match name {
+            "tinyint" => 8,
+            "smallint" | "int2" => 16,
+            "mediumint" => 24,
+            "integer" | "int" | "int4" => 32,
+            "bigint" | "int8" => 64,
+            "numeric" | "decimal" => {
+                let precision = self.precision? as i32;
+                let scale = self.scale.unwrap_or(0);
+                let max = 10f64.powi(precision - scale as i32) - 10f64.powi(-(scale as i32));
+                let min = if unsigned { 0.0 } else { -max };
+                return Some(Limits::Decimal(min, max, scale as usize));
+            }
+            _ => return None,
+        };
+
+        Some(if unsigned {
+            Limits::Integer(0, (1 << bits) - 1)
+        } else {
+            Limits::Integer(-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+        })
+    }
+}
+
+enum Limits {
+    Integer(i128, i128),
+    /// Minimum, maximum and scale
+    Decimal(f64, f64, usize),
+}
+
+impl Display for ColumnType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.length, self.precision) {
+            (Some(length), _) => write!(f, "{}({})", self.name, length),
+            (None, Some(precision)) => {
+                write!(
+                    f,
+                    "{}({},{})",
+                    self.name,
+                    precision,
+                    self.scale.unwrap_or(0)
+                )
+            }
+            (None, None) => write!(f, "{}", self.name),
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(ColumnType::new("smallint").to_string(), "smallint");
+        assert_eq!(
+            ColumnType::new("character varying")
+                .with_length(20)
+                .to_string(),
+            "character varying(20)"
+        );
+        assert_eq!(
+            ColumnType::new("numeric").with_precision(5, 2).to_string(),
+            "numeric(5,2)"
+        );
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(
+            ColumnType::new("smallint").range(),
+            Some((-32768.0, 32767.0))
+        );
+        assert_eq!(
+            ColumnType::new("tinyint unsigned").range(),
+            Some((0.0, 255.0))
+        );
+        assert_eq!(
+            ColumnType::new("numeric").with_precision(5, 2).range(),
+            Some((-999.99, 999.99))
+        );
+        assert_eq!(ColumnType::new("numeric").range(), None);
+        assert_eq!(ColumnType::new("text").range(), None);
+    }
+
+    #[test]
+    fn check() {
+        let smallint = ColumnType::new("smallint");
+        assert!(smallint.check("32767").is_ok());
+        assert!(smallint.check("not a number").is_ok());
+        assert_eq!(
+            smallint.check("100000").unwrap_err(),
+            "the value is out of range of `smallint`"
+        );
+
+        let varchar = ColumnType::new("character varying").with_length(3);
+        assert!(varchar.check("абв").is_ok());
+        assert_eq!(
+            varchar.check("abcd").unwrap_err(),
+            "the value is longer than 3 characters"
+        );
+    }
+
+    #[test]
+    fn fit() {
+        let smallint = ColumnType::new("smallint");
+        assert_eq!(smallint.fit("100000"), "32767");
+        assert_eq!(smallint.fit("-100000"), "-32768");
+        assert!(matches!(smallint.fit("100"), Cow::Borrowed("100")));
+        assert_eq!(ColumnType::new("bigint").fit("1e30"), "9223372036854775807");
+
+        let numeric = ColumnType::new("numeric").with_precision(4, 1);
+        assert_eq!(numeric.fit("12345.67"), "999.9");
+
+        let varchar = ColumnType::new("character varying").with_length(3);
+        assert_eq!(varchar.fit("абвгд"), "абв");
+        assert!(matches!(varchar.fit("abc"), Cow::Borrowed("abc")));
+    }
+}
@@ -1,66 +1,367 @@
 use crate::{
     errors::{EngineError, UnknownColumnError},
-    Settings, TransformContext, Transformer,
+    settings::{OnNull, OnOverflow, Rule},
+    store::{KeyValueStore, SpillStore},
+    transformer::{TransformError, TransformResultHelper},
+    utils::rnd::{self, Seed},
+    Settings, TableTransformer, TransformContext, TransformResult, Transformer,
 };
+use rand::Rng;
+use serde_json::{Map, Value};
 use std::{borrow::Cow, collections::HashMap};
 
 pub struct Engine {
     pub settings: Settings,
+    /// Fake values for original values in consistency domains
+    domain_store: SpillStore,
 }
 
 impl Engine {
     pub fn new(settings: Settings) -> Self {
-        Self { settings }
+        let domain_store = settings.domains.store();
+        Self {
+            settings,
+            domain_store,
+        }
     }
 
+    /// Transforms values of the row (`None` is NULL). Untouched values are borrowed.
+    /// See [Engine::table_transformer] for rows with the same columns.
     pub fn process_row<'a>(
         &self,
         table: String,
         column_indexes: &HashMap<String, usize>,
-        values: &'a [&str],
-    ) -> Result<Vec<Cow<'a, str>>, EngineError> {
+        values: &[Option<&'a str>],
+    ) -> Result<Vec<Option<Cow<'a, str>>>, EngineError> {
+        if !self.settings.is_prepared() {
+            return Err(EngineError::UnpreparedSettings);
+        }
         let ts = self.settings.transformers_for(&table);
 
-        let mut transformed_values = Vec::with_capacity(values.len());
-        for &v in values {
-            transformed_values.push(Cow::from(v));
-        }
+        let mut transformed_values: Vec<Option<Cow<'a, str>>> =
+            values.iter().map(|v| v.map(Cow::Borrowed)).collect();
+        // Columns replaced with NULL, the next rules see them as transformed
+        let mut nulls = vec![false; values.len()];
 
         if let Some(ts) = ts {
-            for (field, tr) in ts {
-                if let Some(&i) = column_indexes.get(field) {
-                    match tr.transform(
-                        &format!("{}.{}", table, field),
-                        values[i],
-                        &Some(TransformContext::new(
+            for (field, rule) in ts {
+                let &i = column_indexes.get(field).ok_or_else(|| {
+                    EngineError::UnknownColumnError(UnknownColumnError {
+                        field_name: field.clone(),
+                    })
+                })?;
+                let value = match (values[i], rule.on_null) {
+                    (Some(v), _) => v,
+                    (None, OnNull::Keep) => continue,
+                    (None, OnNull::Null) => {
+                        nulls[i] = true;
+                        continue;
+                    }
+                    (None, OnNull::Transform) => "",
+                };
+
+                let field_name = format!("{}.{}", table, field);
+                match self.apply_rule(
+                    rule,
+                    &field_name,
+                    value,
+                    &Some(
+                        TransformContext::new(
                             &self.settings.globals,
                             Some(column_indexes),
                             Some(values),
                             Some(&transformed_values),
-                        )),
-                    ) {
-                        Ok(Some(res)) => {
-                            transformed_values[i] = Cow::Owned(res);
-                        }
-                        Err(e) => return Err(EngineError::TransformFieldError(e)),
-                        _ => {}
+                        )
+                        .with_final_nulls(&nulls)
+                        .with_column_type(rule.column_type.as_ref()),
+                    ),
+                ) {
+                    Ok(res) => {
+                        let res = fit_type(rule, &field_name, res)
+                            .map_err(EngineError::TransformFieldError)?;
+                        nulls[i] = res.is_none();
+                        transformed_values[i] = res.map(Cow::Owned);
                     }
-                } else {
-                    return Err(EngineError::UnknownColumnError(UnknownColumnError {
-                        field_name: field.clone(),
-                    }));
+                    Err(e) => return Err(EngineError::TransformFieldError(e)),
                 }
             }
         }
 
         Ok(transformed_values)
     }
+
+    /// Whether rules of the table use the template store (e.g. `store_read` in templates),
+    /// so rows of the table must be transformed in their order
+    pub fn uses_store(&self, table: &str) -> bool {
+        self.settings
+            .transformers_for(table)
+            .is_some_and(|ts| ts.iter().any(|(_, rule)| rule.transformer.uses_store()))
+    }
+
+    /// Whether fake values of the table depend on the values generated before
+    /// (consistency domains, unique values or the template store),
+    /// so they can't be generated again from scratch (e.g. when a dump is resumed)
+    pub fn uses_shared_state(&self, table: &str) -> bool {
+        self.settings.transformers_for(table).is_some_and(|ts| {
+            ts.iter().any(|(_, rule)| {
+                rule.domain.is_some()
+                    || rule.transformer.uses_store()
+                    || rule.transformer.uses_uniq()
+            })
+        })
+    }
+
+    /// Creates the transformer for rows of the table with the given columns.
+    /// Fails if a rule of the table refers to a column that isn't listed.
+    pub fn table_transformer<I, S>(
+        &self,
+        table: &str,
+        columns: I,
+    ) -> Result<TableTransformer<'_>, EngineError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        TableTransformer::new(self, table, columns.into_iter().map(Into::into).collect())
+    }
+
+    /// Transforms a row of `(column, value)` pairs, `None` values are NULL.
+    /// The order of pairs is kept.
+    pub fn transform_named<I, K, V>(
+        &self,
+        table: &str,
+        row: I,
+    ) -> Result<Vec<(String, Option<String>)>, EngineError>
+    where
+        I: IntoIterator<Item = (K, Option<V>)>,
+        K: Into<String>,
+        V: AsRef<str>,
+    {
+        let (columns, originals): (Vec<String>, Vec<Option<V>>) =
+            row.into_iter().map(|(k, v)| (k.into(), v)).unzip();
+        let column_indexes = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| (column.clone(), i))
+            .collect();
+        let values: Vec<Option<&str>> = originals
+            .iter()
+            .map(|v| v.as_ref().map(|v| v.as_ref()))
+            .collect();
+        let transformed_values = self.process_row(table.to_string(), &column_indexes, &values)?;
+
+        Ok(columns
+            .into_iter()
+            .zip(transformed_values)
+            .map(|(column, value)| (column, value.map(Cow::into_owned)))
+            .collect())
+    }
+
+    /// Transforms a JSON object, its keys are columns (see [Engine::transform_json_object])
+    pub fn transform_json(&self, table: &str, row: &Value) -> Result<Value, EngineError> {
+        match row {
+            Value::Object(object) => Ok(self
+                .transform_json_object(table, object)?
+                .map_or_else(|| row.clone(), Value::Object)),
+            _ => Err(EngineError::InvalidRow(String::from(
+                "a JSON object is expected",
+            ))),
+        }
+    }
+
+    /// Keys of the object are columns. Strings are passed to transformers as they are,
+    /// other values (e.g. nested objects for the `json` transformer) - as JSON.
+    /// Columns with rules that are missed in the object are NULL.
+    /// Transformed values keep the JSON type of original ones if possible (e.g. numbers).
+    ///
+    /// Returns `None` if nothing is changed.
+    pub fn transform_json_object(
+        &self,
+        table: &str,
+        object: &Map<String, Value>,
+    ) -> Result<Option<Map<String, Value>>, EngineError> {
+        let mut columns: Vec<&String> = object.keys().collect();
+        if let Some(t) = self.settings.get_table(table) {
+            for column in t.rules.keys() {
+                if !object.contains_key(column) {
+                    columns.push(column);
+                }
+            }
+        }
+
+        let texts: Vec<Option<Cow<str>>> = columns
+            .iter()
+            .map(|&column| object.get(column).and_then(json_text))
+            .collect();
+        let transformed_values = self.transform_named(
+            table,
+            columns
+                .iter()
+                .copied()
+                .zip(texts.iter().map(|t| t.as_deref())),
+        )?;
+
+        let mut transformed = None;
+        for ((column, value), text) in transformed_values.into_iter().zip(texts) {
+            if value.as_deref() == text.as_deref() {
+                continue;
+            }
+            let original = object.get(&column);
+            let value = json_value(value, original);
+            transformed
+                .get_or_insert_with(|| object.clone())
+                .insert(column, value);
+        }
+        Ok(transformed)
+    }
+
+    fn apply_rule(
+        &self,
+        rule: &Rule,
+        field_name: &str,
+        field_value: &str,
+        ctx: &Option<TransformContext>,
+    ) -> TransformResult {
+        let domain = match &rule.domain {
+            Some(domain) => domain,
+            None => return self.transform(rule, field_name, field_value, ctx),
+        };
+
+        let key = format!("{}\0{}", domain, field_value);
+        let store_error = |e: anyhow::Error| TransformError {
+            field_name: field_name.to_string(),
+            field_value: field_value.to_string(),
+            reason: e.to_string(),
+        };
+
+        if let Some(v) = self.domain_store.read_value(&key).map_err(store_error)? {
+            return Ok(v.as_str().map(String::from));
+        }
+
+        // NULL results are stored as `null`
+        let result = self.transform(rule, field_name, field_value, ctx)?;
+        if let Err(e) = self
+            .domain_store
+            .write_value(key.clone(), Value::from(result.clone()))
+        {
+            // Another row with the same value may be written in the meantime
+            return match self.domain_store.read_value(&key) {
+                Ok(Some(v)) => Ok(v.as_str().map(String::from)),
+                _ => Err(store_error(e)),
+            };
+        }
+
+        Ok(result)
+    }
+
+    fn transform(
+        &self,
+        rule: &Rule,
+        field_name: &str,
+        field_value: &str,
+        ctx: &Option<TransformContext>,
+    ) -> TransformResult {
+        let transform = || match rule.nullify {
+            Some(p) if rnd::rng().gen_bool(p) => TransformResult::null(),
+            _ => rule.transformer.transform(field_name, field_value, ctx),
+        };
+
+        match self.seed_for(rule, field_name, field_value) {
+            Some(seed) => rnd::with_seed(seed, transform),
+            None => transform(),
+        }
+    }
+
+    fn seed_for(&self, rule: &Rule, field_name: &str, field_value: &str) -> Option<Seed> {
+        if !rule.deterministic {
+            return None;
+        }
+
+        // Rules in the same domain share seeds, so they stay consistent between runs too
+        let name = rule.domain.as_deref().unwrap_or(field_name);
+        self.settings
+            .seed
+            .as_ref()
+            .map(|secret| rnd::derive_seed(secret.as_bytes(), name, field_value))
+    }
+}
+
+/// Applies [Rule::on_overflow] to the transformed value
+fn fit_type(
+    rule: &Rule,
+    field_name: &str,
+    value: Option<String>,
+) -> Result<Option<String>, TransformError> {
+    let (on_overflow, column_type) = match (rule.on_overflow, &rule.column_type) {
+        (Some(on_overflow), Some(column_type)) => (on_overflow, column_type),
+        _ => return Ok(value),
+    };
+    let error = |value: &str, reason: &str| TransformError {
+        field_name: field_name.to_string(),
+        field_value: value.to_string(),
+        reason: format!(
+            "The value of `{}` doesn't fit the column: {}",
+            field_name, reason
+        ),
+    };
+
+    let value = match value {
+        Some(value) => value,
+        None if column_type.nullable => return Ok(None),
+        None => return Err(error("NULL", "the column is NOT NULL")),
+    };
+    match on_overflow {
+        OnOverflow::Truncate => match column_type.fit(&value) {
+            Cow::Borrowed(_) => Ok(Some(value)),
+            Cow::Owned(fitted) => Ok(Some(fitted)),
+        },
+        OnOverflow::Error => match column_type.check(&value) {
+            Ok(()) => Ok(Some(value)),
+            Err(reason) => Err(error(&value, &reason)),
+        },
+    }
+}
+
+/// The value for transformers
+fn json_text(value: &Value) -> Option<Cow<'_, str>> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(Cow::Borrowed(s)),
+        value => Some(Cow::Owned(value.to_string())),
+    }
+}
+
+fn json_value(value: Option<String>, original: Option<&Value>) -> Value {
+    let value = match value {
+        Some(value) => value,
+        None => return Value::Null,
+    };
+    match original {
+        None | Some(Value::String(_)) => Value::String(value),
+        Some(_) => serde_json::from_str(&value).unwrap_or(Value::String(value)),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Transforms a row without NULL values
+    fn process_values<'a>(
+        engine: &Engine,
+        table: &str,
+        column_indexes: &HashMap<String, usize>,
+        values: &[&'a str],
+    ) -> Vec<Cow<'a, str>> {
+        let values: Vec<Option<&str>> = values.iter().map(|&v| Some(v)).collect();
+        engine
+            .process_row(table.to_string(), column_indexes, &values)
+            .unwrap()
+            .into_iter()
+            .map(Option::unwrap)
+            .collect()
+    }
+
     #[test]
     fn process_row() {
         let config = r#"
@@ -89,9 +390,7 @@ fn process_row() {
         column_indexes.insert(String::from("one_more_column"), 3);
         column_indexes.insert(String::from("last_update"), 4);
 
-        let tr_values = Engine::new(settings)
-            .process_row(table, &column_indexes, &values)
-            .unwrap();
+        let tr_values = process_values(&Engine::new(settings), &table, &column_indexes, &values);
 
         assert_ne!(tr_values[0], "");
         assert_ne!(tr_values[1], "");
@@ -100,6 +399,507 @@ fn process_row() {
         assert_ne!(tr_values[4], "");
     }
 
+    #[test]
+    fn unprepared_settings() {
+        let settings: Settings = serde_yaml::from_str("tables: []").unwrap();
+        let column_indexes = HashMap::from([(String::from("name"), 0)]);
+
+        assert!(matches!(
+            Engine::new(settings).process_row(
+                String::from("users"),
+                &column_indexes,
+                &[Some("John")]
+            ),
+            Err(EngineError::UnpreparedSettings)
+        ));
+    }
+
+    #[test]
+    fn uses_store() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ _0 }}"
+                options:
+                  json:
+                    fields:
+                      - name: "code"
+                        selector: "$..code"
+                        rule:
+                          pipeline:
+                            pipes:
+                              - template:
+                                  format: "{{ store_read(key=_0, default='') }}"
+            - name: orders
+              rules:
+                comment:
+                  template:
+                    format: "{{ _1 }}"
+                    rules:
+                      - first_name: {}
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+
+        assert!(engine.uses_store("users"));
+        assert!(!engine.uses_store("orders"));
+        assert!(!engine.uses_store("unknown"));
+    }
+
+    #[test]
+    fn uses_shared_state() {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                email:
+                  email:
+                    uniq: true
+            - name: orders
+              rules:
+                comment:
+                  template:
+                    format: "{{ _1 }}"
+                    rules:
+                      - email:
+                          uniq: true
+            - name: clients
+              rules:
+                name:
+                  first_name: {}
+                  domain: names
+            - name: logs
+              rules:
+                ip:
+                  ip: {}
+        "#;
+        let engine = Engine::new(Settings::from_yaml(config).unwrap());
+
+        assert!(engine.uses_shared_state("users"));
+        assert!(engine.uses_shared_state("orders"));
+        assert!(engine.uses_shared_state("clients"));
+        assert!(!engine.uses_shared_state("logs"));
+        assert!(!engine.uses_shared_state("unknown"));
+    }
+
+    mod named_rows {
+        use super::*;
+        use serde_json::json;
+
+        fn engine() -> Engine {
+            let config = r#"
+              tables:
+                - name: users
+                  rules:
+                    name:
+                      template:
+                        format: "{{ _0 }} Jr."
+                    age:
+                      template:
+                        format: "{{ _0 | int + 1 }}"
+                    email:
+                      set_null: ~
+            "#;
+            Engine::new(Settings::from_yaml(config).unwrap())
+        }
+
+        #[test]
+        fn transform_named() {
+            let row = engine()
+                .transform_named(
+                    "users",
+                    vec![
+                        ("name", Some("John")),
+                        ("age", Some("30")),
+                        ("email", Some("a@a.com")),
+                        ("id", None),
+                    ],
+                )
+                .unwrap();
+
+            assert_eq!(
+                row,
+                vec![
+                    (String::from("name"), Some(String::from("John Jr."))),
+                    (String::from("age"), Some(String::from("31"))),
+                    (String::from("email"), None),
+                    (String::from("id"), None),
+                ]
+            );
+        }
+
+        #[test]
+        fn transform_map() {
+            let row = HashMap::from([
+                (String::from("name"), String::from("Ann")),
+                (String::from("age"), String::from("20")),
+            ]);
+
+            let err = engine()
+                .transform_named("users", row.iter().map(|(k, v)| (k, Some(v))))
+                .unwrap_err();
+            assert_eq!(err.to_string(), "Unknown column email");
+        }
+
+        #[test]
+        fn transform_json() {
+            let engine = engine();
+
+            assert_eq!(
+                engine
+                    .transform_json("users", &json!({"id": 1, "name": "John", "age": 30}))
+                    .unwrap(),
+                json!({"id": 1, "name": "John Jr.", "age": 31})
+            );
+            assert_eq!(
+                engine
+                    .transform_json("orders", &json!({"id": 1, "name": "John"}))
+                    .unwrap(),
+                json!({"id": 1, "name": "John"})
+            );
+            assert_eq!(
+                engine
+                    .transform_json("users", &json!([1, 2]))
+                    .unwrap_err()
+                    .to_string(),
+                "Invalid row: a JSON object is expected"
+            );
+        }
+
+        #[test]
+        fn untouched_json_object() {
+            let object = json!({"id": 1}).as_object().unwrap().clone();
+
+            assert_eq!(
+                engine().transform_json_object("orders", &object).unwrap(),
+                None
+            );
+        }
+    }
+
+    mod nulls {
+        use super::*;
+
+        fn process(rules: &str, values: &[Option<&str>]) -> Vec<Option<String>> {
+            let config = format!(
+                r#"
+                  tables:
+                    - name: users
+                      rule_order:
+                        - greeting
+                      rules:
+                        {}
+                        greeting:
+                          template:
+                            format: "{{{{ final.name | default(value='-') }}}}"
+                  seed: secret
+                "#,
+                rules
+            );
+            let engine = Engine::new(Settings::from_yaml(config.as_str()).unwrap());
+            let column_indexes =
+                HashMap::from([(String::from("name"), 0), (String::from("greeting"), 1)]);
+
+            engine
+                .process_row(String::from("users"), &column_indexes, values)
+                .unwrap()
+                .into_iter()
+                .map(|v| v.map(Cow::into_owned))
+                .collect()
+        }
+
+        #[test]
+        fn keep() {
+            let row = process(
+                r#"
+                        name:
+                          template:
+                            format: "Dear {{ _0 }}""#,
+                &[None, Some("")],
+            );
+            assert_eq!(row, vec![None, Some(String::from("-"))]);
+        }
+
+        #[test]
+        fn transform() {
+            let row = process(
+                r#"
+                        name:
+                          template:
+                            format: "Dear {{ _0 }}"
+                          on_null: transform"#,
+                &[None, Some("")],
+            );
+            assert_eq!(
+                row,
+                vec![Some(String::from("Dear ")), Some(String::from("Dear "))]
+            );
+        }
+
+        #[test]
+        fn null() {
+            let row = process(
+                r#"
+                        name:
+                          template:
+                            format: "Dear {{ _0 }}"
+                          on_null: "null""#,
+                &[None, Some("")],
+            );
+            // `final.name` is NULL, so the default value is used too
+            assert_eq!(row, vec![None, Some(String::from("-"))]);
+
+            let row = process(
+                r#"
+                        name:
+                          template:
+                            format: "Dear {{ _0 }}"
+                          on_null: "null""#,
+                &[Some("John"), Some("")],
+            );
+            assert_eq!(
+                row,
+                vec![
+                    Some(String::from("Dear John")),
+                    Some(String::from("Dear John"))
+                ]
+            );
+        }
+
+        #[test]
+        fn set_null() {
+            let row = process(
+                r#"
+                        name:
+                          set_null: ~"#,
+                &[Some("John"), Some("")],
+            );
+            assert_eq!(row, vec![None, Some(String::from("-"))]);
+
+            let row = process(
+                r#"
+                        name:
+                          pipeline:
+                            pipes:
+                              - set_null: ~
+                              - capitalize: ~"#,
+                &[Some("John"), Some("")],
+            );
+            assert_eq!(row, vec![None, Some(String::from("-"))]);
+        }
+
+        #[test]
+        fn null_literal() {
+            let row = process(
+                r#"
+                        name:
+                          template:
+                            format: '\N'"#,
+                &[Some("John"), Some("")],
+            );
+            assert_eq!(
+                row,
+                vec![Some(String::from(r"\N")), Some(String::from(r"\N"))]
+            );
+        }
+
+        #[test]
+        fn nullify() {
+            let rule = |p: f64| {
+                format!(
+                    r#"
+                        name:
+                          first_name: {{}}
+                          nullify: {}"#,
+                    p
+                )
+            };
+
+            assert_eq!(process(&rule(1.0), &[Some("John"), None])[0], None);
+            assert!(process(&rule(0.0), &[Some("John"), None])[0].is_some());
+        }
+
+        #[test]
+        fn deterministic_nullify() {
+            let rule = r#"
+                        name:
+                          first_name: {}
+                          nullify: 0.5
+                          deterministic: true"#;
+            let names: Vec<String> = (0..20).map(|i| format!("name{}", i)).collect();
+            let first: Vec<Option<String>> = names
+                .iter()
+                .map(|n| process(rule, &[Some(n), None])[0].clone())
+                .collect();
+            let second: Vec<Option<String>> = names
+                .iter()
+                .map(|n| process(rule, &[Some(n), None])[0].clone())
+                .collect();
+
+            assert_eq!(first, second);
+            assert!(first.iter().any(Option::is_none));
+            assert!(first.iter().any(Option::is_some));
+        }
+    }
+
+    mod deterministic {
+        use super::*;
+
+        fn engine(seed: &str) -> Engine {
+            let config = format!(
+                r#"
+                  tables:
+                    - name: users
+                      rules:
+                        email:
+                          email: {{}}
+                          deterministic: true
+                        name:
+                          person_name: {{}}
+                          deterministic: true
+                        token:
+                          hex_token: {{}}
+                  seed: {}
+                "#,
+                seed
+            );
+            Engine::new(Settings::from_yaml(config.as_str()).unwrap())
+        }
+
+        fn column_indexes() -> HashMap<String, usize> {
+            let mut column_indexes = HashMap::new();
+            column_indexes.insert(String::from("email"), 0);
+            column_indexes.insert(String::from("name"), 1);
+            column_indexes.insert(String::from("token"), 2);
+            column_indexes
+        }
+
+        fn process(engine: &Engine, values: &[&str]) -> Vec<String> {
+            process_values(engine, "users", &column_indexes(), values)
+                .into_iter()
+                .map(|v| v.into_owned())
+                .collect()
+        }
+
+        #[test]
+        fn same_value() {
+            let values = vec!["john@example.com", "John", "abc"];
+
+            let row1 = process(&engine("secret"), &values);
+            let row2 = process(&engine("secret"), &values);
+
+            assert_eq!(row1[0], row2[0]);
+            assert_eq!(row1[1], row2[1]);
+            assert_ne!(row1[2], row2[2]);
+        }
+
+        #[test]
+        fn different_values() {
+            let engine = engine("secret");
+
+            let row1 = process(&engine, &["john@example.com", "John", "abc"]);
+            let row2 = process(&engine, &["jane@example.com", "Jane", "abc"]);
+
+            assert_ne!(row1[0], row2[0]);
+        }
+
+        #[test]
+        fn different_seeds() {
+            let values = vec!["john@example.com", "John", "abc"];
+
+            let row1 = process(&engine("secret"), &values);
+            let row2 = process(&engine("other secret"), &values);
+
+            assert_ne!(row1[0], row2[0]);
+        }
+    }
+
+    mod domains {
+        use super::*;
+
+        fn engine() -> Engine {
+            let config = r#"
+              tables:
+                - name: users
+                  rules:
+                    email:
+                      email: {}
+                      domain: email
+                    backup_email:
+                      email: {}
+                - name: orders
+                  rules:
+                    customer_email:
+                      email: {}
+                      domain: email
+            "#;
+            Engine::new(Settings::from_yaml(config).unwrap())
+        }
+
+        fn process(engine: &Engine, table: &str, columns: &[&str], values: &[&str]) -> Vec<String> {
+            let column_indexes = columns
+                .iter()
+                .enumerate()
+                .map(|(i, &c)| (c.to_string(), i))
+                .collect();
+            process_values(engine, table, &column_indexes, values)
+                .into_iter()
+                .map(|v| v.into_owned())
+                .collect()
+        }
+
+        #[test]
+        fn same_value() {
+            let engine = engine();
+            let columns = ["email", "backup_email"];
+
+            let users1 = process(&engine, "users", &columns, &["a@a.com", "a@a.com"]);
+            let users2 = process(&engine, "users", &columns, &["a@a.com", "a@a.com"]);
+            let users3 = process(&engine, "users", &columns, &["b@b.com", "b@b.com"]);
+            let orders = process(&engine, "orders", &["customer_email"], &["a@a.com"]);
+
+            assert_eq!(users1[0], users2[0]);
+            assert_eq!(users1[0], orders[0]);
+            assert_ne!(users1[0], users3[0]);
+            assert_ne!(users1[0], "a@a.com");
+        }
+
+        #[test]
+        fn spill() {
+            let config = format!(
+                r#"
+                  tables:
+                    - name: users
+                      rules:
+                        name:
+                          person_name: {{}}
+                          domain: name
+                  domains:
+                    spill_dir: {}
+                    memory_limit: 2
+                "#,
+                std::env::temp_dir()
+                    .join("datanymizer_engine_domains")
+                    .display()
+            );
+            let engine = Engine::new(Settings::from_yaml(config.as_str()).unwrap());
+
+            let names: Vec<String> = (0..10).map(|i| format!("name{}", i)).collect();
+            let first: Vec<String> = names
+                .iter()
+                .map(|n| process(&engine, "users", &["name"], &[n])[0].clone())
+                .collect();
+            let second: Vec<String> = names
+                .iter()
+                .map(|n| process(&engine, "users", &["name"], &[n])[0].clone())
+                .collect();
+
+            assert_eq!(first, second);
+        }
+    }
+
     mod row_refs {
         use super::*;
         use crate::transformers::CapitalizeTransformer;
@@ -141,9 +941,8 @@ fn final_row() {
             let table = String::from("some_table");
             let values = vec!["", "", "", "", ""];
 
-            let tr_values = Engine::new(settings)
-                .process_row(table, &column_indexes(), &values)
-                .unwrap();
+            let tr_values =
+                process_values(&Engine::new(settings), &table, &column_indexes(), &values);
 
             assert_ne!(tr_values[0], "");
             assert_eq!(tr_values[1], "");
@@ -184,9 +983,8 @@ fn pipeline_and_final_row() {
             let table = String::from("some_table");
             let values = vec!["", "", "", "", ""];
 
-            let tr_values = Engine::new(settings)
-                .process_row(table, &column_indexes(), &values)
-                .unwrap();
+            let tr_values =
+                process_values(&Engine::new(settings), &table, &column_indexes(), &values);
 
             assert_ne!(tr_values[0], "");
             assert_eq!(tr_values[1], "");
@@ -228,9 +1026,8 @@ fn prev_and_final_row() {
             let table = String::from("some_table");
             let values = vec!["orig_name", "orig_middle_name", "", "", ""];
 
-            let tr_values = Engine::new(settings)
-                .process_row(table, &column_indexes(), &values)
-                .unwrap();
+            let tr_values =
+                process_values(&Engine::new(settings), &table, &column_indexes(), &values);
 
             assert_ne!(tr_values[0], "orig_name");
             assert_eq!(tr_values[1], "orig_middle_name");
@@ -242,4 +1039,113 @@ fn prev_and_final_row() {
             assert_eq!(tr_values[4], format!("{{greeting: \"{}\"}}", tr_values[3]));
         }
     }
+
+    mod overflow {
+        use super::*;
+        use crate::ColumnType;
+
+        fn engine(on_overflow: &str, column_type: ColumnType) -> Engine {
+            let config = format!(
+                r#"
+                  tables:
+                    - name: users
+                      rules:
+                        age:
+                          random_num:
+                            min: 100000
+                            max: 200000
+                          {}
+                        bio:
+                          template:
+                            format: "A very long biography"
+                          {}
+                "#,
+                on_overflow, on_overflow
+            );
+            let mut settings = Settings::from_yaml(config.as_str()).unwrap();
+            settings.set_column_types(
+                &["public.users", "users"],
+                &HashMap::from([
+                    (String::from("age"), ColumnType::new("smallint")),
+                    (String::from("bio"), column_type),
+                ]),
+            );
+            Engine::new(settings)
+        }
+
+        fn process(engine: &Engine) -> Result<Vec<Option<String>>, EngineError> {
+            let column_indexes =
+                HashMap::from([(String::from("age"), 0), (String::from("bio"), 1)]);
+            Ok(engine
+                .process_row(
+                    String::from("users"),
+                    &column_indexes,
+                    &[Some("30"), Some("bio")],
+                )?
+                .into_iter()
+                .map(|v| v.map(Cow::into_owned))
+                .collect())
+        }
+
+        #[test]
+        fn not_set() {
+            let engine = engine("", ColumnType::new("character varying").with_length(6));
+            let row = process(&engine).unwrap();
+
+            assert!(row[0].as_ref().unwrap().parse::<u32>().unwrap() >= 100000);
+            assert_eq!(row[1].as_deref(), Some("A very long biography"));
+        }
+
+        #[test]
+        fn truncate() {
+            let engine = engine(
+                "on_overflow: truncate",
+                ColumnType::new("character varying").with_length(6),
+            );
+
+            assert_eq!(
+                process(&engine).unwrap(),
+                vec![Some(String::from("32767")), Some(String::from("A very"))]
+            );
+        }
+
+        #[test]
+        fn error() {
+            let engine = engine("on_overflow: error", ColumnType::new("text"));
+
+            assert_eq!(
+                process(&engine).unwrap_err().to_string(),
+                "Failed transform The value of `users.age` doesn't fit the column: \
+                the value is out of range of `smallint`"
+            );
+        }
+
+        #[test]
+        fn not_null() {
+            let config = r#"
+              tables:
+                - name: users
+                  rules:
+                    bio:
+                      set_null: ~
+                      on_overflow: truncate
+            "#;
+            let mut settings = Settings::from_yaml(config).unwrap();
+            settings.set_column_types(
+                &["users"],
+                &HashMap::from([(String::from("bio"), ColumnType::new("text").not_null())]),
+            );
+            let engine = Engine::new(settings);
+            let column_indexes = HashMap::from([(String::from("bio"), 0)]);
+
+            assert_eq!(
+                engine
+                    .process_row(String::from("users"), &column_indexes, &[Some("bio")])
+                    .unwrap_err()
+                    .to_string(),
+                "Failed transform The value of `users.bio` doesn't fit the column: \
+                the column is NOT NULL"
+            );
+        }
+    }
 }
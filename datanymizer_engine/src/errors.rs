@@ -15,9 +15,17 @@ fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
 }
 
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum EngineError {
     #[error("Failed transform {0}")]
     TransformFieldError(TransformError),
     #[error("Unknown column {0}")]
     UnknownColumnError(UnknownColumnError),
+    /// Settings are deserialized without [crate::Settings::prepare]
+    #[error("Settings are not prepared")]
+    UnpreparedSettings,
+    #[error("Expected {expected} values, got {actual}")]
+    WrongValueCount { expected: usize, actual: usize },
+    #[error("Invalid row: {0}")]
+    InvalidRow(String),
 }
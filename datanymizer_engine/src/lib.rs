@@ -1,19 +1,55 @@
+//! The engine of datanymizer: applies anonymization rules to rows.
+//!
+//! ```
+//! use datanymizer_engine::{Engine, Settings};
+//!
+//! let settings = Settings::from_yaml(
+//!     r#"
+//!     tables:
+//!       - name: users
+//!         rules:
+//!           email:
+//!             email: {}
+//!     "#,
+//! )
+//! .unwrap();
+//! let engine = Engine::new(settings);
+//!
+//! let users = engine.table_transformer("users", ["id", "email"]).unwrap();
+//! let row = users.transform(&[Some("1"), Some("john@example.com")]).unwrap();
+//! assert_eq!(row[0].as_deref(), Some("1"));
+//! assert_ne!(row[1].as_deref(), Some("john@example.com"));
+//! ```
+//!
+//! Settings can be created in code with [Settings::builder]. Rows with named values are transformed
+//! with [Engine::transform_named] and [Engine::transform_json].
+
+mod column_type;
 mod engine;
 mod errors;
 mod locale;
 mod settings;
 pub(crate) mod store;
+mod table_transformer;
 mod transformer;
 pub mod transformers;
 pub(crate) mod uniq_collector;
 pub mod utils;
+pub mod validation;
 mod value;
 
+pub use column_type::ColumnType;
 pub use engine::Engine;
+pub use errors::{EngineError, UnknownColumnError};
 pub use locale::{ExtData, LocaleConfig, Localized, LocalizedFaker};
-pub use settings::{Filter, Query, Settings, Table, TableList, Tables};
+pub use settings::{
+    Audit, Domains, Filter, OnNull, OnOverflow, Query, Rule, Settings, SettingsBuilder, Subset,
+    SubsetRoot, Table, TableList, Tables,
+};
+pub use table_transformer::TableTransformer;
 pub use transformer::{
-    TransformContext, TransformResult, Transformer, TransformerDefaults, TransformerInitContext,
+    TransformContext, TransformError, TransformResult, Transformer, TransformerDefaults,
+    TransformerInitContext,
 };
 pub use transformers::{AsSqlValue, FkTransformer, Transformers};
 pub use value::StringValue;
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+use wildmatch::WildMatch;
+
+const DEFAULT_SAMPLE_SIZE: u32 = 100;
+
+/// Options of the config coverage audit (the `audit` command and the `--strict` dump option)
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Audit {
+    /// Columns that are known to be safe (`table.column` or `schema.table.column`, wildcards are allowed)
+    #[serde(default, alias = "allow")]
+    pub ignore: Vec<String>,
+    /// Number of rows sampled from each table to check values (default: 100, `0` disables sampling)
+    pub sample_size: Option<u32>,
+}
+
+impl Audit {
+    /// Table names are possible names of the table (e.g. full and short)
+    pub fn is_ignored<T: AsRef<str>>(&self, table_names: &[T], column: &str) -> bool {
+        self.ignore.iter().any(|pattern| {
+            let matcher = WildMatch::new(pattern);
+            table_names
+                .iter()
+                .any(|t| matcher.matches(&format!("{}.{}", t.as_ref(), column)))
+        })
+    }
+
+    pub fn sample_size(&self) -> u32 {
+        self.sample_size.unwrap_or(DEFAULT_SAMPLE_SIZE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_ignored() {
+        let audit = Audit {
+            ignore: vec![
+                String::from("users.nickname"),
+                String::from("public.orders.comment"),
+                String::from("*.created_by"),
+            ],
+            sample_size: None,
+        };
+        let users = ["public.users", "users"];
+        let orders = ["public.orders", "orders"];
+        let other_orders = ["other.orders", "orders"];
+
+        assert!(audit.is_ignored(&users, "nickname"));
+        assert!(audit.is_ignored(&orders, "comment"));
+        assert!(audit.is_ignored(&orders, "created_by"));
+        assert!(!audit.is_ignored(&users, "email"));
+        assert!(!audit.is_ignored(&other_orders, "comment"));
+        assert_eq!(audit.sample_size(), 100);
+    }
+}
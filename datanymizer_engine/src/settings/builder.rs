@@ -0,0 +1,223 @@
+use super::{Domains, Filter, Rule, Settings, Subset, Table};
+use crate::LocaleConfig;
+use config::ConfigError;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+/// Creates [Settings] in code, without a config file:
+///
+/// ```
+/// use datanymizer_engine::{transformers::EmailTransformer, Rule, Settings, Transformers};
+///
+/// let settings = Settings::builder()
+///     .rule("users", "email", Transformers::Email(EmailTransformer::default()))
+///     .rule(
+///         "orders",
+///         "customer_email",
+///         Rule::from(Transformers::Email(EmailTransformer::default())).with_domain("email"),
+///     )
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(settings.tables.len(), 2);
+/// ```
+#[derive(Debug, Default)]
+pub struct SettingsBuilder {
+    tables: Vec<Table>,
+    table_order: Vec<String>,
+    locale: LocaleConfig,
+    filter: Filter,
+    globals: Option<HashMap<String, JsonValue>>,
+    seed: Option<String>,
+    domains: Domains,
+    subset: Option<Subset>,
+}
+
+impl SettingsBuilder {
+    /// Adds the rule for `table.column` (the table is added if it is needed)
+    pub fn rule<R: Into<Rule>>(mut self, table: &str, column: &str, rule: R) -> Self {
+        self.table_mut(table)
+            .rules
+            .insert(column.to_string(), rule.into());
+        self
+    }
+
+    /// Order of applying rules of the table (see [Table::rule_order])
+    pub fn rule_order<I, S>(mut self, table: &str, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.table_mut(table).rule_order = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Adds the table (replaces the table with the same name)
+    pub fn table(mut self, table: Table) -> Self {
+        self.tables.retain(|t| t.name != table.name);
+        self.tables.push(table);
+        self
+    }
+
+    /// See [Settings::table_order]
+    pub fn table_order<I, S>(mut self, tables: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.table_order = tables.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The default locale of transformers
+    pub fn locale(mut self, locale: LocaleConfig) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Adds the global value (see [Settings::globals])
+    pub fn global<V: Into<JsonValue>>(mut self, name: &str, value: V) -> Self {
+        self.globals
+            .get_or_insert_with(HashMap::new)
+            .insert(name.to_string(), value.into());
+        self
+    }
+
+    /// See [Settings::seed]
+    pub fn seed(mut self, seed: &str) -> Self {
+        self.seed = Some(seed.to_string());
+        self
+    }
+
+    pub fn domains(mut self, domains: Domains) -> Self {
+        self.domains = domains;
+        self
+    }
+
+    /// See [Settings::subset]
+    pub fn subset(mut self, subset: Subset) -> Self {
+        self.subset = Some(subset);
+        self
+    }
+
+    /// Checks and initializes rules like [Settings::from_yaml]
+    pub fn build(self) -> Result<Settings, ConfigError> {
+        let mut settings = Settings {
+            tables: self.tables,
+            table_order: self.table_order,
+            default: crate::TransformerDefaults {
+                locale: self.locale,
+            },
+            filter: self.filter,
+            globals: self.globals,
+            templates: None,
+            seed: self.seed,
+            domains: self.domains,
+            audit: Default::default(),
+            subset: self.subset,
+            transform_map: None,
+        };
+        settings.prepare()?;
+
+        Ok(settings)
+    }
+
+    fn table_mut(&mut self, name: &str) -> &mut Table {
+        match self.tables.iter().position(|t| t.name == name) {
+            Some(i) => &mut self.tables[i],
+            None => {
+                self.tables.push(Table::new(name));
+                self.tables.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transformers::{EmailTransformer, FirstNameTransformer},
+        Transformers,
+    };
+
+    #[test]
+    fn build() {
+        let settings = Settings::builder()
+            .rule(
+                "users",
+                "email",
+                Rule::from(Transformers::Email(EmailTransformer::default()))
+                    .with_deterministic(true),
+            )
+            .rule(
+                "users",
+                "name",
+                Transformers::FirstName(FirstNameTransformer::default()),
+            )
+            .rule_order("users", ["name", "email"])
+            .locale(LocaleConfig::RU)
+            .global("company", "Acme")
+            .seed("secret")
+            .build()
+            .unwrap();
+
+        assert!(settings.is_prepared());
+        assert_eq!(settings.tables.len(), 1);
+        let names: Vec<&String> = settings
+            .transformers_for("users")
+            .unwrap()
+            .iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["name", "email"]);
+        assert_eq!(
+            settings.get_table("users").unwrap().rules["name"].transformer,
+            Transformers::FirstName(FirstNameTransformer {
+                locale: Some(LocaleConfig::RU)
+            })
+        );
+        assert_eq!(
+            settings.globals.unwrap()["company"],
+            JsonValue::from("Acme")
+        );
+    }
+
+    #[test]
+    fn replace_table() {
+        let settings = Settings::builder()
+            .rule(
+                "users",
+                "email",
+                Transformers::Email(EmailTransformer::default()),
+            )
+            .table(Table::new("users"))
+            .build()
+            .unwrap();
+
+        assert!(settings.get_table("users").unwrap().rules.is_empty());
+    }
+
+    #[test]
+    fn invalid_rule() {
+        let err = Settings::builder()
+            .rule(
+                "users",
+                "email",
+                Rule::from(Transformers::Email(EmailTransformer::default()))
+                    .with_deterministic(true),
+            )
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "The rule `users.email` is deterministic, but the `seed` option is not set"
+        );
+    }
+}
@@ -0,0 +1,32 @@
+use crate::store::SpillStore;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+const DEFAULT_MEMORY_LIMIT: usize = 1_000_000;
+
+/// Options for consistency domains (see [super::Rule::domain])
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Domains {
+    /// Link foreign key columns to the referenced columns: a foreign key column without
+    /// a rule gets the rule of the referenced column, and both rules share a domain
+    #[serde(default)]
+    pub link_foreign_keys: bool,
+    /// A directory for values that don't fit in memory.
+    /// If it is not set, all values are kept in memory
+    pub spill_dir: Option<String>,
+    /// Max number of values kept in memory when `spill_dir` is set (default: 1000000).
+    /// Every spilled value still takes about 50 bytes of memory (a digest of its key and its position)
+    pub memory_limit: Option<usize>,
+}
+
+impl Domains {
+    pub(crate) fn store(&self) -> SpillStore {
+        match &self.spill_dir {
+            Some(dir) => SpillStore::with_spill(
+                PathBuf::from(dir),
+                self.memory_limit.unwrap_or(DEFAULT_MEMORY_LIMIT),
+            ),
+            None => SpillStore::in_memory(),
+        }
+    }
+}
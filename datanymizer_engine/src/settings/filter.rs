@@ -48,6 +48,11 @@ pub fn schema_match_list(&self) -> &TableList {
         &self.matched_schema
     }
 
+    /// Whether the table schema is dumped (the data can still be filtered out)
+    pub fn filter_schema(&self, table: &str) -> bool {
+        self.matched_schema.filter(table)
+    }
+
     pub fn filter_table(&self, table: &str) -> bool {
         self.matched_schema.filter(table) && self.matched_data.filter(table)
     }
@@ -398,6 +403,7 @@ fn only_data() {
                 filter.load_tables(vec![String::from("table1"), String::from("table2")]);
                 assert!(filter.filter_table("table1"));
                 assert!(!filter.filter_table("table2"));
+                assert!(filter.filter_schema("table2"));
             }
 
             #[test]
@@ -409,6 +415,8 @@ fn schema_and_data() {
                 filter.load_tables(vec![String::from("table1"), String::from("table2")]);
                 assert!(!filter.filter_table("table1"));
                 assert!(filter.filter_table("table2"));
+                assert!(!filter.filter_schema("table1"));
+                assert!(filter.filter_schema("table2"));
             }
 
             #[test]
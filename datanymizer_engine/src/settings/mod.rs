@@ -1,11 +1,16 @@
+mod audit;
+mod builder;
+mod domains;
 mod filter;
+mod rule;
+mod subset;
 mod table;
 mod templates;
 
 use crate::{
     transformer::{TransformerDefaults, TransformerInitContext},
-    transformers::Transformers,
-    Transformer,
+    validation::{ConfigPath, Problem},
+    ColumnType,
 };
 use anyhow::Result;
 use config::{Config, ConfigError, File, FileFormat};
@@ -13,13 +18,18 @@
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 
+pub use audit::Audit;
+pub use builder::SettingsBuilder;
+pub use domains::Domains;
 pub use filter::{Filter, TableList};
+pub use rule::{OnNull, OnOverflow, Rule};
+pub use subset::{Subset, SubsetRoot};
 pub use table::{Query, Table};
 pub use templates::TemplatesCollection;
 
 pub type Tables = Vec<Table>;
 
-type TransformList = Vec<(String, Transformers)>;
+type TransformList = Vec<(String, Rule)>;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
@@ -44,6 +54,21 @@ pub struct Settings {
 
     pub templates: Option<TemplatesCollection>,
 
+    /// A secret for deterministic rules (see [Rule::deterministic]).
+    /// Keep it private: anyone who knows it can check guesses of original values.
+    pub seed: Option<String>,
+
+    /// Consistency domains options
+    #[serde(default)]
+    pub domains: Domains,
+
+    /// Config coverage audit options
+    #[serde(default)]
+    pub audit: Audit,
+
+    /// Referentially complete subset options (PostgreSQL only)
+    pub subset: Option<Subset>,
+
     #[serde(skip)]
     transform_map: Option<HashMap<String, TransformList>>,
 }
@@ -64,11 +89,43 @@ fn from_source<S>(source: S) -> Result<Self, ConfigError>
         let c = Config::builder().add_source(source).build()?;
 
         let mut settings: Self = c.try_deserialize()?;
-        settings.preprocess();
+        settings.prepare()?;
 
         Ok(settings)
     }
 
+    /// Creates settings in code (see [SettingsBuilder])
+    pub fn builder() -> SettingsBuilder {
+        SettingsBuilder::default()
+    }
+
+    /// Initializes rules of settings that are deserialized directly (e.g. with `serde_yaml`).
+    /// Settings created with [Settings::new], [Settings::from_yaml] or [SettingsBuilder]
+    /// are already prepared.
+    pub fn prepare(&mut self) -> Result<(), ConfigError> {
+        if self.is_prepared() {
+            return Ok(());
+        }
+
+        if let Some(subset) = &self.subset {
+            subset.validate().map_err(ConfigError::Message)?;
+        }
+        if let Some(problem) = self.init_rules().into_iter().next() {
+            return Err(ConfigError::Message(problem.message));
+        }
+
+        self.fill_transform_map();
+
+        Ok(())
+    }
+
+    pub fn is_prepared(&self) -> bool {
+        self.transform_map.is_some()
+    }
+
+    /// # Panics
+    ///
+    /// Panics if settings are not prepared (see [Settings::prepare])
     pub fn transformers_for(&self, table: &str) -> Option<&TransformList> {
         if let Some(m) = &self.transform_map {
             m.get(table)
@@ -82,16 +139,108 @@ pub fn get_table(&self, name: &str) -> Option<&Table> {
     }
 
     pub fn find_table<T: AsRef<str>>(&self, names: &[T]) -> Option<&Table> {
-        for name in names {
-            let table = self.get_table(name.as_ref());
-            if table.is_some() {
-                return table;
+        self.find_table_index(names).map(|i| &self.tables[i])
+    }
+
+    /// Links the foreign key column `table.column` to the referenced column `parent_table.parent_column`.
+    /// If the foreign key column has no rule and the referenced column has one, the rule is copied
+    /// and both rules are placed in the same consistency domain.
+    /// Table names are lists of possible names (see [Settings::find_table]).
+    /// Returns `true` if the rule is added.
+    pub fn link_foreign_key<T: AsRef<str>>(
+        &mut self,
+        table_names: &[T],
+        column: &str,
+        parent_table_names: &[T],
+        parent_column: &str,
+    ) -> bool {
+        let parent_index = match self.find_table_index(parent_table_names) {
+            Some(i) => i,
+            None => return false,
+        };
+        if !self.tables[parent_index].rules.contains_key(parent_column) {
+            return false;
+        }
+
+        let index = self.find_table_index(table_names);
+        if let Some(i) = index {
+            if self.tables[i].rules.contains_key(column) {
+                return false;
             }
         }
-        None
+
+        let parent_table_name = self.tables[parent_index].name.clone();
+        let parent_rule = self.tables[parent_index]
+            .rules
+            .get_mut(parent_column)
+            .unwrap();
+        parent_rule
+            .domain
+            .get_or_insert_with(|| format!("{}.{}", parent_table_name, parent_column));
+        let rule = parent_rule.clone();
+
+        let table = match index {
+            Some(i) => &mut self.tables[i],
+            None => {
+                self.tables.push(Table::new(table_names[0].as_ref()));
+                self.tables.last_mut().unwrap()
+            }
+        };
+        table.rules.insert(column.to_string(), rule);
+
+        self.fill_transform_map();
+
+        true
+    }
+
+    /// Sets types of columns for rules of the table (see [Rule::column_type]).
+    /// Table names are lists of possible names (see [Settings::find_table]).
+    pub fn set_column_types<T: AsRef<str>>(
+        &mut self,
+        table_names: &[T],
+        types: &HashMap<String, ColumnType>,
+    ) {
+        let table = match self.find_table_index(table_names) {
+            Some(i) => &mut self.tables[i],
+            None => return,
+        };
+        for (column, rule) in table.rules.iter_mut() {
+            rule.column_type = types.get(column).cloned();
+        }
+
+        if let Some(map) = &mut self.transform_map {
+            map.insert(table.name.clone(), table.transform_list());
+        }
+    }
+
+    fn find_table_index<T: AsRef<str>>(&self, names: &[T]) -> Option<usize> {
+        names
+            .iter()
+            .find_map(|name| self.tables.iter().position(|t| t.name == name.as_ref()))
+    }
+
+    /// Parses the config like [Settings::from_yaml], but doesn't stop at invalid rules:
+    /// all problems of rules are returned with the settings
+    pub(crate) fn from_yaml_with_problems(
+        config: &str,
+    ) -> Result<(Self, Vec<Problem>), ConfigError> {
+        let c = Config::builder()
+            .add_source(File::from_str(config, FileFormat::Yaml))
+            .build()?;
+
+        let mut settings: Self = c.try_deserialize()?;
+        let mut problems = vec![];
+        if let Some(Err(message)) = settings.subset.as_ref().map(Subset::validate) {
+            problems.push(Problem::new(ConfigPath::root().key("subset"), message));
+        }
+        problems.extend(settings.init_rules());
+        settings.fill_transform_map();
+
+        Ok((settings, problems))
     }
 
-    fn preprocess(&mut self) {
+    /// Initializes all rules and checks their options. Returns problems of invalid rules.
+    fn init_rules(&mut self) -> Vec<Problem> {
         let mut init_ctx = TransformerInitContext::from_defaults(self.default.clone());
 
         // Assign extend templates to context
@@ -99,13 +248,39 @@ fn preprocess(&mut self) {
             init_ctx.template_collection = collection.clone();
         }
 
-        for table in self.tables.iter_mut() {
-            for (_name, rule) in table.rules.iter_mut() {
-                rule.init(&init_ctx);
+        let mut problems = vec![];
+        let has_seed = self.seed.is_some();
+        for (i, table) in self.tables.iter_mut().enumerate() {
+            let mut names: Vec<_> = table.rules.keys().cloned().collect();
+            names.sort();
+
+            for name in names {
+                let path = ConfigPath::root()
+                    .key("tables")
+                    .index(i)
+                    .key("rules")
+                    .key(&name);
+                let rule = table.rules.get_mut(&name).unwrap();
+
+                if rule.deterministic && !has_seed {
+                    problems.push(Problem::new(
+                        path.clone(),
+                        format!(
+                            "The rule `{}.{}` is deterministic, but the `seed` option is not set",
+                            table.name, name
+                        ),
+                    ));
+                }
+                if let Err(e) = rule.init(&init_ctx) {
+                    problems.push(Problem::new(
+                        path,
+                        format!("The rule `{}.{}` is invalid: {}", table.name, name, e),
+                    ));
+                }
             }
         }
 
-        self.fill_transform_map();
+        problems
     }
 
     fn fill_transform_map(&mut self) {
@@ -121,7 +296,10 @@ fn fill_transform_map(&mut self) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{transformers::PersonNameTransformer, LocaleConfig};
+    use crate::{
+        transformers::{PersonNameTransformer, Transformers},
+        LocaleConfig,
+    };
 
     #[test]
     fn set_defaults() {
@@ -142,13 +320,13 @@ fn set_defaults() {
         let rules = &s.tables.first().unwrap().rules;
 
         assert_eq!(
-            rules["name"],
+            rules["name"].transformer,
             Transformers::PersonName(PersonNameTransformer {
                 locale: Some(LocaleConfig::RU)
             })
         );
         assert_eq!(
-            rules["alias"],
+            rules["alias"].transformer,
             Transformers::PersonName(PersonNameTransformer {
                 locale: Some(LocaleConfig::EN)
             })
@@ -187,6 +365,125 @@ fn find_table() {
         assert_eq!(t.unwrap().name, "other_schema.users");
     }
 
+    mod seed {
+        use super::*;
+
+        #[test]
+        fn deterministic_rule() {
+            let config = r#"
+                tables:
+                  - name: users
+                    rules:
+                      email:
+                        email: {}
+                        deterministic: true
+                      name:
+                        person_name: {}
+                seed: secret
+                "#;
+            let s = Settings::from_yaml(config).unwrap();
+            let rules = &s.tables.first().unwrap().rules;
+
+            assert_eq!(s.seed, Some(String::from("secret")));
+            assert!(rules["email"].deterministic);
+            assert!(!rules["name"].deterministic);
+        }
+
+        #[test]
+        fn no_seed() {
+            let config = r#"
+                tables:
+                  - name: users
+                    rules:
+                      email:
+                        email: {}
+                        deterministic: true
+                "#;
+            let err = Settings::from_yaml(config).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "The rule `users.email` is deterministic, but the `seed` option is not set"
+            );
+        }
+    }
+
+    mod link_foreign_key {
+        use super::*;
+
+        fn settings() -> Settings {
+            let config = r#"
+                tables:
+                  - name: users
+                    rules:
+                      email:
+                        email: {}
+                      login:
+                        email: {}
+                        domain: logins
+                  - name: public.orders
+                    rules:
+                      note:
+                        none: ~
+                "#;
+            Settings::from_yaml(config).unwrap()
+        }
+
+        #[test]
+        fn new_domain() {
+            let mut s = settings();
+
+            assert!(s.link_foreign_key(
+                &["public.orders", "orders"],
+                "user_email",
+                &["public.users", "users"],
+                "email"
+            ));
+
+            let users = s.get_table("users").unwrap();
+            assert_eq!(
+                users.rules["email"].domain,
+                Some(String::from("users.email"))
+            );
+            let orders = s.get_table("public.orders").unwrap();
+            assert_eq!(orders.rules["user_email"], users.rules["email"]);
+            assert_eq!(s.transformers_for("public.orders").unwrap().len(), 2);
+        }
+
+        #[test]
+        fn existing_domain() {
+            let mut s = settings();
+
+            assert!(s.link_foreign_key(
+                &["public.payments", "payments"],
+                "user_login",
+                &["public.users", "users"],
+                "login"
+            ));
+
+            let payments = s.get_table("public.payments").unwrap();
+            assert_eq!(
+                payments.rules["user_login"].domain,
+                Some(String::from("logins"))
+            );
+            assert!(s.transformers_for("public.payments").is_some());
+        }
+
+        #[test]
+        fn not_linked() {
+            let mut s = settings();
+
+            // the referenced column has no rule
+            assert!(!s.link_foreign_key(&["orders"], "user_id", &["users"], "id"));
+            // the referenced table has no rules
+            assert!(!s.link_foreign_key(&["orders"], "item_id", &["items"], "id"));
+            // the column has its own rule
+            assert!(!s.link_foreign_key(&["public.orders"], "note", &["users"], "email"));
+
+            assert_eq!(s.get_table("users").unwrap().rules["email"].domain, None);
+        }
+    }
+
     mod transformers_for {
         use super::*;
 
@@ -0,0 +1,197 @@
+use crate::{ColumnType, Transformer, TransformerInitContext, Transformers};
+use anyhow::bail;
+use serde::Deserialize;
+
+/// What a rule does with NULL values
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OnNull {
+    /// NULL values are left as they are (the transformer isn't called)
+    #[default]
+    Keep,
+    /// NULL values are transformed as empty strings
+    Transform,
+    /// NULL values are set to NULL: unlike `keep`, they are transformed values for `final` in templates
+    Null,
+}
+
+/// What a rule does with transformed values that don't fit the column type
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnOverflow {
+    /// Strings are truncated, numbers are clamped to the range of the type
+    Truncate,
+    /// The dump fails
+    Error,
+}
+
+/// A column rule: the transformer and the options common for all transformers.
+///
+/// Options are placed next to the transformer name:
+///
+/// ```yaml
+/// rules:
+///   email:
+///     email: {}
+///     deterministic: true
+/// ```
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Rule {
+    #[serde(flatten)]
+    pub transformer: Transformers,
+    /// Derive random values from the original value and the global `seed`,
+    /// so the same original value is always replaced with the same fake one
+    #[serde(default)]
+    pub deterministic: bool,
+    /// The consistency domain: columns with the same domain replace the same original value
+    /// with the same fake value
+    #[serde(default)]
+    pub domain: Option<String>,
+    /// What to do with NULL values
+    #[serde(default)]
+    pub on_null: OnNull,
+    /// The probability (from 0 to 1) of replacing a value with NULL instead of transforming it
+    #[serde(default)]
+    pub nullify: Option<f64>,
+    /// What to do with values that don't fit the column type (values aren't checked if it isn't set)
+    #[serde(default)]
+    pub on_overflow: Option<OnOverflow>,
+    /// The column type (it is set by dumpers, see [crate::Settings::set_column_types])
+    #[serde(skip)]
+    pub column_type: Option<ColumnType>,
+}
+
+impl Rule {
+    /// Sets [Rule::deterministic]
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Sets [Rule::domain]
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    /// Sets [Rule::on_null]
+    pub fn with_on_null(mut self, on_null: OnNull) -> Self {
+        self.on_null = on_null;
+        self
+    }
+
+    /// Sets [Rule::nullify]
+    pub fn with_nullify(mut self, probability: f64) -> Self {
+        self.nullify = Some(probability);
+        self
+    }
+
+    /// Sets [Rule::on_overflow]
+    pub fn with_on_overflow(mut self, on_overflow: OnOverflow) -> Self {
+        self.on_overflow = Some(on_overflow);
+        self
+    }
+
+    pub fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if let Some(p) = self.nullify {
+            if !(0.0..=1.0).contains(&p) {
+                bail!("`nullify` must be between 0 and 1");
+            }
+        }
+        self.transformer.init(ctx)
+    }
+}
+
+impl From<Transformers> for Rule {
+    fn from(transformer: Transformers) -> Self {
+        Self {
+            transformer,
+            deterministic: false,
+            domain: None,
+            on_null: OnNull::Keep,
+            nullify: None,
+            on_overflow: None,
+            column_type: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformers::{EmailTransformer, NoneTransformer};
+
+    #[test]
+    fn only_transformer() {
+        let rule: Rule = serde_yaml::from_str("email: {}").unwrap();
+        assert_eq!(
+            rule,
+            Rule::from(Transformers::Email(EmailTransformer::default()))
+        );
+    }
+
+    #[test]
+    fn with_options() {
+        let config = r#"
+            email: {}
+            deterministic: true
+            "#;
+        let rule: Rule = serde_yaml::from_str(config).unwrap();
+        assert_eq!(
+            rule.transformer,
+            Transformers::Email(EmailTransformer::default())
+        );
+        assert!(rule.deterministic);
+        assert_eq!(rule.domain, None);
+    }
+
+    #[test]
+    fn with_domain() {
+        let config = r#"
+            email: {}
+            domain: customer_email
+            "#;
+        let rule: Rule = serde_yaml::from_str(config).unwrap();
+        assert_eq!(
+            rule.transformer,
+            Transformers::Email(EmailTransformer::default())
+        );
+        assert_eq!(rule.domain, Some(String::from("customer_email")));
+    }
+
+    #[test]
+    fn null_options() {
+        let config = r#"
+            email: {}
+            on_null: transform
+            nullify: 0.25
+            "#;
+        let rule: Rule = serde_yaml::from_str(config).unwrap();
+        assert_eq!(rule.on_null, OnNull::Transform);
+        assert_eq!(rule.nullify, Some(0.25));
+    }
+
+    #[test]
+    fn invalid_nullify() {
+        let mut rule =
+            Rule::from(Transformers::Email(EmailTransformer::default())).with_nullify(1.5);
+        assert_eq!(
+            rule.init(&TransformerInitContext::default())
+                .unwrap_err()
+                .to_string(),
+            "`nullify` must be between 0 and 1"
+        );
+    }
+
+    #[test]
+    fn unit_transformer() {
+        let rule: Rule = serde_yaml::from_str("none: ~").unwrap();
+        assert_eq!(rule, Rule::from(Transformers::None(NoneTransformer)));
+    }
+
+    #[test]
+    fn no_transformer() {
+        let rule: Result<Rule, _> = serde_yaml::from_str("deterministic: true");
+        assert!(rule.is_err());
+    }
+}
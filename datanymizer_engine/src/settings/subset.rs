@@ -0,0 +1,140 @@
+use serde::Deserialize;
+
+/// Options of the referentially complete subset (only rows of root tables, rows referenced by them
+/// and, optionally, rows referencing them are dumped)
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct Subset {
+    /// Tables where the subset starts
+    pub roots: Vec<SubsetRoot>,
+    /// Include rows that reference subset rows of root tables (recursively)
+    #[serde(default)]
+    pub children: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SubsetRoot {
+    /// Table name
+    pub table: String,
+    /// SQL condition (WHERE) for root rows
+    #[serde(rename = "where")]
+    pub condition: Option<String>,
+    /// Percentage of randomly sampled root rows (from 0 to 100)
+    pub percent: Option<f64>,
+}
+
+impl Subset {
+    /// Root options of the table (names are possible names of the table, e.g. full and short)
+    pub fn root<T: AsRef<str>>(&self, names: &[T]) -> Option<&SubsetRoot> {
+        names
+            .iter()
+            .find_map(|name| self.roots.iter().find(|r| r.table == name.as_ref()))
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.roots.is_empty() {
+            return Err(String::from("The subset has no root tables"));
+        }
+
+        for root in &self.roots {
+            if let Some(percent) = root.percent {
+                if !(percent > 0.0 && percent <= 100.0) {
+                    return Err(format!(
+                        "The `percent` of the subset root `{}` must be greater than 0 and not greater than 100",
+                        root.table
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SubsetRoot {
+    pub fn new(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            condition: None,
+            percent: None,
+        }
+    }
+
+    pub fn with_condition(mut self, condition: &str) -> Self {
+        self.condition = Some(condition.to_string());
+        self
+    }
+
+    pub fn with_percent(mut self, percent: f64) -> Self {
+        self.percent = Some(percent);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subset(config: &str) -> Subset {
+        serde_yaml::from_str(config).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let s = subset(
+            r#"
+            roots:
+              - table: public.orders
+                where: "created_at > '2020-01-01'"
+              - table: users
+                percent: 1.5
+            children: true
+            "#,
+        );
+
+        assert!(s.children);
+        assert_eq!(s.roots.len(), 2);
+        assert_eq!(
+            s.roots[0].condition.as_deref(),
+            Some("created_at > '2020-01-01'")
+        );
+        assert_eq!(s.roots[1].percent, Some(1.5));
+        assert!(s.validate().is_ok());
+    }
+
+    #[test]
+    fn root() {
+        let s = subset("roots: [{ table: orders }, { table: public.users }]");
+
+        assert_eq!(
+            s.root(&["public.orders", "orders"]).unwrap().table,
+            "orders"
+        );
+        assert_eq!(
+            s.root(&["public.users", "users"]).unwrap().table,
+            "public.users"
+        );
+        assert!(s.root(&["other.users", "users"]).is_none());
+    }
+
+    #[test]
+    fn no_roots() {
+        assert_eq!(
+            subset("roots: []").validate().unwrap_err(),
+            "The subset has no root tables"
+        );
+    }
+
+    #[test]
+    fn invalid_percent() {
+        for percent in ["0", "-1", "100.5"] {
+            let s = subset(&format!(
+                "roots: [{{ table: users, percent: {} }}]",
+                percent
+            ));
+            assert_eq!(
+                s.validate().unwrap_err(),
+                "The `percent` of the subset root `users` must be greater than 0 and not greater than 100"
+            );
+        }
+    }
+}
@@ -1,9 +1,8 @@
-use super::TransformList;
-use crate::Transformers;
+use super::{Rule, TransformList};
 use serde::Deserialize;
 use std::collections::HashMap;
 
-type Rules = HashMap<String, Transformers>;
+type Rules = HashMap<String, Rule>;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Query {
@@ -29,6 +28,16 @@ pub struct Table {
 }
 
 impl Table {
+    /// A table without rules
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rules: HashMap::new(),
+            rule_order: None,
+            query: None,
+        }
+    }
+
     pub fn transform_list(&self) -> TransformList {
         let explicit_rule_order = self.rule_order.clone().unwrap_or_default();
         let mut transform_list: TransformList = self
@@ -2,8 +2,14 @@
 use serde_json::Value;
 use std::{
     collections::{hash_map::Entry, HashMap},
-    sync::{Arc, RwLock},
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
 };
+use tempfile::TempDir;
+
+const SPILL_BUCKETS: u64 = 256;
 
 pub trait KeyValueStore: Clone + Sync + Send {
     fn read_value(&self, key: &str) -> Result<Option<Value>>;
@@ -49,36 +55,8 @@ fn force_write_value(&self, key: String, value: Value) -> Result<()> {
     fn inc_value(&self, key: String, value: Value) -> Result<()> {
         match self.as_ref().write() {
             Ok(mut map) => {
-                match map.entry(key) {
-                    Entry::Occupied(mut e) => {
-                        let entry_value = e.get();
-                        let sum;
-                        if entry_value.is_i64() && value.is_i64() {
-                            sum = Value::from(
-                                entry_value.as_i64().unwrap() + value.as_i64().unwrap(),
-                            );
-                        } else if let (Some(a), Some(b)) = (entry_value.as_f64(), value.as_f64()) {
-                            sum = Value::from(a + b);
-                        } else {
-                            bail!(
-                                "Can't increment a value for the key {} (not a number)",
-                                e.key()
-                            );
-                        };
-
-                        e.insert(sum);
-                    }
-                    Entry::Vacant(e) => {
-                        if !value.is_number() {
-                            bail!(
-                                "Can't increment a value for the key {} (not a number)",
-                                e.key()
-                            )
-                        }
-                        e.insert(value);
-                    }
-                };
-
+                let sum = inc(&key, map.get(&key), value)?;
+                map.insert(key, sum);
                 Ok(())
             }
             Err(_) => Err(anyhow!("Sync write error")),
@@ -86,6 +64,214 @@ fn inc_value(&self, key: String, value: Value) -> Result<()> {
     }
 }
 
+fn inc(key: &str, entry_value: Option<&Value>, value: Value) -> Result<Value> {
+    match entry_value {
+        Some(entry_value) => {
+            if entry_value.is_i64() && value.is_i64() {
+                Ok(Value::from(
+                    entry_value.as_i64().unwrap() + value.as_i64().unwrap(),
+                ))
+            } else if let (Some(a), Some(b)) = (entry_value.as_f64(), value.as_f64()) {
+                Ok(Value::from(a + b))
+            } else {
+                bail!("Can't increment a value for the key {} (not a number)", key);
+            }
+        }
+        None => {
+            if !value.is_number() {
+                bail!("Can't increment a value for the key {} (not a number)", key)
+            }
+            Ok(value)
+        }
+    }
+}
+
+/// The store keeps values in memory. If the spill directory is set, it moves all values
+/// to files in a temporary directory inside it when the number of values in memory exceeds the limit.
+/// Only 128-bit digests of keys of moved values stay in memory (with positions of values in the files),
+/// so a moved value takes a fixed amount of memory whatever the key is (keys aren't written to the files).
+/// The files are removed when the last clone of the store is dropped.
+#[derive(Clone)]
+pub struct SpillStore {
+    inner: Arc<Mutex<SpillStoreInner>>,
+}
+
+struct SpillStoreInner {
+    memory: HashMap<String, Value>,
+    spill: Option<Spill>,
+}
+
+struct Spill {
+    base_dir: PathBuf,
+    memory_limit: usize,
+    /// Created on the first spill
+    dir: Option<TempDir>,
+    buckets: HashMap<u64, File>,
+    /// Bucket, offset and length of the last value of each key (by the key digest)
+    index: HashMap<u128, (u64, u64, usize)>,
+}
+
+impl SpillStore {
+    pub fn in_memory() -> Self {
+        Self::new(None)
+    }
+
+    pub fn with_spill(dir: PathBuf, memory_limit: usize) -> Self {
+        Self::new(Some(Spill {
+            base_dir: dir,
+            memory_limit,
+            dir: None,
+            buckets: HashMap::new(),
+            index: HashMap::new(),
+        }))
+    }
+
+    fn new(spill: Option<Spill>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SpillStoreInner {
+                memory: HashMap::new(),
+                spill,
+            })),
+        }
+    }
+
+    fn with_inner<T>(&self, f: impl FnOnce(&mut SpillStoreInner) -> Result<T>) -> Result<T> {
+        match self.inner.lock() {
+            Ok(mut inner) => f(&mut inner),
+            Err(_) => Err(anyhow!("Sync error")),
+        }
+    }
+}
+
+impl SpillStoreInner {
+    fn get(&mut self, key: &str) -> Result<Option<Value>> {
+        if let Some(value) = self.memory.get(key) {
+            return Ok(Some(value.clone()));
+        }
+
+        match &mut self.spill {
+            Some(spill) => spill.read(key),
+            None => Ok(None),
+        }
+    }
+
+    fn insert(&mut self, key: String, value: Value) -> Result<()> {
+        self.memory.insert(key, value);
+
+        if let Some(spill) = &mut self.spill {
+            if self.memory.len() > spill.memory_limit {
+                spill.write(self.memory.drain())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Spill {
+    fn digest(key: &str) -> u128 {
+        let hash = blake3::hash(key.as_bytes());
+        u128::from_le_bytes(hash.as_bytes()[..16].try_into().unwrap())
+    }
+
+    fn bucket(digest: u128) -> u64 {
+        (digest % SPILL_BUCKETS as u128) as u64
+    }
+
+    fn read(&mut self, key: &str) -> Result<Option<Value>> {
+        let (bucket, offset, len) = match self.index.get(&Self::digest(key)) {
+            Some(&position) => position,
+            None => return Ok(None),
+        };
+        let file = self
+            .buckets
+            .get_mut(&bucket)
+            .ok_or_else(|| anyhow!("The spill file {} is not found", bucket))?;
+
+        let mut buf = vec![0; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buf)?;
+        Ok(Some(serde_json::from_slice(&buf)?))
+    }
+
+    fn write(&mut self, entries: impl Iterator<Item = (String, Value)>) -> Result<()> {
+        let dir = match &self.dir {
+            Some(dir) => dir.path().to_path_buf(),
+            None => {
+                fs::create_dir_all(&self.base_dir)?;
+                let dir = tempfile::Builder::new()
+                    .prefix("datanymizer-")
+                    .tempdir_in(&self.base_dir)?;
+                let path = dir.path().to_path_buf();
+                self.dir = Some(dir);
+                path
+            }
+        };
+
+        let mut buckets: HashMap<u64, Vec<(u128, Value)>> = HashMap::new();
+        for (k, v) in entries {
+            let digest = Self::digest(&k);
+            buckets
+                .entry(Self::bucket(digest))
+                .or_default()
+                .push((digest, v));
+        }
+
+        for (bucket, entries) in buckets {
+            let file = match self.buckets.entry(bucket) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => e.insert(
+                    OpenOptions::new()
+                        .create(true)
+                        .read(true)
+                        .append(true)
+                        .open(dir.join(format!("{}.jsonl", bucket)))?,
+                ),
+            };
+
+            let mut offset = file.seek(SeekFrom::End(0))?;
+            let mut buf = Vec::new();
+            for (digest, v) in entries {
+                let start = buf.len();
+                serde_json::to_writer(&mut buf, &v)?;
+                let len = buf.len() - start;
+                buf.push(b'\n');
+                self.index.insert(digest, (bucket, offset, len));
+                offset += (len + 1) as u64;
+            }
+            file.write_all(&buf)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl KeyValueStore for SpillStore {
+    fn read_value(&self, key: &str) -> Result<Option<Value>> {
+        self.with_inner(|inner| inner.get(key))
+    }
+
+    fn write_value(&self, key: String, value: Value) -> Result<()> {
+        self.with_inner(|inner| {
+            if inner.get(&key)?.is_some() {
+                bail!("Can't overwrite the key {}", key);
+            }
+            inner.insert(key, value)
+        })
+    }
+
+    fn force_write_value(&self, key: String, value: Value) -> Result<()> {
+        self.with_inner(|inner| inner.insert(key, value))
+    }
+
+    fn inc_value(&self, key: String, value: Value) -> Result<()> {
+        self.with_inner(|inner| {
+            let sum = inc(&key, inner.get(&key)?.as_ref(), value)?;
+            inner.insert(key, sum)
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,4 +393,115 @@ fn not_a_number_first() {
             );
         }
     }
+
+    mod spill_store {
+        use super::*;
+
+        #[test]
+        fn in_memory() {
+            let store = SpillStore::in_memory();
+
+            assert_eq!(store.read_value("key").unwrap(), None);
+            store
+                .write_value(String::from("key"), Value::from("abc"))
+                .unwrap();
+            assert_eq!(store.read_value("key").unwrap().unwrap(), "abc");
+
+            let result = store.write_value(String::from("key"), Value::from("abc"));
+            assert_eq!(
+                result.err().unwrap().to_string(),
+                "Can't overwrite the key key"
+            );
+        }
+
+        #[test]
+        fn spill() {
+            let base = tempfile::tempdir().unwrap();
+            let dir = base.path().to_path_buf();
+            let store = SpillStore::with_spill(dir.clone(), 10);
+
+            for i in 0..100 {
+                store
+                    .write_value(format!("key{}", i), Value::from(i))
+                    .unwrap();
+            }
+            for i in 0..100 {
+                assert_eq!(store.read_value(&format!("key{}", i)).unwrap().unwrap(), i);
+            }
+            assert_eq!(store.read_value("key100").unwrap(), None);
+
+            let result = store.write_value(String::from("key5"), Value::from(5));
+            assert!(result.is_err());
+
+            store
+                .force_write_value(String::from("key5"), Value::from(500))
+                .unwrap();
+            store
+                .inc_value(String::from("key6"), Value::from(1))
+                .unwrap();
+            for i in 100..120 {
+                store
+                    .write_value(format!("key{}", i), Value::from(i))
+                    .unwrap();
+            }
+            assert_eq!(store.read_value("key5").unwrap().unwrap(), 500);
+            assert_eq!(store.read_value("key6").unwrap().unwrap(), 7);
+
+            let spilled = || fs::read_dir(&dir).unwrap().count();
+            assert_eq!(spilled(), 1);
+            drop(store);
+            assert_eq!(spilled(), 0);
+        }
+
+        #[test]
+        fn spilled_keys() {
+            let base = tempfile::tempdir().unwrap();
+            let store = SpillStore::with_spill(base.path().to_path_buf(), 1);
+
+            for i in 0..10 {
+                store
+                    .write_value(format!("john.doe{}@example.com", i), Value::from(i))
+                    .unwrap();
+            }
+            for i in 0..10 {
+                assert_eq!(
+                    store
+                        .read_value(&format!("john.doe{}@example.com", i))
+                        .unwrap()
+                        .unwrap(),
+                    i
+                );
+            }
+
+            let dir = fs::read_dir(base.path()).unwrap().next().unwrap().unwrap();
+            for file in fs::read_dir(dir.path()).unwrap() {
+                let content = fs::read_to_string(file.unwrap().path()).unwrap();
+                assert!(!content.contains("john.doe"));
+            }
+        }
+
+        #[test]
+        fn separate_stores() {
+            let base = tempfile::tempdir().unwrap();
+            let store1 = SpillStore::with_spill(base.path().to_path_buf(), 1);
+            let store2 = SpillStore::with_spill(base.path().to_path_buf(), 1);
+
+            for i in 0..10 {
+                store1
+                    .write_value(format!("key{}", i), Value::from(i))
+                    .unwrap();
+                store2
+                    .write_value(format!("key{}", i), Value::from(i * 10))
+                    .unwrap();
+            }
+            drop(store1);
+
+            for i in 0..10 {
+                assert_eq!(
+                    store2.read_value(&format!("key{}", i)).unwrap().unwrap(),
+                    i * 10
+                );
+            }
+        }
+    }
 }
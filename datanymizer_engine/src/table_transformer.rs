@@ -0,0 +1,149 @@
+use crate::{
+    errors::{EngineError, UnknownColumnError},
+    Engine,
+};
+use std::{borrow::Cow, collections::HashMap};
+
+/// Transforms rows of a table with the same columns (see [Engine::table_transformer]).
+/// Columns are checked once, when the transformer is created.
+pub struct TableTransformer<'a> {
+    engine: &'a Engine,
+    table: String,
+    columns: Vec<String>,
+    column_indexes: HashMap<String, usize>,
+}
+
+impl<'a> TableTransformer<'a> {
+    pub(crate) fn new(
+        engine: &'a Engine,
+        table: &str,
+        columns: Vec<String>,
+    ) -> Result<Self, EngineError> {
+        if !engine.settings.is_prepared() {
+            return Err(EngineError::UnpreparedSettings);
+        }
+
+        let column_indexes: HashMap<String, usize> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| (column.clone(), i))
+            .collect();
+        if let Some(rules) = engine.settings.transformers_for(table) {
+            if let Some((field, _)) = rules
+                .iter()
+                .find(|(field, _)| !column_indexes.contains_key(field))
+            {
+                return Err(EngineError::UnknownColumnError(UnknownColumnError {
+                    field_name: field.clone(),
+                }));
+            }
+        }
+
+        Ok(Self {
+            engine,
+            table: table.to_string(),
+            columns,
+            column_indexes,
+        })
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Values are in the order of columns (`None` is NULL). Untouched values are borrowed.
+    pub fn transform<'v>(
+        &self,
+        values: &[Option<&'v str>],
+    ) -> Result<Vec<Option<Cow<'v, str>>>, EngineError> {
+        if values.len() != self.columns.len() {
+            return Err(EngineError::WrongValueCount {
+                expected: self.columns.len(),
+                actual: values.len(),
+            });
+        }
+
+        self.engine
+            .process_row(self.table.clone(), &self.column_indexes, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Engine, EngineError, Settings};
+
+    fn engine() -> Engine {
+        let config = r#"
+          tables:
+            - name: users
+              rules:
+                name:
+                  template:
+                    format: "{{ _0 }} Jr."
+        "#;
+        Engine::new(Settings::from_yaml(config).unwrap())
+    }
+
+    #[test]
+    fn transform() {
+        let engine = engine();
+        let transformer = engine.table_transformer("users", ["id", "name"]).unwrap();
+
+        assert_eq!(transformer.columns(), ["id", "name"]);
+        assert_eq!(
+            transformer.transform(&[Some("1"), Some("John")]).unwrap(),
+            vec![Some("1".into()), Some("John Jr.".into())]
+        );
+        assert_eq!(
+            transformer.transform(&[Some("2"), None]).unwrap(),
+            vec![Some("2".into()), None]
+        );
+    }
+
+    #[test]
+    fn table_without_rules() {
+        let engine = engine();
+        let transformer = engine.table_transformer("orders", ["id"]).unwrap();
+
+        assert_eq!(
+            transformer.transform(&[Some("1")]).unwrap(),
+            vec![Some("1".into())]
+        );
+    }
+
+    #[test]
+    fn unknown_column() {
+        let engine = engine();
+
+        assert!(matches!(
+            engine.table_transformer("users", ["id"]),
+            Err(EngineError::UnknownColumnError(e)) if e.field_name == "name"
+        ));
+    }
+
+    #[test]
+    fn wrong_value_count() {
+        let engine = engine();
+        let transformer = engine.table_transformer("users", ["id", "name"]).unwrap();
+
+        assert_eq!(
+            transformer.transform(&[Some("1")]).unwrap_err().to_string(),
+            "Expected 2 values, got 1"
+        );
+    }
+
+    #[test]
+    fn unprepared_settings() {
+        let settings: Settings = serde_yaml::from_str("tables: []").unwrap();
+        let engine = Engine::new(settings);
+
+        assert!(matches!(
+            engine.table_transformer("users", ["id"]),
+            Err(EngineError::UnpreparedSettings)
+        ));
+    }
+}
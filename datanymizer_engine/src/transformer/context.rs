@@ -1,30 +1,48 @@
 use super::Globals;
+use crate::ColumnType;
 use std::{borrow::Cow, collections::HashMap};
 
 #[derive(Clone)]
 pub struct TransformContext<'a> {
     pub globals: &'a Option<Globals>,
     column_indexes: Option<&'a HashMap<String, usize>>,
-    prev_row: Option<&'a [&'a str]>,
-    final_row: Option<&'a Vec<Cow<'a, str>>>,
+    prev_row: Option<&'a [Option<&'a str>]>,
+    final_row: Option<&'a [Option<Cow<'a, str>>]>,
+    /// Columns of the final row that are replaced with NULL
+    final_nulls: Option<&'a [bool]>,
+    /// The type of the transformed column (if the dumper knows it)
+    pub column_type: Option<&'a ColumnType>,
 }
 
 impl<'a> TransformContext<'a> {
     pub fn new(
         globals: &'a Option<Globals>,
         column_indexes: Option<&'a HashMap<String, usize>>,
-        prev_row: Option<&'a [&'a str]>,
-        final_row: Option<&'a Vec<Cow<'a, str>>>,
+        prev_row: Option<&'a [Option<&'a str>]>,
+        final_row: Option<&'a [Option<Cow<'a, str>>]>,
     ) -> Self {
         Self {
             globals,
             column_indexes,
             prev_row,
             final_row,
+            final_nulls: None,
+            column_type: None,
         }
     }
 
-    pub fn prev_row_map(&self) -> Option<HashMap<&String, &str>> {
+    pub fn with_final_nulls(mut self, final_nulls: &'a [bool]) -> Self {
+        self.final_nulls = Some(final_nulls);
+        self
+    }
+
+    pub fn with_column_type(mut self, column_type: Option<&'a ColumnType>) -> Self {
+        self.column_type = column_type;
+        self
+    }
+
+    /// Original values of the row (`None` is NULL)
+    pub fn prev_row_map(&self) -> Option<HashMap<&String, Option<&str>>> {
         if let Some(row) = self.prev_row {
             if let Some(column_indexes) = self.column_indexes {
                 let mut row_map = HashMap::with_capacity(row.len());
@@ -39,13 +57,16 @@ pub fn prev_row_map(&self) -> Option<HashMap<&String, &str>> {
         None
     }
 
-    pub fn final_row_map(&self) -> Option<HashMap<&String, &String>> {
+    /// Already transformed values of the row (`None` is NULL)
+    pub fn final_row_map(&self) -> Option<HashMap<&String, Option<&str>>> {
         if let Some(row) = self.final_row {
             if let Some(column_indexes) = self.column_indexes {
                 let mut row_map = HashMap::with_capacity(row.len());
                 for (k, &i) in column_indexes.iter() {
-                    if let Cow::Owned(ref already_transformed) = row[i] {
-                        row_map.insert(k, already_transformed);
+                    if let Some(Cow::Owned(already_transformed)) = &row[i] {
+                        row_map.insert(k, Some(already_transformed.as_str()));
+                    } else if self.final_nulls.is_some_and(|nulls| nulls[i]) {
+                        row_map.insert(k, None);
                     }
                 }
 
@@ -64,6 +85,8 @@ fn default() -> Self {
             column_indexes: None,
             prev_row: None,
             final_row: None,
+            final_nulls: None,
+            column_type: None,
         }
     }
 }
@@ -80,32 +103,35 @@ fn row_maps() {
         column_indexes.insert(String::from("last_name"), 2);
         column_indexes.insert(String::from("options"), 3);
 
-        let prev_row = vec!["First", "Middle", "Last", "{}"];
-        // first_name and last_name are already transformed
+        let prev_row = vec![Some("First"), Some("Middle"), Some("Last"), Some("{}")];
+        // first_name and last_name are already transformed, options is replaced with NULL
         let final_row = vec![
-            Cow::Owned("t_First".to_string()),
-            Cow::Borrowed(prev_row[1]),
-            Cow::Owned("t_Last".to_string()),
-            Cow::Borrowed(prev_row[3]),
+            Some(Cow::Owned("t_First".to_string())),
+            prev_row[1].map(Cow::Borrowed),
+            Some(Cow::Owned("t_Last".to_string())),
+            None,
         ];
+        let final_nulls = [false, false, false, true];
 
         let ctx = TransformContext::new(
             &None,
             Some(&column_indexes),
             Some(&prev_row),
             Some(&final_row),
-        );
+        )
+        .with_final_nulls(&final_nulls);
 
         let prev_row_map = ctx.prev_row_map().unwrap();
         assert_eq!(prev_row_map.len(), 4);
-        assert_eq!(prev_row_map[&"first_name".to_string()], "First");
-        assert_eq!(prev_row_map[&"middle_name".to_string()], "Middle");
-        assert_eq!(prev_row_map[&"last_name".to_string()], "Last");
-        assert_eq!(prev_row_map[&"options".to_string()], "{}");
+        assert_eq!(prev_row_map[&"first_name".to_string()], Some("First"));
+        assert_eq!(prev_row_map[&"middle_name".to_string()], Some("Middle"));
+        assert_eq!(prev_row_map[&"last_name".to_string()], Some("Last"));
+        assert_eq!(prev_row_map[&"options".to_string()], Some("{}"));
 
         let final_row_map = ctx.final_row_map().unwrap();
-        assert_eq!(final_row_map.len(), 2);
-        assert_eq!(final_row_map[&"first_name".to_string()], "t_First");
-        assert_eq!(final_row_map[&"last_name".to_string()], "t_Last");
+        assert_eq!(final_row_map.len(), 3);
+        assert_eq!(final_row_map[&"first_name".to_string()], Some("t_First"));
+        assert_eq!(final_row_map[&"last_name".to_string()], Some("t_Last"));
+        assert_eq!(final_row_map[&"options".to_string()], None);
     }
 }
@@ -17,6 +17,7 @@
 
 use crate::{settings::TemplatesCollection, LocaleConfig};
 
+/// `Ok(None)` is NULL (e.g. the result of the `set_null` transformer)
 pub type TransformResult = Result<Option<String>, TransformError>;
 pub type Globals = HashMap<String, Value>;
 type TemplateStore = Arc<RwLock<HashMap<String, tera::Value>>>;
@@ -59,6 +60,10 @@ fn present<T>(value: T) -> TransformResult
         Ok(Some(value.to_string()))
     }
 
+    fn null() -> TransformResult {
+        Ok(None)
+    }
+
     fn error<T>(field_name: T, field_value: T, reason: T) -> TransformResult
     where
         T: ToString,
@@ -87,7 +92,22 @@ fn transform(
         ctx: &Option<TransformContext>,
     ) -> TransformResult;
 
-    fn init(&mut self, _ctx: &TransformerInitContext) {}
+    /// Prepares the transformer before transforming (e.g. compiles templates)
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Whether the transformer reads or writes the template store,
+    /// so values must be transformed in the order of rows (it is known after `init`)
+    fn uses_store(&self) -> bool {
+        false
+    }
+
+    /// Whether the transformer generates unique values,
+    /// so its results depend on the values generated before
+    fn uses_uniq(&self) -> bool {
+        false
+    }
 }
 
 impl error::Error for TransformError {
@@ -47,7 +47,9 @@ fn try_limit_message(&self, field_name: &str) -> String {
         )
     }
 
-    fn init(&mut self, _ctx: &TransformerInitContext) {}
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 impl<T> Transformer for T
@@ -74,8 +76,12 @@ fn transform(
         }
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
-        self.init(ctx);
+    fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        self.init(ctx)
+    }
+
+    fn uses_uniq(&self) -> bool {
+        self.uniq().required
     }
 }
 
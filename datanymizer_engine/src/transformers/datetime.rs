@@ -1,8 +1,15 @@
-use crate::transformer::{TransformContext, TransformResult, TransformResultHelper, Transformer};
+use crate::{
+    transformer::{
+        TransformContext, TransformResult, TransformResultHelper, Transformer,
+        TransformerInitContext,
+    },
+    utils::rnd,
+};
+use anyhow::{anyhow, bail};
 use chrono::{DateTime, Duration, NaiveDateTime, ParseError, ParseResult};
 use rand::distributions::{Distribution, Uniform};
 use serde::{Deserialize, Serialize};
-use std::fmt::Debug;
+use std::fmt::{Debug, Write};
 use std::hash::{Hash, Hasher};
 
 const BOUNDS_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f%:z";
@@ -108,13 +115,26 @@ fn transform(
         _ctx: &Option<TransformContext>,
     ) -> TransformResult {
         let duration = (self.parsed_to - self.parsed_from).num_seconds();
-        let mut rng = rand::thread_rng();
+        let mut rng = rnd::rng();
         let rnd_duration = Uniform::new_inclusive(0, duration).sample(&mut rng);
 
         let res = (self.parsed_from + Duration::seconds(rnd_duration)).format(&self.format);
 
         TransformResult::present(res)
     }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if self.parsed_from > self.parsed_to {
+            bail!("`from` of the `datetime` transformer is later than `to`");
+        }
+        // An invalid format fails only when a date is formatted
+        write!(String::new(), "{}", self.parsed_from.format(&self.format)).map_err(|_| {
+            anyhow!(
+                "Invalid format `{}` of the `datetime` transformer",
+                self.format
+            )
+        })
+    }
 }
 
 #[derive(Deserialize)]
@@ -138,6 +158,7 @@ fn default() -> Self {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transformers::test_utils::transformer;
 
     fn transformed_value(cfg: &str) -> String {
         let transformer: RandomDateTimeTransformer = serde_yaml::from_str(cfg).unwrap();
@@ -189,4 +210,22 @@ fn custom_format_ymd() {
         let result = transformed_value(cfg);
         assert_eq!(result, "2000-12-31");
     }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            transformer(
+                "datetime: { from: '2010-01-01T00:00:00+00:00', to: '2000-01-01T00:00:00+00:00' }"
+            )
+            .unwrap_err()
+            .to_string(),
+            "`from` of the `datetime` transformer is later than `to`"
+        );
+        assert_eq!(
+            transformer("datetime: { format: '%Q' }")
+                .unwrap_err()
+                .to_string(),
+            "Invalid format `%Q` of the `datetime` transformer"
+        );
+    }
 }
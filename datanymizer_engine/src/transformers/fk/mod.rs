@@ -9,6 +9,7 @@
 use crate::{
     locale::{ExtData, LocaleConfig, Localized, LocalizedFaker},
     transformer::{TransformContext, TransformResult, TransformerDefaults, TransformerInitContext},
+    utils::rnd,
     Transformer,
 };
 use fake::{
@@ -197,19 +198,19 @@ fn default() -> Self {
 macro_rules! impl_localized_faker {
     ( $fk:ident, $sql:ty, Empty ) => {
         fn fake<L: ExtData>(&self, l: L) -> $sql {
-            $fk(l).fake()
+            $fk(l).fake_with_rng(&mut rnd::rng())
         }
     };
 
     ( $fk:ident, $sql:ty, Ratio ) => {
         fn fake<L: ExtData>(&self, l: L) -> $sql {
-            $fk(l, self.ratio).fake()
+            $fk(l, self.ratio).fake_with_rng(&mut rnd::rng())
         }
     };
 
     ( $fk:ident, $sql:ty, Count ) => {
         fn fake<L: ExtData>(&self, l: L) -> $sql {
-            $fk(l, self.min..self.max + 1).fake()
+            $fk(l, self.min..self.max + 1).fake_with_rng(&mut rnd::rng())
         }
     };
 }
@@ -251,8 +252,9 @@ fn transform(
                 self.transform_with_faker()
             }
 
-            fn init(&mut self, ctx: &TransformerInitContext) {
+            fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
                 self.set_defaults_for_faker(&ctx.defaults);
+                Ok(())
             }
         }
     };
@@ -503,7 +505,8 @@ fn locale_is_none() {
                 TransformerDefaults {
                     locale: LocaleConfig::RU,
                 },
-            ));
+            ))
+            .unwrap();
             assert_eq!(t.locale, Some(LocaleConfig::RU));
         }
 
@@ -516,7 +519,8 @@ fn locale_is_some() {
                 TransformerDefaults {
                     locale: LocaleConfig::RU,
                 },
-            ));
+            ))
+            .unwrap();
             assert_eq!(t.locale, Some(LocaleConfig::EN));
         }
     }
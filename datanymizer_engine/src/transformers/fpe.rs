@@ -0,0 +1,296 @@
+use crate::transformer::{
+    TransformContext, TransformResult, TransformResultHelper, Transformer, TransformerInitContext,
+};
+use ::fpe::ff1::{FlexibleNumeralString, FF1};
+use aes::Aes256;
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    env,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+/// Characters that are encrypted (other characters are kept as is)
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FpeAlphabet {
+    /// `0-9`
+    #[default]
+    Digits,
+    /// `0-9` and `a-z`
+    LowerAlphanumeric,
+    /// `0-9`, `A-Z` and `a-z`
+    Alphanumeric,
+}
+
+impl FpeAlphabet {
+    fn chars(&self) -> &'static [u8] {
+        match self {
+            Self::Digits => b"0123456789",
+            Self::LowerAlphanumeric => b"0123456789abcdefghijklmnopqrstuvwxyz",
+            Self::Alphanumeric => b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz",
+        }
+    }
+
+    fn index(&self, c: char) -> Option<u16> {
+        if !c.is_ascii() {
+            return None;
+        }
+        self.chars()
+            .iter()
+            .position(|&a| a == c as u8)
+            .map(|i| i as u16)
+    }
+}
+
+/// Format-preserving encryption (FF1 with AES-256): values keep their length and alphabet,
+/// and they can be decrypted with the same key and tweak
+pub struct Fpe {
+    ff1: FF1<Aes256>,
+    alphabet: FpeAlphabet,
+    tweak: Vec<u8>,
+}
+
+impl Fpe {
+    /// The AES key is the SHA-256 hash of `key`
+    pub fn new(key: &str, alphabet: FpeAlphabet, tweak: &str) -> Self {
+        let secret: [u8; 32] = Sha256::digest(key.as_bytes()).into();
+        let radix = alphabet.chars().len() as u32;
+        Self {
+            ff1: FF1::new(&secret, radix).expect("radixes of alphabets are valid"),
+            alphabet,
+            tweak: tweak.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn encrypt(&self, value: &str) -> anyhow::Result<String> {
+        self.apply(value, "encrypt", |ff1, tweak, ns| ff1.encrypt(tweak, ns))
+    }
+
+    pub fn decrypt(&self, value: &str) -> anyhow::Result<String> {
+        self.apply(value, "decrypt", |ff1, tweak, ns| ff1.decrypt(tweak, ns))
+    }
+
+    /// Encrypts (or decrypts) characters of the alphabet, other characters stay in their positions
+    fn apply<F, E>(&self, value: &str, action: &str, f: F) -> anyhow::Result<String>
+    where
+        F: FnOnce(&FF1<Aes256>, &[u8], &FlexibleNumeralString) -> Result<FlexibleNumeralString, E>,
+        E: std::fmt::Display,
+    {
+        let numerals: Vec<u16> = value
+            .chars()
+            .filter_map(|c| self.alphabet.index(c))
+            .collect();
+        let numerals: Vec<u16> = f(&self.ff1, &self.tweak, &numerals.into())
+            .map_err(|e| anyhow!("Can't {} `{}`: {}", action, value, e))?
+            .into();
+
+        let chars = self.alphabet.chars();
+        let mut numerals = numerals.into_iter();
+        Ok(value
+            .chars()
+            .map(|c| match self.alphabet.index(c) {
+                Some(_) => chars[numerals.next().unwrap() as usize] as char,
+                None => c,
+            })
+            .collect())
+    }
+}
+
+impl Debug for Fpe {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Fpe")
+            .field("alphabet", &self.alphabet)
+            .field("tweak", &self.tweak)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encrypts values with a key, so they can be restored with `pg_datanymizer decrypt`
+/// (format-preserving encryption: FF1 with AES-256).
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     fpe:
+///       key_env: DATANYMIZER_FPE_KEY
+///       alphabet: alphanumeric
+/// ```
+/// where:
+/// * `key` or `key_env` - the secret key or the name of the environment variable with it;
+/// * `alphabet` - `digits` (default), `lower_alphanumeric` or `alphanumeric`. Other characters (e.g. dashes)
+///   are kept as is;
+/// * `tweak` - an optional public value that changes the encryption (e.g. the column name).
+///
+/// Values must have at least 6 digits (or 4 alphanumeric characters).
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct FpeTransformer {
+    pub key: Option<String>,
+    pub key_env: Option<String>,
+    pub alphabet: FpeAlphabet,
+    pub tweak: String,
+    /// The cipher with the key (built in `init`)
+    #[serde(skip)]
+    fpe: Option<Arc<Fpe>>,
+}
+
+impl FpeTransformer {
+    fn key(&self) -> anyhow::Result<String> {
+        match (&self.key, &self.key_env) {
+            (Some(key), None) => Ok(key.clone()),
+            (None, Some(name)) => env::var(name).map_err(|_| {
+                anyhow!(
+                    "The environment variable `{}` with the key of the `fpe` transformer is not set",
+                    name
+                )
+            }),
+            _ => bail!("The `fpe` transformer needs either the `key` or the `key_env` option"),
+        }
+    }
+}
+
+impl PartialEq for FpeTransformer {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+            && self.key_env == other.key_env
+            && self.alphabet == other.alphabet
+            && self.tweak == other.tweak
+    }
+}
+
+impl Eq for FpeTransformer {}
+
+impl Hash for FpeTransformer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+        self.key_env.hash(state);
+        self.alphabet.hash(state);
+        self.tweak.hash(state);
+    }
+}
+
+impl Transformer for FpeTransformer {
+    fn transform(
+        &self,
+        field_name: &str,
+        field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> TransformResult {
+        let encrypted = match &self.fpe {
+            Some(fpe) => fpe.encrypt(field_value),
+            None => self
+                .key()
+                .and_then(|key| Fpe::new(&key, self.alphabet, &self.tweak).encrypt(field_value)),
+        };
+
+        match encrypted {
+            Ok(value) => TransformResult::present(value),
+            Err(e) => TransformResult::error(field_name, field_value, &e.to_string()),
+        }
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        self.fpe = Some(Arc::new(Fpe::new(&self.key()?, self.alphabet, &self.tweak)));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transformers::test_utils::{transform, transformer},
+        utils::EnumWrapper,
+        Transformers,
+    };
+
+    #[test]
+    fn parse() {
+        let transformer: Transformers =
+            EnumWrapper::parse("fpe: { key: secret, alphabet: lower_alphanumeric }").unwrap();
+
+        assert_eq!(
+            transformer,
+            Transformers::Fpe(FpeTransformer {
+                key: Some(String::from("secret")),
+                alphabet: FpeAlphabet::LowerAlphanumeric,
+                ..FpeTransformer::default()
+            })
+        );
+    }
+
+    #[test]
+    fn digits() {
+        let value = transform("fpe: { key: secret }", "4111-1111-1111-1234");
+
+        assert_ne!(value, "4111-1111-1111-1234");
+        assert_eq!(value.len(), 19);
+        for (i, c) in value.chars().enumerate() {
+            if i % 5 == 4 {
+                assert_eq!(c, '-');
+            } else {
+                assert!(c.is_ascii_digit());
+            }
+        }
+        assert_eq!(
+            Fpe::new("secret", FpeAlphabet::Digits, "")
+                .decrypt(&value)
+                .unwrap(),
+            "4111-1111-1111-1234"
+        );
+    }
+
+    #[test]
+    fn alphanumeric() {
+        let config = "fpe: { key: secret, alphabet: alphanumeric, tweak: users.login }";
+        let value = transform(config, "John_Smith42");
+
+        assert_eq!(transform(config, "John_Smith42"), value);
+        assert_eq!(value.chars().nth(4), Some('_'));
+        assert!(value.chars().all(|c| c == '_' || c.is_ascii_alphanumeric()));
+
+        let fpe = Fpe::new("secret", FpeAlphabet::Alphanumeric, "users.login");
+        assert_eq!(fpe.decrypt(&value).unwrap(), "John_Smith42");
+        let other_tweak = Fpe::new("secret", FpeAlphabet::Alphanumeric, "");
+        assert_ne!(other_tweak.encrypt("John_Smith42").unwrap(), value);
+        let other_key = Fpe::new("other", FpeAlphabet::Alphanumeric, "users.login");
+        assert_ne!(other_key.encrypt("John_Smith42").unwrap(), value);
+    }
+
+    #[test]
+    fn too_short() {
+        let err = transformer("fpe: { key: secret }")
+            .unwrap()
+            .transform("field", "12-34", &None)
+            .unwrap_err();
+        assert!(err.reason.starts_with("Can't encrypt `12-34`: "));
+    }
+
+    #[test]
+    fn key_env() {
+        env::set_var("DATANYMIZER_TEST_FPE_KEY", "secret");
+        assert_eq!(
+            transform("fpe: { key_env: DATANYMIZER_TEST_FPE_KEY }", "123456"),
+            transform("fpe: { key: secret }", "123456")
+        );
+
+        assert_eq!(
+            transformer("fpe: { key_env: DATANYMIZER_TEST_FPE_UNKNOWN }")
+                .unwrap_err()
+                .to_string(),
+            "The environment variable `DATANYMIZER_TEST_FPE_UNKNOWN` with the key of the `fpe` transformer is not set"
+        );
+
+        assert_eq!(
+            transformer("fpe: {}").unwrap_err().to_string(),
+            "The `fpe` transformer needs either the `key` or the `key_env` option"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+use crate::transformer::{
+    TransformContext, TransformResult, TransformResultHelper, Transformer, TransformerInitContext,
+};
+use anyhow::{anyhow, bail};
+use data_encoding::{BASE32_NOPAD, BASE64, BASE64URL_NOPAD, HEXLOWER};
+use hmac::{Hmac, Mac};
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::env;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Sha512,
+    Blake3,
+    /// Only for compatibility with legacy systems
+    Md5,
+}
+
+impl HashAlgorithm {
+    /// Hashes `data`, with a key it is HMAC (the keyed mode for BLAKE3)
+    pub fn digest(&self, key: Option<&[u8]>, data: &[u8]) -> Vec<u8> {
+        match (self, key) {
+            (Self::Sha256, None) => Sha256::digest(data).to_vec(),
+            (Self::Sha256, Some(key)) => hmac::<Hmac<Sha256>>(key, data),
+            (Self::Sha512, None) => Sha512::digest(data).to_vec(),
+            (Self::Sha512, Some(key)) => hmac::<Hmac<Sha512>>(key, data),
+            (Self::Blake3, None) => blake3::hash(data).as_bytes().to_vec(),
+            (Self::Blake3, Some(key)) => blake3::keyed_hash(blake3::hash(key).as_bytes(), data)
+                .as_bytes()
+                .to_vec(),
+            (Self::Md5, None) => Md5::digest(data).to_vec(),
+            (Self::Md5, Some(key)) => hmac::<Hmac<Md5>>(key, data),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HashEncoding {
+    /// Lowercase
+    #[default]
+    Hex,
+    /// Without padding
+    Base32,
+    /// With padding
+    Base64,
+    /// URL-safe, without padding
+    Base64url,
+}
+
+impl HashEncoding {
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Hex => HEXLOWER.encode(bytes),
+            Self::Base32 => BASE32_NOPAD.encode(bytes),
+            Self::Base64 => BASE64.encode(bytes),
+            Self::Base64url => BASE64URL_NOPAD.encode(bytes),
+        }
+    }
+}
+
+fn hmac<M: Mac + hmac::digest::KeyInit>(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Hashes values, so they become stable pseudonyms: equal values have equal hashes
+/// (e.g. hashed emails and user IDs stay joinable between tables).
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     hash:
+///       algorithm: sha256
+///       key_env: DATANYMIZER_HASH_KEY
+///       encoding: base32
+///       length: 16
+/// ```
+/// where:
+/// * `algorithm` - `sha256` (default), `sha512`, `blake3` or `md5` (only for legacy systems);
+/// * `key`, `key_env` or `key_global` - an optional secret key, the name of the environment variable with it,
+///   or the name of the global value with it. Without a key, hashes of guessable values (e.g. emails)
+///   can be recovered by brute force. With a key, HMAC is used (the keyed mode for BLAKE3);
+/// * `encoding` - `hex` (default), `base32`, `base64` or `base64url`;
+/// * `length` - an optional maximum length of the result (in characters).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[serde(default)]
+pub struct HashTransformer {
+    pub algorithm: HashAlgorithm,
+    pub key: Option<String>,
+    pub key_env: Option<String>,
+    pub key_global: Option<String>,
+    pub encoding: HashEncoding,
+    pub length: Option<usize>,
+}
+
+impl HashTransformer {
+    fn key(&self, ctx: &Option<TransformContext>) -> anyhow::Result<Option<String>> {
+        match (&self.key, &self.key_env, &self.key_global) {
+            (None, None, None) => Ok(None),
+            (Some(key), None, None) => Ok(Some(key.clone())),
+            (None, Some(name), None) => env::var(name).map(Some).map_err(|_| {
+                anyhow!(
+                    "The environment variable `{}` with the key of the `hash` transformer is not set",
+                    name
+                )
+            }),
+            (None, None, Some(name)) => {
+                let value = ctx
+                    .as_ref()
+                    .and_then(|ctx| ctx.globals.as_ref())
+                    .and_then(|globals| globals.get(name));
+                match value {
+                    Some(Value::String(s)) => Ok(Some(s.clone())),
+                    Some(value) => Ok(Some(value.to_string())),
+                    None => bail!(
+                        "The global value `{}` with the key of the `hash` transformer is not set",
+                        name
+                    ),
+                }
+            }
+            _ => bail!(
+                "The `hash` transformer needs only one of the `key`, `key_env` and `key_global` options"
+            ),
+        }
+    }
+}
+
+impl Transformer for HashTransformer {
+    fn transform(
+        &self,
+        field_name: &str,
+        field_value: &str,
+        ctx: &Option<TransformContext>,
+    ) -> TransformResult {
+        let key = match self.key(ctx) {
+            Ok(key) => key,
+            Err(e) => return TransformResult::error(field_name, field_value, &e.to_string()),
+        };
+
+        let digest = self
+            .algorithm
+            .digest(key.as_ref().map(|k| k.as_bytes()), field_value.as_bytes());
+        let mut value = self.encoding.encode(&digest);
+        if let Some(length) = self.length {
+            // Encoded values are ASCII
+            value.truncate(length);
+        }
+        TransformResult::present(value)
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        // Globals are checked when transforming
+        if self.key_global.is_none() {
+            self.key(&None)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transformers::test_utils::{transform, transformer},
+        utils::EnumWrapper,
+        Transformers,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn parse() {
+        let t: Transformers =
+            EnumWrapper::parse("hash: { algorithm: blake3, encoding: base64url, length: 10 }")
+                .unwrap();
+        assert_eq!(
+            t,
+            Transformers::Hash(HashTransformer {
+                algorithm: HashAlgorithm::Blake3,
+                encoding: HashEncoding::Base64url,
+                length: Some(10),
+                ..HashTransformer::default()
+            })
+        );
+    }
+
+    #[test]
+    fn algorithms() {
+        assert_eq!(
+            transform("hash: {}", "abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            transform("hash: { algorithm: md5 }", "abc"),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(transform("hash: { algorithm: sha512 }", "abc").len(), 128);
+        assert_eq!(
+            transform("hash: { algorithm: blake3 }", "abc"),
+            "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+        );
+    }
+
+    #[test]
+    fn keys() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            transform("hash: { key: Jefe }", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+
+        env::set_var("DATANYMIZER_TEST_HASH_KEY", "Jefe");
+        assert_eq!(
+            transform(
+                "hash: { key_env: DATANYMIZER_TEST_HASH_KEY }",
+                "what do ya want for nothing?"
+            ),
+            transform("hash: { key: Jefe }", "what do ya want for nothing?")
+        );
+
+        let globals = Some(HashMap::from([(
+            String::from("hash_key"),
+            Value::from("Jefe"),
+        )]));
+        let ctx = Some(TransformContext::new(&globals, None, None, None));
+        let t = transformer("hash: { key_global: hash_key }").unwrap();
+        assert_eq!(
+            t.transform("field", "what do ya want for nothing?", &ctx)
+                .unwrap()
+                .unwrap(),
+            transform("hash: { key: Jefe }", "what do ya want for nothing?")
+        );
+        assert_eq!(
+            t.transform("field", "abc", &None).unwrap_err().reason,
+            "The global value `hash_key` with the key of the `hash` transformer is not set"
+        );
+
+        assert_eq!(
+            transformer("hash: { key: Jefe, key_env: DATANYMIZER_TEST_HASH_KEY }")
+                .unwrap_err()
+                .to_string(),
+            "The `hash` transformer needs only one of the `key`, `key_env` and `key_global` options"
+        );
+    }
+
+    #[test]
+    fn encodings() {
+        assert_eq!(
+            transform("hash: { algorithm: md5, encoding: base64 }", "abc"),
+            "kAFQmDzST7DWlj99KOF/cg=="
+        );
+        assert_eq!(
+            transform("hash: { algorithm: md5, encoding: base64url }", "abc"),
+            "kAFQmDzST7DWlj99KOF_cg"
+        );
+        assert_eq!(
+            transform("hash: { algorithm: md5, encoding: base32 }", "abc"),
+            "SAAVBGB42JH3BVUWH56SRYL7OI"
+        );
+    }
+
+    #[test]
+    fn length() {
+        assert_eq!(transform("hash: { length: 12 }", "abc"), "ba7816bf8f01");
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     transformer::{TransformContext, UniqTransformer, Uniqueness},
-    utils::rnd::rnd_chars,
+    utils::rnd::{self, rnd_chars},
     Transformer, TransformerInitContext, Transformers,
 };
 use fake::{faker::internet::raw::*, locales::EN, Fake};
@@ -104,20 +104,15 @@ fn default() -> Self {
 }
 
 /// Kind of email
-#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Default)]
 pub enum EmailKind {
     /// Only for free email providers (e.g., "gmail.com", "yahoo.com", "hotmail.com")
     Free,
     /// Only for example domains (e.g., "example.com") - not real email addresses
+    #[default]
     Safe,
 }
 
-impl Default for EmailKind {
-    fn default() -> Self {
-        Self::Safe
-    }
-}
-
 impl EmailTransformer {
     pub fn new() -> Self {
         Self::default()
@@ -132,8 +127,8 @@ fn do_transform(
         ctx: &Option<TransformContext>,
     ) -> String {
         let mut email: String = match self.kind {
-            EmailKind::Free => FreeEmail(EN).fake(),
-            EmailKind::Safe => SafeEmail(EN).fake(),
+            EmailKind::Free => FreeEmail(EN).fake_with_rng(&mut rnd::rng()),
+            EmailKind::Safe => SafeEmail(EN).fake_with_rng(&mut rnd::rng()),
         };
 
         if let Some(suffix) = &self.suffix {
@@ -163,14 +158,15 @@ fn uniq(&self) -> &Uniqueness {
         &self.uniq
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
+    fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
         if let Some(Affix::Custom(tr)) = &mut self.prefix {
-            tr.init(ctx);
+            tr.init(ctx)?;
         }
 
         if let Some(Affix::Custom(tr)) = &mut self.suffix {
-            tr.init(ctx);
+            tr.init(ctx)?;
         }
+        Ok(())
     }
 }
 
@@ -235,7 +231,7 @@ mod transform {
 
         fn user_and_domain(config: &str) -> Vec<String> {
             let mut transformer: EmailTransformer = serde_yaml::from_str(config).unwrap();
-            UniqTransformer::init(&mut transformer, &TransformerInitContext::default());
+            UniqTransformer::init(&mut transformer, &TransformerInitContext::default()).unwrap();
             let result = transformer
                 .transform("field", "orig@domain.com", &None)
                 .unwrap()
@@ -315,7 +311,7 @@ fn init() {
         let locale = LocaleConfig::RU;
         let ctx = TransformerInitContext::from_defaults(TransformerDefaults { locale });
 
-        Transformer::init(&mut transformer, &ctx);
+        Transformer::init(&mut transformer, &ctx).unwrap();
 
         assert_eq!(
             transformer.prefix.unwrap(),
@@ -1,4 +1,11 @@
-use crate::transformer::{TransformContext, TransformResult, TransformResultHelper, Transformer};
+use crate::{
+    transformer::{
+        TransformContext, TransformResult, TransformResultHelper, Transformer,
+        TransformerInitContext,
+    },
+    utils::rnd,
+};
+use anyhow::bail;
 use fake::{faker::internet::raw::*, locales::EN, Fake};
 use serde::{Deserialize, Serialize};
 
@@ -60,15 +67,24 @@ fn transform(
         _ctx: &Option<TransformContext>,
     ) -> TransformResult {
         let range = self.min.0..self.max.0 + 1;
-        let val: String = Password(EN, range).fake();
+        let val: String = Password(EN, range).fake_with_rng(&mut rnd::rng());
         TransformResult::present(val)
     }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if self.min.0 > self.max.0 {
+            bail!("`min` of the `password` transformer is greater than `max`");
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::{MaxValue, MinValue};
-    use crate::{utils::EnumWrapper, Transformer, Transformers};
+    use crate::{
+        transformers::test_utils::transformer, utils::EnumWrapper, Transformer, Transformers,
+    };
 
     #[test]
     fn deserialize_default_transformer() {
@@ -111,4 +127,14 @@ fn transformed_length() {
 
         assert_eq!(value.len(), 8);
     }
+
+    #[test]
+    fn invalid_length() {
+        assert_eq!(
+            transformer("password: { min: 10, max: 5 }")
+                .unwrap_err()
+                .to_string(),
+            "`min` of the `password` transformer is greater than `max`"
+        );
+    }
 }
@@ -70,7 +70,7 @@ fn transform_parsed_value(
                                 }
                             }
                         }
-                        None => None,
+                        None => Some(Value::Null),
                     },
                     Err(e) => {
                         err = Some(e.into());
@@ -122,13 +122,24 @@ fn transform(
         }
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
+    fn init(&mut self, ctx: &TransformerInitContext) -> Result<()> {
         for field in &mut self.fields {
-            field.rule.init(ctx)
+            field.rule.init(ctx)?;
         }
         if let OnInvalid::ReplaceWith(ReplaceInvalid::Rule(t)) = &mut self.on_invalid {
-            t.init(ctx);
+            t.init(ctx)?;
         }
+        Ok(())
+    }
+
+    fn uses_store(&self) -> bool {
+        self.fields.iter().any(|field| field.rule.uses_store())
+            || matches!(&self.on_invalid, OnInvalid::ReplaceWith(ReplaceInvalid::Rule(t)) if t.uses_store())
+    }
+
+    fn uses_uniq(&self) -> bool {
+        self.fields.iter().any(|field| field.rule.uses_uniq())
+            || matches!(&self.on_invalid, OnInvalid::ReplaceWith(ReplaceInvalid::Rule(t)) if t.uses_uniq())
     }
 }
 
@@ -201,7 +212,7 @@ fn transform() {
             ]
         );
         let mut t: Transformers = EnumWrapper::parse(config).unwrap();
-        t.init(&TransformerInitContext::default());
+        t.init(&TransformerInitContext::default()).unwrap();
 
         let new_json: Value = serde_json::from_str(
             t.transform("field", json.to_string().as_str(), &None)
@@ -296,7 +307,7 @@ fn replace_with_rule() {
                 "#;
 
             let mut t: Transformers = EnumWrapper::parse(config).unwrap();
-            t.init(&TransformerInitContext::default());
+            t.init(&TransformerInitContext::default()).unwrap();
             let new_json = t.transform("field", "invalid", &None).unwrap().unwrap();
 
             assert_eq!(new_json, "{\"rule\": true}");
@@ -0,0 +1,197 @@
+use crate::{
+    transformer::{TransformContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of attempts to generate a unique value (if the `try_count` option is not set)
+const MAX_TRY_COUNT: i64 = 100;
+
+/// Replaces characters of the original value, but keeps its shape: the length and positions of
+/// letters, digits and other characters (punctuation, spaces, etc.)
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     mask:
+///       keep_last: 4
+/// ```
+/// where:
+/// * `keep_first` - number of characters at the beginning that are kept (default: 0);
+/// * `keep_last` - number of characters at the end that are kept (default: 0);
+/// * `char` - replace letters and digits with this character (e.g. `*`). By default, digits are replaced
+///   with random digits, and letters are replaced with random latin letters of the same case.
+///
+/// Other characters are always kept, so `AB-1234-cd` can become `QZ-8051-xe`
+/// (or `**-****-cd` with `char: "*"` and `keep_last: 2`).
+///
+/// Random replacements support deterministic rules and the `uniq` option.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Debug, Clone, Default)]
+#[serde(default)]
+pub struct MaskTransformer {
+    pub keep_first: usize,
+    pub keep_last: usize,
+    pub char: Option<char>,
+    pub uniq: Uniqueness,
+}
+
+impl MaskTransformer {
+    fn is_kept(&self, i: usize, len: usize) -> bool {
+        i < self.keep_first || i + self.keep_last >= len
+    }
+
+    fn mask_char<R: Rng>(&self, c: char, rng: &mut R) -> char {
+        if !c.is_alphanumeric() {
+            return c;
+        }
+        if let Some(mask) = self.char {
+            return mask;
+        }
+
+        if c.is_numeric() {
+            rng.gen_range(b'0'..=b'9') as char
+        } else if c.is_uppercase() {
+            rng.gen_range(b'A'..=b'Z') as char
+        } else {
+            rng.gen_range(b'a'..=b'z') as char
+        }
+    }
+}
+
+impl UniqTransformer for MaskTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let mut rng = rnd::rng();
+        let len = field_value.chars().count();
+
+        field_value
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                if self.is_kept(i, len) {
+                    c
+                } else {
+                    self.mask_char(c, &mut rng)
+                }
+            })
+            .collect()
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn default_try_count(&self) -> i64 {
+        MAX_TRY_COUNT
+    }
+
+    fn try_limit_message(&self, field_name: &str) -> String {
+        let message = format!(
+            "field: `{}` with retry limit: `{}` exceeded",
+            field_name,
+            self.try_count()
+        );
+        match self.char {
+            Some(_) => format!("{} (values masked with `char` can't be unique)", message),
+            None => message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transformers::test_utils::{transform, transformer},
+        utils::rnd::with_seed,
+        Transformer, Transformers,
+    };
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            transformer("mask: { keep_last: 4, char: '*' }").unwrap(),
+            Transformers::Mask(MaskTransformer {
+                keep_last: 4,
+                char: Some('*'),
+                ..MaskTransformer::default()
+            })
+        );
+    }
+
+    #[test]
+    fn random_chars() {
+        let value = transform("mask: {}", "AB-1234 cd/É");
+
+        assert_eq!(value.chars().count(), 12);
+        for (original, masked) in "AB-1234 cd/É".chars().zip(value.chars()) {
+            match original {
+                'A'..='Z' | 'É' => assert!(masked.is_ascii_uppercase()),
+                'a'..='z' => assert!(masked.is_ascii_lowercase()),
+                '0'..='9' => assert!(masked.is_ascii_digit()),
+                _ => assert_eq!(masked, original),
+            }
+        }
+    }
+
+    #[test]
+    fn fixed_char() {
+        assert_eq!(
+            transform("mask: { char: '*' }", "4111-1111-1111-1234"),
+            "****-****-****-****"
+        );
+    }
+
+    #[test]
+    fn keep() {
+        assert_eq!(
+            transform(
+                "mask: { char: '*', keep_first: 2, keep_last: 4 }",
+                "4111-1111-1111-1234"
+            ),
+            "41**-****-****-1234"
+        );
+        assert_eq!(
+            transform("mask: { char: '#', keep_first: 2, keep_last: 4 }", "abc"),
+            "abc"
+        );
+        assert_eq!(transform("mask: { keep_last: 4 }", ""), "");
+    }
+
+    #[test]
+    fn deterministic() {
+        let t = transformer("mask: {}").unwrap();
+        let masked = |seed| {
+            with_seed(seed, || {
+                t.transform("field", "ORD-000123", &None).unwrap().unwrap()
+            })
+        };
+
+        assert_eq!(masked([1; 32]), masked([1; 32]));
+        assert_ne!(masked([1; 32]), masked([2; 32]));
+    }
+
+    #[test]
+    fn uniq() {
+        let t = transformer("mask: { uniq: true }").unwrap();
+        let first = t.transform("mask_uniq", "12", &None).unwrap().unwrap();
+        for _ in 0..50 {
+            assert_ne!(
+                t.transform("mask_uniq", "12", &None).unwrap(),
+                Some(first.clone())
+            );
+        }
+
+        let t = transformer("mask: { char: '*', uniq: true }").unwrap();
+        assert!(t.transform("mask_fixed_uniq", "12", &None).is_ok());
+        assert!(t.transform("mask_fixed_uniq", "12", &None).is_err());
+    }
+}
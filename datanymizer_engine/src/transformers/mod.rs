@@ -4,6 +4,9 @@
 mod none;
 pub use none::NoneTransformer;
 
+mod set_null;
+pub use set_null::SetNullTransformer;
+
 mod internet;
 pub use internet::{EmailKind, EmailTransformer, IpTransformer, PasswordTransformer};
 
@@ -31,6 +34,21 @@
 mod plain;
 pub use plain::PlainTransformer;
 
+mod mask;
+pub use mask::MaskTransformer;
+
+mod fpe;
+pub use self::fpe::{Fpe, FpeAlphabet, FpeTransformer};
+
+mod hash;
+pub use hash::{HashAlgorithm, HashEncoding, HashTransformer};
+
+mod payment;
+pub use payment::*;
+
+mod national_id;
+pub use national_id::*;
+
 mod uuid;
 pub use self::uuid::UuidTransformer;
 
@@ -41,6 +59,9 @@
 pub use fk::sql_value::AsSqlValue;
 pub use fk::*;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 // The TemplateTransformer is much larger than others (about 350 bytes), so we add
 // #[allow(clippy::large_enum_variant)].
 // We can box TemplateTransformer.renderer, but reducing memory usage even by several hundred
@@ -78,6 +99,7 @@ fn mut_transformer(&mut self) -> &mut dyn Transformer {
 
 define_transformers_enum![
     ("none", None, NoneTransformer),
+    ("set_null", SetNull, SetNullTransformer),
     ("email", Email, EmailTransformer),
     ("ip", Ip, IpTransformer),
     ("phone", Phone, PhoneTransformer),
@@ -88,6 +110,21 @@ fn mut_transformer(&mut self) -> &mut dyn Transformer {
     ("password", Password, PasswordTransformer),
     ("datetime", DateTime, RandomDateTimeTransformer),
     ("plain", Plain, PlainTransformer),
+    ("mask", Mask, MaskTransformer),
+    ("fpe", Fpe, FpeTransformer),
+    ("hash", Hash, HashTransformer),
+
+    ("credit_card", CreditCard, CreditCardTransformer),
+    ("card_expiry", CardExpiry, CardExpiryTransformer),
+    ("iban", Iban, IbanTransformer),
+    ("bic", Bic, BicTransformer),
+
+    ("ssn", Ssn, SsnTransformer),
+    ("inn", Inn, InnTransformer),
+    ("snils", Snils, SnilsTransformer),
+    ("ogrn", Ogrn, OgrnTransformer),
+    ("kpp", Kpp, KppTransformer),
+    ("ru_passport", RuPassport, RuPassportTransformer),
 
     ("json", Json, JsonTransformer),
 
@@ -182,8 +219,16 @@ fn transform(
         self.transformer().transform(field_name, field_value, ctx)
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
-        self.mut_transformer().init(ctx);
+    fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        self.mut_transformer().init(ctx)
+    }
+
+    fn uses_store(&self) -> bool {
+        self.transformer().uses_store()
+    }
+
+    fn uses_uniq(&self) -> bool {
+        self.transformer().uses_uniq()
     }
 }
 
@@ -199,7 +244,8 @@ fn init() {
             TransformerDefaults {
                 locale: LocaleConfig::RU,
             },
-        ));
+        ))
+        .unwrap();
 
         assert!(matches!(ts, Transformers::FirstName(t) if t.locale == Some(LocaleConfig::RU)));
     }
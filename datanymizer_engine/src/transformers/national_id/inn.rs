@@ -0,0 +1,145 @@
+use super::{check_format, format_digits, plain_format, random_number, weighted_sum, TaxpayerKind};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+const WEIGHTS_10: [u32; 9] = [2, 4, 10, 3, 5, 9, 4, 6, 8];
+const WEIGHTS_11: [u32; 10] = [7, 2, 4, 10, 3, 5, 9, 4, 6, 8];
+const WEIGHTS_12: [u32; 11] = [3, 7, 2, 4, 10, 3, 5, 9, 4, 6, 8];
+
+/// Generates Russian taxpayer numbers (INN) with valid check digits.
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     inn:
+///       kind: individual
+/// ```
+/// where:
+/// * `kind` - `organization` (10 digits, by default) or `individual` (12 digits);
+/// * `format` - the output format with a `#` placeholder for each digit (only digits by default).
+///
+/// The first 2 digits are a region code (from 01 to 89).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[serde(default)]
+pub struct InnTransformer {
+    pub kind: TaxpayerKind,
+    pub format: Option<String>,
+    pub uniq: Uniqueness,
+}
+
+impl InnTransformer {
+    fn length(&self) -> usize {
+        match self.kind {
+            TaxpayerKind::Organization => 10,
+            TaxpayerKind::Individual => 12,
+        }
+    }
+
+    fn format(&self) -> String {
+        self.format
+            .clone()
+            .unwrap_or_else(|| plain_format(self.length()))
+    }
+}
+
+fn check_digit(digits: &[u8], weights: &[u32]) -> u8 {
+    (weighted_sum(digits, weights) % 11 % 10) as u8
+}
+
+fn generate<R: Rng>(length: usize, rng: &mut R) -> Vec<u8> {
+    // A region and a tax office
+    let mut digits = random_number(1, 89, 2, rng);
+    digits.extend(random_number(1, 99, 2, rng));
+    while digits.len() < length - 1 {
+        digits.push(rng.gen_range(0..10));
+    }
+
+    if length == 10 {
+        digits.push(check_digit(&digits, &WEIGHTS_10));
+    } else {
+        digits.truncate(10);
+        digits.push(check_digit(&digits, &WEIGHTS_11));
+        digits.push(check_digit(&digits, &WEIGHTS_12));
+    }
+    digits
+}
+
+impl UniqTransformer for InnTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        format_digits(&self.format(), &generate(self.length(), &mut rnd::rng()))
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_format(&self.format(), self.length(), "inn")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformers::test_utils::{transform, transformer};
+
+    fn digits(s: &str) -> Vec<u8> {
+        s.bytes().map(|b| b - b'0').collect()
+    }
+
+    fn is_valid(inn: &str) -> bool {
+        let d = digits(inn);
+        match d.len() {
+            10 => check_digit(&d, &WEIGHTS_10) == d[9],
+            12 => check_digit(&d, &WEIGHTS_11) == d[10] && check_digit(&d, &WEIGHTS_12) == d[11],
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn check_digits() {
+        assert!(is_valid("7707083893"));
+        assert!(!is_valid("7707083894"));
+        assert!(is_valid("500100732259"));
+        assert!(!is_valid("500100732258"));
+    }
+
+    #[test]
+    fn kinds() {
+        for _ in 0..100 {
+            let inn = transform("inn: {}", "");
+            assert_eq!(inn.len(), 10);
+            assert!(is_valid(&inn), "{}", inn);
+
+            let inn = transform("inn: { kind: individual }", "");
+            assert_eq!(inn.len(), 12);
+            assert!(is_valid(&inn), "{}", inn);
+        }
+    }
+
+    #[test]
+    fn format() {
+        let inn = transform("inn: { format: 'INN ##########' }", "");
+        assert!(inn.starts_with("INN "));
+        assert!(is_valid(&inn[4..]));
+
+        assert_eq!(
+            transformer("inn: { kind: individual, format: '##########' }")
+                .unwrap_err()
+                .to_string(),
+            "The format `##########` of the `inn` transformer must have 12 `#` placeholders"
+        );
+    }
+}
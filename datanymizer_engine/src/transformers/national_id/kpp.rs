@@ -0,0 +1,82 @@
+use super::{check_format, format_digits, random_number};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use serde::{Deserialize, Serialize};
+
+const LENGTH: usize = 9;
+const DEFAULT_FORMAT: &str = "#########";
+
+/// Generates Russian tax registration reason codes (KPP).
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     kpp:
+///       format: "####-##-###"
+/// ```
+/// where `format` is the output format with a `#` placeholder for each of 9 digits (only digits by default).
+///
+/// Codes consist of a tax office code (a region code from 01 to 89 and 2 digits), a reason code (from 01 to 50)
+/// and a serial number. KPP has no check digits.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[serde(default)]
+pub struct KppTransformer {
+    pub format: String,
+    pub uniq: Uniqueness,
+}
+
+impl Default for KppTransformer {
+    fn default() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            uniq: Uniqueness::default(),
+        }
+    }
+}
+
+impl UniqTransformer for KppTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let mut rng = rnd::rng();
+        let mut digits = random_number(1, 89, 2, &mut rng);
+        digits.extend(random_number(1, 99, 2, &mut rng));
+        digits.extend(random_number(1, 50, 2, &mut rng));
+        digits.extend(random_number(1, 999, 3, &mut rng));
+        format_digits(&self.format, &digits)
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_format(&self.format, LENGTH, "kpp")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transformers::test_utils::transform;
+
+    #[test]
+    fn generate() {
+        for _ in 0..100 {
+            let kpp = transform("kpp: {}", "");
+            assert_eq!(kpp.len(), 9);
+
+            let region: u32 = kpp[..2].parse().unwrap();
+            let reason: u32 = kpp[4..6].parse().unwrap();
+            assert!((1..=89).contains(&region));
+            assert!((1..=50).contains(&reason));
+        }
+    }
+}
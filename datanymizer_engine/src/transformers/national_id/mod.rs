@@ -0,0 +1,124 @@
+use anyhow::bail;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+mod inn;
+pub use inn::InnTransformer;
+
+mod kpp;
+pub use kpp::KppTransformer;
+
+mod ogrn;
+pub use ogrn::OgrnTransformer;
+
+mod ru_passport;
+pub use ru_passport::RuPassportTransformer;
+
+mod snils;
+pub use snils::SnilsTransformer;
+
+mod ssn;
+pub use ssn::SsnTransformer;
+
+const PLACEHOLDER: char = '#';
+/// The default last year in generated identifiers (the `max_year` option). It isn't the current year,
+/// so deterministic rules give the same values after New Year.
+const DEFAULT_MAX_YEAR: u64 = 2025;
+/// Identifiers keep 2 last digits of years, so years are limited by the century
+const LAST_CENTURY_YEAR: u64 = 2099;
+
+/// Kinds of Russian taxpayers (for INN and OGRN)
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxpayerKind {
+    #[default]
+    Organization,
+    /// Individuals (for OGRN - individual entrepreneurs, OGRNIP)
+    Individual,
+}
+
+/// Checks that the format has a placeholder for each digit of the identifier
+fn check_format(format: &str, length: usize, transformer: &str) -> anyhow::Result<()> {
+    let count = format.chars().filter(|&c| c == PLACEHOLDER).count();
+    if count != length {
+        bail!(
+            "The format `{}` of the `{}` transformer must have {} `#` placeholders",
+            format,
+            transformer,
+            length
+        );
+    }
+    Ok(())
+}
+
+/// Checks that the last year is in the range of years of the identifier
+fn check_max_year(max_year: u64, first_year: u64, transformer: &str) -> anyhow::Result<()> {
+    if !(first_year..=LAST_CENTURY_YEAR).contains(&max_year) {
+        bail!(
+            "`max_year` of the `{}` transformer must be from {} to {}",
+            transformer,
+            first_year,
+            LAST_CENTURY_YEAR
+        );
+    }
+    Ok(())
+}
+
+/// Replaces placeholders of the format with digits (in order)
+fn format_digits(format: &str, digits: &[u8]) -> String {
+    let mut digits = digits.iter();
+    format
+        .chars()
+        .map(|c| match c {
+            PLACEHOLDER => digits.next().map_or(c, |&d| char::from(b'0' + d)),
+            _ => c,
+        })
+        .collect()
+}
+
+fn plain_format(length: usize) -> String {
+    PLACEHOLDER.to_string().repeat(length)
+}
+
+/// Digits of the number padded with zeros to `length`
+fn number_digits(number: u64, length: usize) -> Vec<u8> {
+    format!("{:0width$}", number, width = length)
+        .bytes()
+        .map(|b| b - b'0')
+        .collect()
+}
+
+/// Random number (with `length` digits) from the range
+fn random_number<R: Rng>(min: u64, max: u64, length: usize, rng: &mut R) -> Vec<u8> {
+    number_digits(rng.gen_range(min..=max), length)
+}
+
+fn to_number(digits: &[u8]) -> u64 {
+    digits.iter().fold(0, |n, &d| n * 10 + d as u64)
+}
+
+fn weighted_sum(digits: &[u8], weights: &[u32]) -> u32 {
+    digits.iter().zip(weights).map(|(&d, w)| d as u32 * w).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        assert_eq!(format_digits("###-##", &[1, 2, 3, 4, 5]), "123-45");
+        assert_eq!(format_digits("(##) #", &[1, 2]), "(12) #");
+        assert!(check_format("###-##", 5, "ssn").is_ok());
+        assert_eq!(
+            check_format("###-##", 9, "ssn").unwrap_err().to_string(),
+            "The format `###-##` of the `ssn` transformer must have 9 `#` placeholders"
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number_digits(42, 4), vec![0, 0, 4, 2]);
+        assert_eq!(to_number(&[0, 0, 4, 2]), 42);
+    }
+}
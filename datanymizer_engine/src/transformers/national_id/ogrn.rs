@@ -0,0 +1,163 @@
+use super::{
+    check_format, check_max_year, format_digits, plain_format, random_number, to_number,
+    TaxpayerKind, DEFAULT_MAX_YEAR,
+};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// The first year of registration numbers
+const FIRST_YEAR: u64 = 2002;
+
+/// Generates Russian state registration numbers (OGRN and OGRNIP) with valid check digits.
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     ogrn:
+///       kind: individual
+/// ```
+/// where:
+/// * `kind` - `organization` (OGRN, 13 digits, by default) or `individual` (OGRNIP of individual
+///   entrepreneurs, 15 digits);
+/// * `format` - the output format with a `#` placeholder for each digit (only digits by default);
+/// * `max_year` - the last year of registration (2025 by default).
+///
+/// Numbers contain a year of registration (from 2002 to `max_year`) and a region code (from 01 to 89).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[serde(default)]
+pub struct OgrnTransformer {
+    pub kind: TaxpayerKind,
+    pub format: Option<String>,
+    pub max_year: u64,
+    pub uniq: Uniqueness,
+}
+
+impl Default for OgrnTransformer {
+    fn default() -> Self {
+        Self {
+            kind: TaxpayerKind::default(),
+            format: None,
+            max_year: DEFAULT_MAX_YEAR,
+            uniq: Uniqueness::default(),
+        }
+    }
+}
+
+impl OgrnTransformer {
+    fn length(&self) -> usize {
+        match self.kind {
+            TaxpayerKind::Organization => 13,
+            TaxpayerKind::Individual => 15,
+        }
+    }
+
+    fn format(&self) -> String {
+        self.format
+            .clone()
+            .unwrap_or_else(|| plain_format(self.length()))
+    }
+
+    fn generate<R: Rng>(&self, rng: &mut R) -> Vec<u8> {
+        let mut digits = match self.kind {
+            TaxpayerKind::Organization => vec![*[1, 5].choose(rng).unwrap()],
+            TaxpayerKind::Individual => vec![3],
+        };
+        digits.extend(random_number(FIRST_YEAR % 100, self.max_year % 100, 2, rng));
+        digits.extend(random_number(1, 89, 2, rng));
+
+        let length = self.length();
+        while digits.len() < length - 1 {
+            digits.push(rng.gen_range(0..10));
+        }
+        digits.push(check_digit(&digits) as u8);
+        digits
+    }
+}
+
+/// The remainder of the number divided by 11 (13 for OGRNIP), the last digit of it
+fn check_digit(digits: &[u8]) -> u64 {
+    let divisor = if digits.len() == 12 { 11 } else { 13 };
+    to_number(digits) % divisor % 10
+}
+
+impl UniqTransformer for OgrnTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        format_digits(&self.format(), &self.generate(&mut rnd::rng()))
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_format(&self.format(), self.length(), "ogrn")?;
+        check_max_year(self.max_year, FIRST_YEAR, "ogrn")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformers::test_utils::{transform, transformer};
+
+    fn is_valid(ogrn: &str) -> bool {
+        let digits: Vec<u8> = ogrn.bytes().map(|b| b - b'0').collect();
+        let (number, check) = digits.split_at(digits.len() - 1);
+        matches!(digits.len(), 13 | 15) && check_digit(number) == check[0] as u64
+    }
+
+    #[test]
+    fn check_digits() {
+        assert!(is_valid("1027700132195"));
+        assert!(!is_valid("1027700132196"));
+        assert!(is_valid("304500116000157"));
+        assert!(!is_valid("304500116000158"));
+    }
+
+    #[test]
+    fn kinds() {
+        for _ in 0..100 {
+            let ogrn = transform("ogrn: {}", "");
+            assert_eq!(ogrn.len(), 13);
+            assert!(ogrn.starts_with('1') || ogrn.starts_with('5'));
+            assert!(is_valid(&ogrn), "{}", ogrn);
+            let year: u64 = ogrn[1..3].parse().unwrap();
+            assert!((FIRST_YEAR % 100..=DEFAULT_MAX_YEAR % 100).contains(&year));
+
+            let ogrnip = transform(
+                "ogrn: { kind: individual, format: 'OGRNIP ###############' }",
+                "",
+            );
+            assert!(ogrnip.starts_with("OGRNIP 3"));
+            assert!(is_valid(&ogrnip[7..]), "{}", ogrnip);
+        }
+    }
+
+    #[test]
+    fn max_year() {
+        for _ in 0..100 {
+            let ogrn = transform("ogrn: { max_year: 2003 }", "");
+            assert!(is_valid(&ogrn), "{}", ogrn);
+            assert!(matches!(&ogrn[1..3], "02" | "03"), "{}", ogrn);
+        }
+
+        assert_eq!(
+            transformer("ogrn: { max_year: 2001 }")
+                .unwrap_err()
+                .to_string(),
+            "`max_year` of the `ogrn` transformer must be from 2002 to 2099"
+        );
+    }
+}
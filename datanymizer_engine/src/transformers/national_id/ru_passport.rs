@@ -0,0 +1,128 @@
+use super::{
+    check_format, check_max_year, format_digits, number_digits, random_number, DEFAULT_MAX_YEAR,
+};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+const LENGTH: usize = 10;
+const DEFAULT_FORMAT: &str = "## ## ######";
+
+/// OKATO codes of regions
+const REGIONS: [u64; 82] = [
+    1, 3, 4, 5, 7, 8, 10, 11, 12, 14, 15, 17, 18, 19, 20, 22, 24, 25, 26, 27, 28, 29, 30, 32, 33,
+    34, 35, 36, 37, 38, 40, 41, 42, 44, 45, 46, 47, 49, 50, 52, 53, 54, 56, 57, 58, 60, 61, 63, 64,
+    65, 66, 67, 68, 69, 70, 71, 73, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87, 88, 89, 90,
+    91, 92, 93, 94, 95, 96, 97, 98, 99,
+];
+
+/// Passports of the current type are issued since 1997
+const FIRST_YEAR: u64 = 1997;
+
+/// Generates series and numbers of Russian internal passports.
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     ru_passport:
+///       format: "##########"
+///       max_year: 2020
+/// ```
+/// where:
+/// * `format` - the output format with a `#` placeholder for each of 10 digits (`## ## ######` by default);
+/// * `max_year` - the last year of issue (2025 by default).
+///
+/// A series consists of a region code (OKATO) and 2 last digits of the year of issue (from 1997 to `max_year`).
+/// Passports have no check digits.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[serde(default)]
+pub struct RuPassportTransformer {
+    pub format: String,
+    pub max_year: u64,
+    pub uniq: Uniqueness,
+}
+
+impl Default for RuPassportTransformer {
+    fn default() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            max_year: DEFAULT_MAX_YEAR,
+            uniq: Uniqueness::default(),
+        }
+    }
+}
+
+fn generate<R: Rng>(max_year: u64, rng: &mut R) -> Vec<u8> {
+    let year = rng.gen_range(FIRST_YEAR..=max_year);
+    let mut digits = number_digits(*REGIONS.choose(rng).unwrap(), 2);
+    digits.extend(number_digits(year % 100, 2));
+    digits.extend(random_number(101, 999_999, 6, rng));
+    digits
+}
+
+impl UniqTransformer for RuPassportTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        format_digits(&self.format, &generate(self.max_year, &mut rnd::rng()))
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_format(&self.format, LENGTH, "ru_passport")?;
+        check_max_year(self.max_year, FIRST_YEAR, "ru_passport")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformers::test_utils::{transform, transformer};
+
+    #[test]
+    fn series_and_number() {
+        for _ in 0..100 {
+            let passport = transform("ru_passport: {}", "");
+            assert_eq!(passport.len(), 12);
+
+            let parts: Vec<u64> = passport.split(' ').map(|p| p.parse().unwrap()).collect();
+            assert!(REGIONS.contains(&parts[0]));
+            assert!(parts[1] >= 97 || parts[1] <= DEFAULT_MAX_YEAR % 100);
+            assert!(parts[2] >= 101);
+        }
+    }
+
+    #[test]
+    fn format() {
+        let passport = transform("ru_passport: { format: '####-######' }", "");
+        assert_eq!(passport.len(), 11);
+        assert_eq!(&passport[4..5], "-");
+    }
+
+    #[test]
+    fn max_year() {
+        for _ in 0..100 {
+            let passport = transform("ru_passport: { max_year: 1998 }", "");
+            assert!(matches!(&passport[3..5], "97" | "98"), "{}", passport);
+        }
+
+        assert_eq!(
+            transformer("ru_passport: { max_year: 1990 }")
+                .unwrap_err()
+                .to_string(),
+            "`max_year` of the `ru_passport` transformer must be from 1997 to 2099"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use super::{check_format, format_digits, number_digits, random_number, weighted_sum};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use serde::{Deserialize, Serialize};
+
+const LENGTH: usize = 11;
+const DEFAULT_FORMAT: &str = "###-###-### ##";
+const WEIGHTS: [u32; 9] = [9, 8, 7, 6, 5, 4, 3, 2, 1];
+/// Check digits are defined only for greater numbers
+const MIN_NUMBER: u64 = 1_001_999;
+
+/// Generates Russian insurance numbers (SNILS) with valid check digits.
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     snils:
+///       format: "###########"
+/// ```
+/// where `format` is the output format with a `#` placeholder for each of 11 digits (`###-###-### ##` by default).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[serde(default)]
+pub struct SnilsTransformer {
+    pub format: String,
+    pub uniq: Uniqueness,
+}
+
+impl Default for SnilsTransformer {
+    fn default() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            uniq: Uniqueness::default(),
+        }
+    }
+}
+
+fn check_number(digits: &[u8]) -> u64 {
+    let sum = weighted_sum(digits, &WEIGHTS) as u64;
+    match sum {
+        0..=99 => sum,
+        100 | 101 => 0,
+        _ => sum % 101 % 100,
+    }
+}
+
+impl UniqTransformer for SnilsTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let mut digits = random_number(MIN_NUMBER, 999_999_999, 9, &mut rnd::rng());
+        digits.extend(number_digits(check_number(&digits), 2));
+        format_digits(&self.format, &digits)
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_format(&self.format, LENGTH, "snils")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformers::test_utils::transform;
+
+    fn is_valid(snils: &str) -> bool {
+        let digits: Vec<u8> = snils
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| d as u8)
+            .collect();
+        digits.len() == LENGTH && check_number(&digits[..9]) == (digits[9] * 10 + digits[10]) as u64
+    }
+
+    #[test]
+    fn check_digits() {
+        assert!(is_valid("112-233-445 95"));
+        assert!(!is_valid("112-233-445 96"));
+        // The sums are 100, 201 and 405
+        assert_eq!(check_number(&[0, 0, 1, 5, 0, 8, 8, 1, 5]), 0);
+        assert_eq!(check_number(&[0, 0, 6, 9, 9, 6, 6, 8, 2]), 0);
+        assert_eq!(check_number(&[9, 9, 9, 9, 9, 9, 9, 9, 9]), 1);
+    }
+
+    #[test]
+    fn generate() {
+        for _ in 0..100 {
+            let snils = transform("snils: {}", "");
+            assert_eq!(snils.len(), 14);
+            assert!(is_valid(&snils), "{}", snils);
+        }
+
+        let snils = transform("snils: { format: '###########' }", "");
+        assert_eq!(snils.len(), 11);
+        assert!(is_valid(&snils));
+    }
+}
@@ -0,0 +1,124 @@
+use super::{check_format, format_digits, number_digits, random_number, to_number};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+const LENGTH: usize = 9;
+const DEFAULT_FORMAT: &str = "###-##-####";
+
+/// Numbers which were misused publicly (078-05-1120 and 219-09-9999 were printed in ads)
+const INVALIDATED: [u64; 2] = [78051120, 219099999];
+
+/// Generates US Social Security numbers outside reserved ranges.
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     ssn:
+///       format: "#########"
+/// ```
+/// where `format` is the output format with a `#` placeholder for each of 9 digits (`###-##-####` by default).
+///
+/// Area numbers are never 000, 666 or 900-999, group numbers are never 00 and serial numbers are never 0000.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[serde(default)]
+pub struct SsnTransformer {
+    pub format: String,
+    pub uniq: Uniqueness,
+}
+
+impl Default for SsnTransformer {
+    fn default() -> Self {
+        Self {
+            format: DEFAULT_FORMAT.to_string(),
+            uniq: Uniqueness::default(),
+        }
+    }
+}
+
+fn generate<R: Rng>(rng: &mut R) -> Vec<u8> {
+    loop {
+        let area = match rng.gen_range(1..=898) {
+            // 666 is skipped
+            a if a >= 666 => a + 1,
+            a => a,
+        };
+        let mut digits = number_digits(area, 3);
+        digits.extend(random_number(1, 99, 2, rng));
+        digits.extend(random_number(1, 9999, 4, rng));
+
+        if !INVALIDATED.contains(&to_number(&digits)) {
+            return digits;
+        }
+    }
+}
+
+impl UniqTransformer for SsnTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        format_digits(&self.format, &generate(&mut rnd::rng()))
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_format(&self.format, LENGTH, "ssn")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        transformers::test_utils::{transform, transformer},
+        utils::rnd::with_seed,
+        Transformer,
+    };
+
+    #[test]
+    fn ranges() {
+        for _ in 0..1000 {
+            let ssn = transform("ssn: {}", "");
+            assert_eq!(ssn.len(), 11);
+
+            let parts: Vec<u32> = ssn.split('-').map(|p| p.parse().unwrap()).collect();
+            assert!(parts[0] > 0 && parts[0] != 666 && parts[0] < 900);
+            assert!(parts[1] > 0);
+            assert!(parts[2] > 0);
+        }
+    }
+
+    #[test]
+    fn format() {
+        let ssn = transform("ssn: { format: '#########' }", "");
+        assert_eq!(ssn.len(), 9);
+        assert!(ssn.chars().all(|c| c.is_ascii_digit()));
+
+        assert_eq!(
+            transformer("ssn: { format: '###-##' }")
+                .unwrap_err()
+                .to_string(),
+            "The format `###-##` of the `ssn` transformer must have 9 `#` placeholders"
+        );
+    }
+
+    #[test]
+    fn deterministic() {
+        let t = transformer("ssn: {}").unwrap();
+        let ssn = |seed| with_seed(seed, || t.transform("field", "", &None).unwrap().unwrap());
+
+        assert_eq!(ssn([1; 32]), ssn([1; 32]));
+        assert_ne!(ssn([1; 32]), ssn([2; 32]));
+    }
+}
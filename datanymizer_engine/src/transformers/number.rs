@@ -1,4 +1,8 @@
-use crate::transformer::{TransformContext, UniqTransformer, Uniqueness};
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use anyhow::bail;
 use rand::distributions::{Distribution, Uniform};
 use serde::{Deserialize, Serialize};
 
@@ -61,7 +65,7 @@ fn do_transform(
         _field_value: &str,
         _ctx: &Option<TransformContext>,
     ) -> String {
-        let mut rng = rand::thread_rng();
+        let mut rng = rnd::rng();
         Uniform::new_inclusive(self.min.0, self.max.0)
             .sample(&mut rng)
             .to_string()
@@ -70,4 +74,36 @@ fn do_transform(
     fn uniq(&self) -> &Uniqueness {
         &self.uniq
     }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if self.min.0 > self.max.0 {
+            bail!("`min` of the `random_num` transformer is greater than `max`");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transformers::test_utils::{transform, transformer};
+
+    #[test]
+    fn range() {
+        for _ in 0..10 {
+            let value: usize = transform("random_num: { min: 10, max: 20 }", "")
+                .parse()
+                .unwrap();
+            assert!((10..=20).contains(&value));
+        }
+    }
+
+    #[test]
+    fn invalid_range() {
+        assert_eq!(
+            transformer("random_num: { min: 2, max: 1 }")
+                .unwrap_err()
+                .to_string(),
+            "`min` of the `random_num` transformer is greater than `max`"
+        );
+    }
 }
@@ -0,0 +1,123 @@
+use super::iban::COUNTRIES;
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use anyhow::bail;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Generates BICs (SWIFT codes): 4 letters of the bank, 2 letters of the country, 2 characters
+/// of the location and, optionally, 3 characters of the branch.
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     bic:
+///       country: DE
+/// ```
+/// where `country` is the country code. By default, it is the country of the original BIC,
+/// or a random one (if the original value is not a BIC).
+///
+/// BICs have the branch code if the original BIC has it (11 characters).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[serde(default)]
+pub struct BicTransformer {
+    pub country: Option<String>,
+    pub uniq: Uniqueness,
+}
+
+impl UniqTransformer for BicTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let mut rng = rnd::rng();
+        let original = field_value.trim();
+
+        let country = match &self.country {
+            Some(country) => country.to_uppercase(),
+            None => match original.get(4..6) {
+                Some(c) if c.chars().all(|c| c.is_ascii_alphabetic()) => c.to_uppercase(),
+                _ => COUNTRIES.choose(&mut rng).unwrap().0.to_string(),
+            },
+        };
+
+        let mut bic = random_chars(LETTERS, 4, &mut rng);
+        bic.push_str(&country);
+        bic.push_str(&random_chars(ALPHANUMERIC, 2, &mut rng));
+        if original.len() == 11 {
+            bic.push_str(&random_chars(ALPHANUMERIC, 3, &mut rng));
+        }
+        bic
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if let Some(country) = &self.country {
+            if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+                bail!(
+                    "The country `{}` of the `bic` transformer must be a code of 2 letters",
+                    country
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn random_chars<R: Rng>(chars: &[u8], count: usize, rng: &mut R) -> String {
+    (0..count)
+        .map(|_| *chars.choose(rng).unwrap() as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transformers::test_utils::{transform, transformer};
+
+    fn assert_bic(bic: &str, country: &str, len: usize) {
+        assert_eq!(bic.len(), len);
+        assert!(bic[..4].chars().all(|c| c.is_ascii_uppercase()));
+        assert_eq!(&bic[4..6], country);
+        assert!(bic[6..]
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+    }
+
+    #[test]
+    fn original() {
+        assert_bic(&transform("bic: {}", "DEUTDEFF"), "DE", 8);
+        assert_bic(&transform("bic: {}", "DEUTDEFF500"), "DE", 11);
+        assert_bic(&transform("bic: { country: fr }", "DEUTDEFF500"), "FR", 11);
+    }
+
+    #[test]
+    fn random_country() {
+        let bic = transform("bic: {}", "");
+        assert_eq!(bic.len(), 8);
+        assert!(bic.chars().all(|c| c.is_ascii_alphanumeric()));
+    }
+
+    #[test]
+    fn invalid_country() {
+        assert_eq!(
+            transformer("bic: { country: Germany }")
+                .unwrap_err()
+                .to_string(),
+            "The country `Germany` of the `bic` transformer must be a code of 2 letters"
+        );
+        assert!(transformer("bic: { country: D1 }").is_err());
+    }
+}
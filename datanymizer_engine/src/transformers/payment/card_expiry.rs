@@ -0,0 +1,195 @@
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use anyhow::{anyhow, bail};
+use chrono::{Datelike, Months, NaiveDate};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+const DEFAULT_FORMAT: &str = "%m/%y";
+const DEFAULT_MIN_MONTHS: i32 = 1;
+const DEFAULT_MAX_MONTHS: i32 = 60;
+/// Dates are relative to a fixed date, so dumps don't depend on the day they are made
+const DEFAULT_BASE_DATE: &str = "2025-01-01";
+const BASE_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Generates expiration dates of cards (months relative to the month of the base date).
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     card_expiry:
+///       min_months: -12
+///       max_months: 36
+///       format: "%Y-%m-%d"
+///       base_date: "2026-06-01"
+/// ```
+/// where:
+/// * `min_months` and `max_months` - the range of months from the month of the base date (from 1 to 60 by default,
+///   negative values are in the past, e.g. for expired cards);
+/// * `format` - the output format (the first day of the month is formatted), `%m/%y` by default;
+/// * `base_date` - the date the months are counted from (`YYYY-MM-DD`), `2025-01-01` by default.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+#[serde(default)]
+pub struct CardExpiryTransformer {
+    pub min_months: i32,
+    pub max_months: i32,
+    pub format: String,
+    pub base_date: String,
+    pub uniq: Uniqueness,
+
+    #[serde(skip)]
+    parsed_base_date: NaiveDate,
+}
+
+impl Default for CardExpiryTransformer {
+    fn default() -> Self {
+        Self {
+            min_months: DEFAULT_MIN_MONTHS,
+            max_months: DEFAULT_MAX_MONTHS,
+            format: DEFAULT_FORMAT.to_string(),
+            base_date: DEFAULT_BASE_DATE.to_string(),
+            uniq: Uniqueness::default(),
+            parsed_base_date: NaiveDate::parse_from_str(DEFAULT_BASE_DATE, BASE_DATE_FORMAT)
+                .unwrap(),
+        }
+    }
+}
+
+impl CardExpiryTransformer {
+    fn expiry(&self, base_date: NaiveDate, offset: i32) -> anyhow::Result<String> {
+        let month = base_date.with_day(1).unwrap();
+        let months = Months::new(offset.unsigned_abs());
+        let date = if offset < 0 {
+            month.checked_sub_months(months)
+        } else {
+            month.checked_add_months(months)
+        }
+        .ok_or_else(|| anyhow!("The date is out of range"))?;
+
+        let mut s = String::new();
+        write!(s, "{}", date.format(&self.format)).map_err(|_| {
+            anyhow!(
+                "Invalid format `{}` of the `card_expiry` transformer",
+                self.format
+            )
+        })?;
+        Ok(s)
+    }
+}
+
+impl UniqTransformer for CardExpiryTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let offset = rnd::rng().gen_range(self.min_months..=self.max_months);
+        // The format and the range are checked in `init`
+        self.expiry(self.parsed_base_date, offset)
+            .unwrap_or_default()
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn default_try_count(&self) -> i64 {
+        (self.max_months - self.min_months) as i64 + 1
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if self.min_months > self.max_months {
+            bail!("`min_months` of the `card_expiry` transformer is greater than `max_months`");
+        }
+        self.parsed_base_date = NaiveDate::parse_from_str(&self.base_date, BASE_DATE_FORMAT)
+            .map_err(|_| {
+                anyhow!(
+                    "Invalid `base_date` `{}` of the `card_expiry` transformer (`YYYY-MM-DD` is expected)",
+                    self.base_date
+                )
+            })?;
+        self.expiry(self.parsed_base_date, self.min_months)?;
+        self.expiry(self.parsed_base_date, self.max_months)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transformers::test_utils::transformer, Transformer};
+
+    #[test]
+    fn expiry() {
+        let t = CardExpiryTransformer::default();
+        let base_date = NaiveDate::from_ymd_opt(2024, 11, 15).unwrap();
+
+        assert_eq!(t.expiry(base_date, 0).unwrap(), "11/24");
+        assert_eq!(t.expiry(base_date, 1).unwrap(), "12/24");
+        assert_eq!(t.expiry(base_date, 2).unwrap(), "01/25");
+        assert_eq!(t.expiry(base_date, -11).unwrap(), "12/23");
+    }
+
+    #[test]
+    fn range() {
+        let t =
+            transformer("card_expiry: { min_months: 0, max_months: 0, format: '%Y-%m' }").unwrap();
+        assert_eq!(
+            t.transform("field", "", &None).unwrap(),
+            Some("2025-01".to_string())
+        );
+
+        let t = transformer(
+            "card_expiry: { min_months: -2, max_months: -2, format: '%Y-%m', base_date: '2024-03-31' }",
+        )
+        .unwrap();
+        assert_eq!(
+            t.transform("field", "", &None).unwrap(),
+            Some("2024-01".to_string())
+        );
+
+        let t = transformer("card_expiry: {}").unwrap();
+        let value = t.transform("field", "", &None).unwrap().unwrap();
+        assert_eq!(value.len(), 5);
+        assert_eq!(&value[2..3], "/");
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            transformer("card_expiry: { min_months: 2, max_months: 1 }")
+                .unwrap_err()
+                .to_string(),
+            "`min_months` of the `card_expiry` transformer is greater than `max_months`"
+        );
+        assert_eq!(
+            transformer("card_expiry: { format: '%Q' }")
+                .unwrap_err()
+                .to_string(),
+            "Invalid format `%Q` of the `card_expiry` transformer"
+        );
+        assert_eq!(
+            transformer("card_expiry: { base_date: '01/02/2024' }")
+                .unwrap_err()
+                .to_string(),
+            "Invalid `base_date` `01/02/2024` of the `card_expiry` transformer (`YYYY-MM-DD` is expected)"
+        );
+    }
+
+    #[test]
+    fn uniq() {
+        let t = transformer(
+            "card_expiry: { min_months: 1, max_months: 2, uniq: { required: true, try_count: 50 } }",
+        ).unwrap();
+        assert!(t.transform("card_expiry_uniq", "", &None).is_ok());
+        assert!(t.transform("card_expiry_uniq", "", &None).is_ok());
+        assert!(t.transform("card_expiry_uniq", "", &None).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{
+    transformer::{TransformContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// Minimum number of digits of card numbers
+const MIN_LENGTH: usize = 12;
+const BIN_LENGTH: usize = 6;
+const LAST_DIGITS: usize = 4;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum CardBrand {
+    Visa,
+    Mastercard,
+    Amex,
+    Discover,
+    Jcb,
+}
+
+impl CardBrand {
+    const ALL: [CardBrand; 5] = [
+        Self::Visa,
+        Self::Mastercard,
+        Self::Amex,
+        Self::Discover,
+        Self::Jcb,
+    ];
+
+    /// Ranges of prefixes (IIN ranges)
+    fn prefixes(&self) -> &'static [(u32, u32)] {
+        match self {
+            Self::Visa => &[(4, 4)],
+            Self::Mastercard => &[(51, 55), (2221, 2720)],
+            Self::Amex => &[(34, 34), (37, 37)],
+            Self::Discover => &[(6011, 6011), (644, 649), (65, 65)],
+            Self::Jcb => &[(3528, 3589)],
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::Amex => 15,
+            _ => 16,
+        }
+    }
+}
+
+/// Generates card numbers with valid check digits (the Luhn algorithm).
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     credit_card:
+///       brand: mastercard
+/// ```
+/// where:
+/// * `brand` - `visa`, `mastercard`, `amex`, `discover` or `jcb` (a random brand by default);
+/// * `preserve_bin` - keep the first 6 digits of the original number (the issuer), the brand is ignored then;
+/// * `preserve_last4` - keep the last 4 digits of the original number.
+///
+/// With `preserve_bin` or `preserve_last4` the number has the same length as the original one
+/// (if the original value has at least 12 digits, otherwise a new number is generated).
+/// Numbers contain only digits.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CreditCardTransformer {
+    pub brand: Option<CardBrand>,
+    pub preserve_bin: bool,
+    pub preserve_last4: bool,
+    pub uniq: Uniqueness,
+}
+
+impl CreditCardTransformer {
+    fn generate<R: Rng>(&self, rng: &mut R) -> Vec<u8> {
+        let brand = match self.brand {
+            Some(brand) => brand,
+            None => *CardBrand::ALL.choose(rng).unwrap(),
+        };
+        let (min, max) = *brand.prefixes().choose(rng).unwrap();
+        let mut digits = digits(&rng.gen_range(min..=max).to_string());
+
+        let length = brand.length();
+        while digits.len() < length {
+            digits.push(rng.gen_range(0..10));
+        }
+        fix_check_digit(&mut digits, length - 1);
+        digits
+    }
+
+    /// Replaces digits of the original number (except preserved ones)
+    fn replace<R: Rng>(&self, original: &[u8], rng: &mut R) -> Vec<u8> {
+        let length = original.len();
+        let kept = |i: usize| {
+            (self.preserve_bin && i < BIN_LENGTH)
+                || (self.preserve_last4 && i >= length - LAST_DIGITS)
+        };
+
+        let mut digits: Vec<u8> = original
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| if kept(i) { d } else { rng.gen_range(0..10) })
+            .collect();
+        // The rightmost replaced digit makes the number valid (there is one at least,
+        // because the number is longer than the BIN and the last digits)
+        let free = (0..length).rev().find(|&i| !kept(i)).unwrap();
+        fix_check_digit(&mut digits, free);
+        digits
+    }
+}
+
+impl UniqTransformer for CreditCardTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let mut rng = rnd::rng();
+        let original = digits(field_value);
+
+        let digits = if (self.preserve_bin || self.preserve_last4) && original.len() >= MIN_LENGTH {
+            self.replace(&original, &mut rng)
+        } else {
+            self.generate(&mut rng)
+        };
+        digits.iter().map(|d| char::from(b'0' + d)).collect()
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+}
+
+fn digits(s: &str) -> Vec<u8> {
+    s.chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| d as u8)
+        .collect()
+}
+
+/// Sum of digits by the Luhn algorithm (every second digit from the right is doubled)
+fn luhn_sum(digits: &[u8]) -> u32 {
+    digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            let d = d as u32;
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 {
+                    doubled - 9
+                } else {
+                    doubled
+                }
+            } else {
+                d
+            }
+        })
+        .sum()
+}
+
+fn is_luhn_valid(digits: &[u8]) -> bool {
+    luhn_sum(digits).is_multiple_of(10)
+}
+
+/// Changes the digit at `position`, so the number becomes valid
+/// (each digit value gives a different remainder, so there is exactly one)
+fn fix_check_digit(digits: &mut [u8], position: usize) {
+    for d in 0..10 {
+        digits[position] = d;
+        if is_luhn_valid(digits) {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        transformers::test_utils::{transform, transformer},
+        Transformer,
+    };
+
+    #[test]
+    fn luhn() {
+        assert!(is_luhn_valid(&digits("4111 1111 1111 1111")));
+        assert!(is_luhn_valid(&digits("378282246310005")));
+        assert!(!is_luhn_valid(&digits("4111 1111 1111 1112")));
+    }
+
+    #[test]
+    fn brands() {
+        for _ in 0..20 {
+            let visa = transform("credit_card: { brand: visa }", "");
+            assert_eq!(visa.len(), 16);
+            assert!(visa.starts_with('4'));
+            assert!(is_luhn_valid(&digits(&visa)));
+
+            let amex = transform("credit_card: { brand: amex }", "");
+            assert_eq!(amex.len(), 15);
+            assert!(amex.starts_with("34") || amex.starts_with("37"));
+            assert!(is_luhn_valid(&digits(&amex)));
+
+            let any = transform("credit_card: {}", "");
+            assert!(is_luhn_valid(&digits(&any)));
+        }
+    }
+
+    #[test]
+    fn preserve() {
+        for _ in 0..20 {
+            let card = transform(
+                "credit_card: { preserve_bin: true, preserve_last4: true }",
+                "5500-0000-0000-0004",
+            );
+            assert_eq!(card.len(), 16);
+            assert!(card.starts_with("550000"));
+            assert!(card.ends_with("0004"));
+            assert!(is_luhn_valid(&digits(&card)));
+
+            let card = transform("credit_card: { preserve_last4: true }", "378282246310005");
+            assert_eq!(card.len(), 15);
+            assert!(card.ends_with("0005"));
+            assert!(is_luhn_valid(&digits(&card)));
+        }
+
+        let card = transform("credit_card: { preserve_bin: true, brand: visa }", "n/a");
+        assert!(card.starts_with('4'));
+    }
+
+    #[test]
+    fn uniq() {
+        let t = transformer("credit_card: { preserve_bin: true, uniq: true }").unwrap();
+        let mut cards: Vec<String> = (0..100)
+            .map(|_| {
+                t.transform("credit_card_uniq", "4111111111111111", &None)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect();
+        cards.sort();
+        cards.dedup();
+        assert_eq!(cards.len(), 100);
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    transformer::{TransformContext, TransformerInitContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
+use anyhow::bail;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// BBAN formats from the IBAN registry: `n` - digits, `a` - uppercase letters, `c` - digits and letters
+pub(crate) const COUNTRIES: [(&str, &str); 16] = [
+    ("AT", "16n"),
+    ("BE", "12n"),
+    ("CH", "5n12c"),
+    ("DE", "18n"),
+    ("DK", "14n"),
+    ("ES", "20n"),
+    ("FI", "14n"),
+    ("FR", "10n11c2n"),
+    ("GB", "4a14n"),
+    ("IE", "4a14n"),
+    ("IT", "1a10n12c"),
+    ("NL", "4a10n"),
+    ("NO", "11n"),
+    ("PL", "24n"),
+    ("PT", "21n"),
+    ("SE", "20n"),
+];
+
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const ALPHANUMERIC: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Generates IBANs with valid check digits (mod 97).
+///
+/// # Example:
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     iban:
+///       country: DE
+/// ```
+/// where `country` is the country code: AT, BE, CH, DE, DK, ES, FI, FR, GB, IE, IT, NL, NO, PL, PT or SE.
+/// By default, it is the country of the original IBAN (if it is supported), otherwise a random one.
+///
+/// IBANs are in the electronic format (without spaces). National check digits inside BBANs
+/// (e.g. in BE, ES, FR and IT) are not valid.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug, Default)]
+#[serde(default)]
+pub struct IbanTransformer {
+    pub country: Option<String>,
+    pub uniq: Uniqueness,
+}
+
+impl IbanTransformer {
+    fn format(&self, original: &str) -> (&'static str, &'static str) {
+        let country = match &self.country {
+            Some(country) => Some(country.as_str()),
+            None => original.trim().get(..2),
+        };
+        country
+            .and_then(|country| {
+                COUNTRIES
+                    .iter()
+                    .find(|(c, _)| c.eq_ignore_ascii_case(country))
+            })
+            .copied()
+            .unwrap_or_else(|| *COUNTRIES.choose(&mut rnd::rng()).unwrap())
+    }
+}
+
+impl UniqTransformer for IbanTransformer {
+    fn do_transform(
+        &self,
+        _field_name: &str,
+        field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> String {
+        let (country, format) = self.format(field_value);
+        let bban = bban(format, &mut rnd::rng());
+        format!("{}{:02}{}", country, check_digits(country, &bban), bban)
+    }
+
+    fn uniq(&self) -> &Uniqueness {
+        &self.uniq
+    }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        if let Some(country) = &self.country {
+            if !COUNTRIES
+                .iter()
+                .any(|(c, _)| c.eq_ignore_ascii_case(country))
+            {
+                bail!(
+                    "The country `{}` is not supported by the `iban` transformer",
+                    country
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn bban<R: Rng>(format: &str, rng: &mut R) -> String {
+    let mut bban = String::new();
+    let mut count = 0;
+    for c in format.chars() {
+        match c.to_digit(10) {
+            Some(d) => count = count * 10 + d as usize,
+            None => {
+                let chars = match c {
+                    'a' => LETTERS,
+                    'c' => ALPHANUMERIC,
+                    _ => b"0123456789",
+                };
+                for _ in 0..count {
+                    bban.push(*chars.choose(rng).unwrap() as char);
+                }
+                count = 0;
+            }
+        }
+    }
+    bban
+}
+
+/// ISO 7064 MOD 97-10 check digits
+fn check_digits(country: &str, bban: &str) -> u32 {
+    98 - mod97(&format!("{}{}00", bban, country))
+}
+
+/// Remainder of the number where letters are replaced with numbers (A = 10, ..., Z = 35)
+fn mod97(s: &str) -> u32 {
+    s.chars().filter_map(|c| c.to_digit(36)).fold(0, |rem, d| {
+        if d < 10 {
+            (rem * 10 + d) % 97
+        } else {
+            (rem * 100 + d) % 97
+        }
+    })
+}
+
+/// Checks the length and the check digits of the IBAN (without spaces)
+#[cfg(test)]
+fn is_valid(iban: &str) -> bool {
+    let format = match COUNTRIES.iter().find(|(c, _)| iban.starts_with(c)) {
+        Some((_, format)) => format,
+        None => return false,
+    };
+    let length = format
+        .split(|c: char| c.is_ascii_alphabetic())
+        .filter_map(|n| n.parse::<usize>().ok())
+        .sum::<usize>()
+        + 4;
+    iban.len() == length && mod97(&format!("{}{}", &iban[4..], &iban[..4])) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformers::test_utils::{transform, transformer};
+
+    #[test]
+    fn check() {
+        assert!(is_valid("GB82WEST12345698765432"));
+        assert!(is_valid("DE89370400440532013000"));
+        assert!(!is_valid("DE89370400440532013001"));
+        assert_eq!(check_digits("GB", "WEST12345698765432"), 82);
+    }
+
+    #[test]
+    fn countries() {
+        for (country, _) in COUNTRIES {
+            for _ in 0..10 {
+                let iban = transform(&format!("iban: {{ country: {} }}", country), "");
+                assert!(iban.starts_with(country));
+                assert!(is_valid(&iban), "{}", iban);
+            }
+        }
+    }
+
+    #[test]
+    fn original_country() {
+        let iban = transform("iban: {}", "GB82 WEST 1234 5698 7654 32");
+        assert!(iban.starts_with("GB"));
+        assert_eq!(iban.len(), 22);
+        assert!(is_valid(&iban));
+
+        assert!(is_valid(&transform("iban: {}", "XX00")));
+        assert!(transform("iban: { country: nl }", "DE89370400440532013000").starts_with("NL"));
+    }
+
+    #[test]
+    fn unknown_country() {
+        assert_eq!(
+            transformer("iban: { country: US }")
+                .unwrap_err()
+                .to_string(),
+            "The country `US` is not supported by the `iban` transformer"
+        );
+    }
+}
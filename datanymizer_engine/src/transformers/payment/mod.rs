@@ -0,0 +1,11 @@
+mod bic;
+pub use bic::BicTransformer;
+
+mod card_expiry;
+pub use card_expiry::CardExpiryTransformer;
+
+mod credit_card;
+pub use credit_card::{CardBrand, CreditCardTransformer};
+
+mod iban;
+pub use iban::IbanTransformer;
@@ -1,6 +1,9 @@
 use super::deserialize_phone_format;
 use super::phone_format::PhoneFormat;
-use crate::transformer::{TransformContext, UniqTransformer, Uniqueness};
+use crate::{
+    transformer::{TransformContext, UniqTransformer, Uniqueness},
+    utils::rnd,
+};
 use fake::Fake;
 use serde::{Deserialize, Serialize};
 use std::char;
@@ -58,7 +61,7 @@ fn do_transform(
         _field_value: &str,
         _ctx: &Option<TransformContext>,
     ) -> String {
-        let mut rng = rand::thread_rng();
+        let mut rng = rnd::rng();
 
         self.phone_format()
             .source_format
@@ -40,25 +40,32 @@ fn transform(
         field_value: &str,
         ctx: &Option<TransformContext>,
     ) -> TransformResult {
-        let res: String = self
-            .pipes
-            .iter()
-            .fold(field_value.to_string(), |acc, pipe| {
-                let transformed = pipe.transform(field_name, &acc, ctx);
-                if let Ok(Some(x)) = transformed {
-                    x
-                } else {
-                    "".to_string()
-                }
-            });
+        let mut res = field_value.to_string();
+        for pipe in &self.pipes {
+            res = match pipe.transform(field_name, &res, ctx) {
+                Ok(Some(x)) => x,
+                // The next pipes can't transform NULL
+                Ok(None) => return TransformResult::null(),
+                Err(_) => "".to_string(),
+            };
+        }
 
         TransformResult::present(res)
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
+    fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
         for t in &mut self.pipes {
-            t.init(ctx);
+            t.init(ctx)?;
         }
+        Ok(())
+    }
+
+    fn uses_store(&self) -> bool {
+        self.pipes.iter().any(|t| t.uses_store())
+    }
+
+    fn uses_uniq(&self) -> bool {
+        self.pipes.iter().any(|t| t.uses_uniq())
     }
 }
 
@@ -86,7 +93,8 @@ fn init() {
             TransformerDefaults {
                 locale: LocaleConfig::RU,
             },
-        ));
+        ))
+        .unwrap();
 
         assert!(
             matches!(&t.pipes[0], Transformers::FirstName(t) if t.locale == Some(LocaleConfig::RU))
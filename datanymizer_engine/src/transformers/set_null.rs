@@ -0,0 +1,35 @@
+use crate::transformer::{TransformContext, TransformResult, TransformResultHelper, Transformer};
+use serde::{Deserialize, Serialize};
+
+/// Replaces values with NULL (`null` is a reserved word in YAML, so it can't be the name).
+///
+/// ```yaml
+/// set_null: ~
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Debug)]
+pub struct SetNullTransformer;
+
+impl Transformer for SetNullTransformer {
+    fn transform(
+        &self,
+        _field_name: &str,
+        _field_value: &str,
+        _ctx: &Option<TransformContext>,
+    ) -> TransformResult {
+        TransformResult::null()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rule;
+
+    #[test]
+    fn parse_and_transform() {
+        let rule: Rule = serde_yaml::from_str("set_null: ~").unwrap();
+        let value = rule.transformer.transform("field", "value", &None).unwrap();
+
+        assert_eq!(value, None);
+    }
+}
@@ -1,11 +1,22 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, env};
 
+use crate::transformers::{HashAlgorithm, HashEncoding};
 use bcrypt::{hash, DEFAULT_COST};
 use serde_json::{from_value, Value};
-use tera::{try_get_value, Error, Result, Tera};
+use tera::{try_get_value, Error, Filter, Result, Tera};
 
 pub fn register(t: &mut Tera) {
     t.register_filter("bcrypt_hash", bcrypt_hash);
+
+    t.register_filter("sha256", HashFilter(HashAlgorithm::Sha256));
+    t.register_filter("sha512", HashFilter(HashAlgorithm::Sha512));
+    t.register_filter("blake3", HashFilter(HashAlgorithm::Blake3));
+    t.register_filter("md5", HashFilter(HashAlgorithm::Md5));
+    t.register_filter("hmac_sha256", hmac_sha256);
+
+    t.register_filter("hex", EncodingFilter(HashEncoding::Hex));
+    t.register_filter("base32", EncodingFilter(HashEncoding::Base32));
+    t.register_filter("base64", EncodingFilter(HashEncoding::Base64));
 }
 
 /// BCrypt hash function
@@ -47,6 +58,115 @@ fn bcrypt_hash(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
         .map_err(|err| Error::from(err.to_string()))
 }
 
+/// Hash functions: `sha256`, `sha512`, `blake3` and `md5` (only for legacy systems).
+/// Hashes are encoded with `hex` by default, the `encoding` argument can be `hex`, `base32`, `base64` or `base64url`.
+///
+/// # Examples
+///
+/// ```yaml
+/// #...
+/// rules:
+///   email:
+///     template:
+///       format: "{{ _0 | sha256 }}@example.com"
+///   token:
+///     template:
+///       format: "{{ _0 | blake3(encoding='base64url') }}"
+/// ```
+struct HashFilter(HashAlgorithm);
+
+impl Filter for HashFilter {
+    fn filter(&self, value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+        let name = filter_name(self.0);
+        let s = try_get_value!(name, "value", String, value);
+        let encoding = encoding_arg(name, args)?;
+        Ok(encoding.encode(&self.0.digest(None, s.as_bytes())).into())
+    }
+}
+
+/// HMAC-SHA256 (a keyed hash). The key is the `key` argument (e.g. a global value)
+/// or the environment variable from the `key_env` argument.
+///
+/// # Examples
+///
+/// ```yaml
+/// #...
+/// rules:
+///   user_ref:
+///     template:
+///       # `hash_key` is a global value
+///       format: "{{ _0 | hmac_sha256(key=hash_key) }}"
+///   email:
+///     template:
+///       format: "{{ _0 | hmac_sha256(key_env='DATANYMIZER_HASH_KEY', encoding='base32') | lower }}@example.com"
+/// ```
+fn hmac_sha256(value: &Value, args: &HashMap<String, Value>) -> Result<Value> {
+    let key = match (args.get("key"), args.get("key_env")) {
+        (Some(Value::String(key)), None) => key.clone(),
+        (Some(key), None) => key.to_string(),
+        (None, Some(name)) => {
+            let name = try_get_value!("hmac_sha256", "key_env", String, name);
+            env::var(&name).map_err(|_| {
+                Error::msg(format!(
+                    "Filter `hmac_sha256`: the environment variable `{}` is not set",
+                    name
+                ))
+            })?
+        }
+        _ => {
+            return Err(Error::msg(
+                "Filter `hmac_sha256` needs either the `key` or the `key_env` argument",
+            ))
+        }
+    };
+
+    let s = try_get_value!("hmac_sha256", "value", String, value);
+    let encoding = encoding_arg("hmac_sha256", args)?;
+    let digest = HashAlgorithm::Sha256.digest(Some(key.as_bytes()), s.as_bytes());
+    Ok(encoding.encode(&digest).into())
+}
+
+/// Encodes strings (their UTF-8 bytes) with `hex`, `base32` (without padding) or `base64`
+///
+/// # Examples
+///
+/// ```yaml
+/// #...
+/// rules:
+///   field_name:
+///     template:
+///       format: "{{ _0 | base64 }}"
+/// ```
+struct EncodingFilter(HashEncoding);
+
+impl Filter for EncodingFilter {
+    fn filter(&self, value: &Value, _args: &HashMap<String, Value>) -> Result<Value> {
+        let s = try_get_value!("encoding", "value", String, value);
+        Ok(self.0.encode(s.as_bytes()).into())
+    }
+}
+
+fn filter_name(algorithm: HashAlgorithm) -> &'static str {
+    match algorithm {
+        HashAlgorithm::Sha256 => "sha256",
+        HashAlgorithm::Sha512 => "sha512",
+        HashAlgorithm::Blake3 => "blake3",
+        HashAlgorithm::Md5 => "md5",
+    }
+}
+
+fn encoding_arg(filter: &str, args: &HashMap<String, Value>) -> Result<HashEncoding> {
+    match args.get("encoding") {
+        Some(val) => from_value(val.clone()).map_err(|_| {
+            Error::msg(format!(
+                "Filter `{}` received encoding={} but `encoding` can only be hex, base32, base64 or base64url",
+                filter, val
+            ))
+        }),
+        None => Ok(HashEncoding::default()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +200,54 @@ fn bcrypt_hash_cost() {
         let real_value = t.render("empty_filter", &context).unwrap();
         assert!(verify(pass, &real_value).unwrap());
     }
+
+    fn render(template: &str) -> Result<String> {
+        let mut t = Tera::default();
+        let mut context = Context::new();
+        context.insert("value", "abc");
+        context.insert("hash_key", "Jefe");
+
+        register(&mut t);
+        t.add_raw_template("template", template).unwrap();
+        t.render("template", &context)
+    }
+
+    #[test]
+    fn hash_filters() {
+        assert_eq!(
+            render("{{ value | sha256 }}").unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            render("{{ value | md5(encoding='base64') }}").unwrap(),
+            "kAFQmDzST7DWlj99KOF/cg=="
+        );
+        assert_eq!(render("{{ value | sha512 }}").unwrap().len(), 128);
+        assert_eq!(render("{{ value | blake3 }}").unwrap().len(), 64);
+        assert!(render("{{ value | sha256(encoding='base16') }}").is_err());
+    }
+
+    #[test]
+    fn hmac_sha256_filter() {
+        let expected =
+            HashEncoding::Hex.encode(&HashAlgorithm::Sha256.digest(Some(b"Jefe"), b"abc"));
+        assert_eq!(
+            render("{{ value | hmac_sha256(key=hash_key) }}").unwrap(),
+            expected
+        );
+
+        env::set_var("DATANYMIZER_TEST_HMAC_KEY", "Jefe");
+        assert_eq!(
+            render("{{ value | hmac_sha256(key_env='DATANYMIZER_TEST_HMAC_KEY') }}").unwrap(),
+            expected
+        );
+        assert!(render("{{ value | hmac_sha256 }}").is_err());
+    }
+
+    #[test]
+    fn encoding_filters() {
+        assert_eq!(render("{{ value | hex }}").unwrap(), "616263");
+        assert_eq!(render("{{ value | base32 }}").unwrap(), "MFRGG");
+        assert_eq!(render("{{ value | base64 }}").unwrap(), "YWJj");
+    }
 }
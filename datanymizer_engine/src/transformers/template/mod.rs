@@ -14,6 +14,7 @@
 use serde_json::Value;
 use std::{
     collections::HashMap,
+    fs,
     hash::{Hash, Hasher},
 };
 use tera::{Context, Tera};
@@ -55,6 +56,9 @@ pub struct TemplateTransformer {
 
     #[serde(skip)]
     renderer: Tera,
+    /// The template or the templates of the collection call the store functions
+    #[serde(skip)]
+    uses_store: bool,
 }
 
 impl TemplateTransformer {
@@ -71,6 +75,7 @@ pub fn new(
             rules,
             variables,
             renderer,
+            uses_store: false,
         }
     }
 
@@ -126,8 +131,7 @@ fn transform(
                 let key = format!("_{}", i + 1);
                 let transform_result: Option<String> =
                     rule.transform(field_name, field_value, ctx)?;
-                let value = transform_result.unwrap_or_default();
-                rules_names.insert(key, Value::String(value));
+                rules_names.insert(key, transform_result.map_or(Value::Null, Value::String));
             }
         }
 
@@ -161,38 +165,72 @@ fn transform(
         }
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
+    fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
         store_functions::register(&mut self.renderer, ctx.template_store.clone());
         hash_functions::register(&mut self.renderer);
 
         let mut ext_renderer = Tera::default();
+        self.uses_store = store_functions::used_in(&self.format);
 
         if let Some(templates) = &ctx.template_collection.raw {
             for (name, body) in templates {
-                ext_renderer.add_raw_template(name, body).unwrap();
+                ext_renderer
+                    .add_raw_template(name, body)
+                    .map_err(template_error)?;
+                self.uses_store |= store_functions::used_in(body);
             }
         }
 
         if let Some(files) = &ctx.template_collection.files {
             for file in files.iter() {
-                ext_renderer.add_template_file(file, None).unwrap();
+                ext_renderer
+                    .add_template_file(file, None)
+                    .map_err(template_error)?;
+                self.uses_store |= fs::read_to_string(file)
+                    .map(|body| store_functions::used_in(&body))
+                    .unwrap_or(true);
             }
         }
 
-        self.renderer.extend(&ext_renderer).unwrap();
+        self.renderer
+            .extend(&ext_renderer)
+            .map_err(template_error)?;
 
         if let Some(ts) = &mut self.rules {
             for t in ts {
-                t.init(ctx);
+                t.init(ctx)?;
+                self.uses_store |= t.uses_store();
             }
         }
 
         self.renderer
             .add_raw_template(TEMPLATE_NAME, &self.format)
-            .unwrap();
+            .map_err(template_error)?;
+        Ok(())
+    }
+
+    fn uses_store(&self) -> bool {
+        self.uses_store
+    }
+
+    fn uses_uniq(&self) -> bool {
+        self.rules
+            .as_ref()
+            .is_some_and(|rules| rules.iter().any(|t| t.uses_uniq()))
     }
 }
 
+/// Tera errors keep the details (e.g. the position of a syntax error) in the sources
+fn template_error(err: tera::Error) -> anyhow::Error {
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(&err);
+    while let Some(e) = source {
+        message.push_str(&format!(": {}", e));
+        source = e.source();
+    }
+    anyhow::Error::msg(message)
+}
+
 impl From<Config> for TemplateTransformer {
     fn from(cfg: Config) -> Self {
         Self::new(
@@ -242,7 +280,9 @@ fn template_interpolation() {
                           "#;
 
         let mut transformer: Transformers = EnumWrapper::parse(config).unwrap();
-        transformer.init(&TransformerInitContext::default());
+        transformer
+            .init(&TransformerInitContext::default())
+            .unwrap();
 
         let res = transformer.transform(
             "",
@@ -274,7 +314,8 @@ fn init() {
             TransformerDefaults {
                 locale: LocaleConfig::RU,
             },
-        ));
+        ))
+        .unwrap();
 
         let rules = t.rules.unwrap();
 
@@ -303,7 +344,7 @@ fn transformer() -> Transformers {
                                        format: "Hello, {{ prev.first_name }} {{ prev.last_name }}!"
                                   "#;
                 let mut t: Transformers = EnumWrapper::parse(config).unwrap();
-                t.init(&TransformerInitContext::default());
+                t.init(&TransformerInitContext::default()).unwrap();
 
                 t
             }
@@ -312,7 +353,7 @@ fn transformer() -> Transformers {
             fn interpolation() {
                 let expected: String = String::from("Hello, FIRST LAST!");
 
-                let prev_row = vec!["FIRST", "MIDDLE", "LAST"];
+                let prev_row = vec![Some("FIRST"), Some("MIDDLE"), Some("LAST")];
 
                 let res = transformer().transform(
                     "",
@@ -338,7 +379,7 @@ fn transformer() -> Transformers {
                                    format: "Hello, {{ final.first_name }} {{ final.last_name }}!"
                               "#;
                 let mut t: Transformers = EnumWrapper::parse(config).unwrap();
-                t.init(&TransformerInitContext::default());
+                t.init(&TransformerInitContext::default()).unwrap();
 
                 t
             }
@@ -348,9 +389,9 @@ fn interpolation() {
                 let expected: String = String::from("Hello, FIRST LAST!");
 
                 let final_row = vec![
-                    Cow::Owned(String::from("FIRST")),
-                    Cow::Borrowed("untransformed"),
-                    Cow::Owned(String::from("LAST")),
+                    Some(Cow::Owned(String::from("FIRST"))),
+                    Some(Cow::Borrowed("untransformed")),
+                    Some(Cow::Owned(String::from("LAST"))),
                 ];
 
                 let res = transformer().transform(
@@ -372,9 +413,9 @@ fn nested_interpolation() {
                 let expected: String = String::from("Hello, FIRST LAST!");
 
                 let final_row = vec![
-                    Cow::Owned(String::from("FIRST")),
-                    Cow::Borrowed("untransformed"),
-                    Cow::Owned(String::from("LAST")),
+                    Some(Cow::Owned(String::from("FIRST"))),
+                    Some(Cow::Borrowed("untransformed")),
+                    Some(Cow::Owned(String::from("LAST"))),
                 ];
 
                 let config = r#"
@@ -385,7 +426,7 @@ fn nested_interpolation() {
                                          format: "{{ final.last_name }}"
                               "#;
                 let mut t: Transformers = EnumWrapper::parse(config).unwrap();
-                t.init(&TransformerInitContext::default());
+                t.init(&TransformerInitContext::default()).unwrap();
 
                 let res = t.transform(
                     "",
@@ -404,9 +445,9 @@ fn nested_interpolation() {
             #[test]
             fn ref_to_untransformed_value() {
                 let final_row = vec![
-                    Cow::Owned(String::from("FIRST")),
-                    Cow::Borrowed("untransformed"),
-                    Cow::Borrowed("untransformed"),
+                    Some(Cow::Owned(String::from("FIRST"))),
+                    Some(Cow::Borrowed("untransformed")),
+                    Some(Cow::Borrowed("untransformed")),
                 ];
 
                 let res = transformer().transform(
@@ -440,7 +481,7 @@ fn transformer() -> Transformers {
                                        format: "Hello, {{ prev.first_name }} {{ final.last_name }}!"
                                   "#;
                 let mut t: Transformers = EnumWrapper::parse(config).unwrap();
-                t.init(&TransformerInitContext::default());
+                t.init(&TransformerInitContext::default()).unwrap();
 
                 t
             }
@@ -449,12 +490,12 @@ fn transformer() -> Transformers {
             fn interpolation() {
                 let expected: String = String::from("Hello, FIRST tLAST!");
 
-                let prev_row = vec!["FIRST", "MIDDLE", "LAST"];
+                let prev_row = vec![Some("FIRST"), Some("MIDDLE"), Some("LAST")];
 
                 let final_row = vec![
-                    Cow::Owned(String::from("tFIRST")),
-                    Cow::Borrowed("untransformed"),
-                    Cow::Owned(String::from("tLAST")),
+                    Some(Cow::Owned(String::from("tFIRST"))),
+                    Some(Cow::Borrowed("untransformed")),
+                    Some(Cow::Owned(String::from("tLAST"))),
                 ];
 
                 let res = transformer().transform(
@@ -512,9 +553,9 @@ fn basic() {
             let mut w = write_transformer();
             let ctx = TransformerInitContext::default();
 
-            r.init(&ctx);
-            rd.init(&ctx);
-            w.init(&ctx);
+            r.init(&ctx).unwrap();
+            rd.init(&ctx).unwrap();
+            w.init(&ctx).unwrap();
 
             let value = w.transform("field", "a", &None).unwrap().unwrap();
             assert_eq!(value, "Write: value_a into key_a");
@@ -548,9 +589,9 @@ fn repeatable_read() {
             let mut w = write_transformer();
             let ctx = TransformerInitContext::default();
 
-            r.init(&ctx);
-            rd.init(&ctx);
-            w.init(&ctx);
+            r.init(&ctx).unwrap();
+            rd.init(&ctx).unwrap();
+            w.init(&ctx).unwrap();
 
             let value = w.transform("field", "a", &None).unwrap().unwrap();
             assert_eq!(value, "Write: value_a into key_a");
@@ -582,8 +623,8 @@ fn condition() {
             let mut w = write_transformer();
             let ctx = TransformerInitContext::default();
 
-            t.init(&ctx);
-            w.init(&ctx);
+            t.init(&ctx).unwrap();
+            w.init(&ctx).unwrap();
 
             let value = w.transform("field", "a", &None).unwrap().unwrap();
             assert_eq!(value, "Write: value_a into key_a");
@@ -611,8 +652,8 @@ fn overwrite() {
             let mut w: Transformers = EnumWrapper::parse(config).unwrap();
             let ctx = TransformerInitContext::default();
 
-            r.init(&ctx);
-            w.init(&ctx);
+            r.init(&ctx).unwrap();
+            w.init(&ctx).unwrap();
 
             let value = w.transform("field", "a", &None).unwrap().unwrap();
             assert_eq!(value, "Write: value_a into key");
@@ -638,8 +679,8 @@ fn inc() {
             let mut w: Transformers = EnumWrapper::parse(config).unwrap();
             let ctx = TransformerInitContext::default();
 
-            r.init(&ctx);
-            w.init(&ctx);
+            r.init(&ctx).unwrap();
+            w.init(&ctx).unwrap();
 
             let value = w.transform("field", "0.5", &None).unwrap().unwrap();
             assert_eq!(value, "Write: 0.5 into key");
@@ -686,7 +727,7 @@ fn using_macros() {
                 template_collection: templates_collection,
                 ..Default::default()
             };
-            t.init(&context);
+            t.init(&context).unwrap();
 
             let value = t.transform("field", "", &None).unwrap().unwrap();
             assert_eq!(value, "10-9-8-7-6-5-4-3-2-1");
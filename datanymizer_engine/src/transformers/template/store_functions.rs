@@ -2,6 +2,13 @@
 use std::collections::HashMap;
 use tera::{Function, Tera, Value};
 
+const FUNCTIONS: [&str; 4] = [
+    "store_read",
+    "store_write",
+    "store_force_write",
+    "store_inc",
+];
+
 pub fn register<S: 'static + KeyValueStore>(t: &mut Tera, store: S) {
     t.register_function("store_read", read(store.clone()));
     t.register_function("store_write", write(store.clone()));
@@ -9,6 +16,11 @@ pub fn register<S: 'static + KeyValueStore>(t: &mut Tera, store: S) {
     t.register_function("store_inc", inc(store));
 }
 
+/// Whether the template source calls the store functions
+pub fn used_in(source: &str) -> bool {
+    FUNCTIONS.iter().any(|f| source.contains(f))
+}
+
 fn read<S: KeyValueStore>(store: S) -> impl Function {
     Box::new(
         move |args: &HashMap<String, Value>| -> tera::Result<Value> {
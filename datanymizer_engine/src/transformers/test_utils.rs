@@ -0,0 +1,20 @@
+//! Fixtures for tests of transformers
+
+use super::Transformers;
+use crate::{transformer::TransformerInitContext, utils::EnumWrapper, Transformer};
+
+/// Parses and initializes the transformer
+pub fn transformer(config: &str) -> anyhow::Result<Transformers> {
+    let mut t: Transformers = EnumWrapper::parse(config).unwrap();
+    t.init(&TransformerInitContext::default())?;
+    Ok(t)
+}
+
+/// Transforms the value with a new transformer, the result must be present
+pub fn transform(config: &str, value: &str) -> String {
+    transformer(config)
+        .unwrap()
+        .transform("field", value, &None)
+        .unwrap()
+        .unwrap()
+}
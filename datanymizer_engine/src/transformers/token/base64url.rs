@@ -1,5 +1,9 @@
+use super::check_padding;
 use crate::{
-    transformer::{TransformContext, TransformResult, TransformResultHelper, Transformer},
+    transformer::{
+        TransformContext, TransformResult, TransformResultHelper, Transformer,
+        TransformerInitContext,
+    },
     utils::rnd::rnd_chars,
 };
 use serde::{Deserialize, Serialize};
@@ -63,8 +67,8 @@ fn transform(
         let padding = match self.pad {
             0 => "",
             1 => "%3D",
-            2 => "%3D%3D",
-            _ => panic!("Incorrect padding"),
+            // The padding is checked in `init`
+            _ => "%3D%3D",
         };
         TransformResult::present(format!(
             "{}{}",
@@ -72,12 +76,16 @@ fn transform(
             padding
         ))
     }
+
+    fn init(&mut self, _ctx: &TransformerInitContext) -> anyhow::Result<()> {
+        check_padding(self.len, self.pad, "base64url_token")
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{utils::EnumWrapper, Transformers};
+    use crate::{transformers::test_utils::transformer, utils::EnumWrapper, Transformers};
 
     fn transformed_value(cfg: &str) -> String {
         let transformer: Base64UrlTokenTransformer = serde_yaml::from_str(cfg).unwrap();
@@ -125,4 +133,20 @@ fn padding() {
         }
         assert_eq!(&value[30..], "%3D%3D");
     }
+
+    #[test]
+    fn invalid_padding() {
+        assert_eq!(
+            transformer("base64url_token: { pad: 3 }")
+                .unwrap_err()
+                .to_string(),
+            "`pad` of the `base64url_token` transformer must be from 0 to 2"
+        );
+        assert_eq!(
+            transformer("base64url_token: { len: 1, pad: 2 }")
+                .unwrap_err()
+                .to_string(),
+            "`pad` of the `base64url_token` transformer is greater than `len`"
+        );
+    }
 }
@@ -1,3 +1,5 @@
+use anyhow::bail;
+
 mod base64;
 pub use base64::Base64TokenTransformer;
 
@@ -6,3 +8,20 @@
 
 mod hex;
 pub use hex::HexTokenTransformer;
+
+/// Checks the padding of Base64 tokens (it's included into the length)
+fn check_padding(len: usize, pad: usize, transformer: &str) -> anyhow::Result<()> {
+    if pad > 2 {
+        bail!(
+            "`pad` of the `{}` transformer must be from 0 to 2",
+            transformer
+        );
+    }
+    if pad > len {
+        bail!(
+            "`pad` of the `{}` transformer is greater than `len`",
+            transformer
+        );
+    }
+    Ok(())
+}
@@ -1,6 +1,10 @@
-use crate::transformer::{TransformContext, TransformResult, TransformResultHelper, Transformer};
+use crate::{
+    transformer::{TransformContext, TransformResult, TransformResultHelper, Transformer},
+    utils::rnd,
+};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use uuid::Builder;
 
 /// Generates random UUIDs (http://en.wikipedia.org/wiki/Universally_unique_identifier)
 /// It uses the UUID version 4 algorithm.
@@ -23,7 +27,8 @@ fn transform(
         _field_value: &str,
         _ctx: &Option<TransformContext>,
     ) -> TransformResult {
-        TransformResult::present(Uuid::new_v4().to_string())
+        let uuid = Builder::from_random_bytes(rnd::rng().gen()).into_uuid();
+        TransformResult::present(uuid.to_string())
     }
 }
 
@@ -31,6 +36,7 @@ fn transform(
 mod tests {
     use super::*;
     use crate::{utils::EnumWrapper, Transformers};
+    use uuid::Uuid;
 
     #[test]
     fn transform() {
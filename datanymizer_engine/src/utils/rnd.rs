@@ -1,10 +1,88 @@
-use rand::distributions::{Distribution, Uniform};
+use hmac::{Hmac, Mac};
+use rand::{
+    distributions::{Distribution, Uniform},
+    RngCore, SeedableRng,
+};
+use rand_chacha::ChaCha20Rng;
+use sha2::Sha256;
+use std::cell::RefCell;
+
+pub type Seed = [u8; 32];
+
+thread_local! {
+    static SEEDED_RNG: RefCell<Option<ChaCha20Rng>> = const { RefCell::new(None) };
+}
+
+/// The random number generator for transformers.
+///
+/// Inside [with_seed] it draws from the seeded generator (so the results are reproducible),
+/// otherwise it draws from `rand::thread_rng()`.
+/// Transformers should use it instead of `rand::thread_rng()` to support deterministic rules.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TransformRng;
+
+impl TransformRng {
+    fn with<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        SEEDED_RNG.with(|cell| match cell.borrow_mut().as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut rand::thread_rng()),
+        })
+    }
+}
+
+impl RngCore for TransformRng {
+    fn next_u32(&mut self) -> u32 {
+        Self::with(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Self::with(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Self::with(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        Self::with(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+pub fn rng() -> TransformRng {
+    TransformRng
+}
+
+/// Runs `f` with the generator from [rng] seeded by `seed`.
+/// The previous state is restored afterwards, so calls can be nested.
+pub fn with_seed<T>(seed: Seed, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<ChaCha20Rng>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            SEEDED_RNG.with(|cell| *cell.borrow_mut() = prev);
+        }
+    }
+
+    let _restore =
+        Restore(SEEDED_RNG.with(|cell| cell.replace(Some(ChaCha20Rng::from_seed(seed)))));
+    f()
+}
+
+/// Derives a seed as HMAC-SHA256(secret, name + value).
+/// The name is length-prefixed, so different (name, value) pairs can't produce the same message.
+pub fn derive_seed(secret: &[u8], name: &str, value: &str) -> Seed {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&(name.len() as u64).to_be_bytes());
+    mac.update(name.as_bytes());
+    mac.update(value.as_bytes());
+    mac.finalize().into_bytes().into()
+}
 
 pub fn rnd_chars(len: usize, src: &[char]) -> String {
-    let rng = rand::thread_rng();
     let distribution = Uniform::<usize>::from(0..src.len());
     distribution
-        .sample_iter(rng)
+        .sample_iter(rng())
         .take(len)
         .map(|i| src[i])
         .collect::<String>()
@@ -39,4 +117,66 @@ fn different_chars() {
             assert!(chars.contains(&ch));
         }
     }
+
+    mod seeded {
+        use super::*;
+
+        const CHARS: [char; 16] = [
+            '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd', 'e', 'f',
+        ];
+
+        #[test]
+        fn same_seed() {
+            let seed = derive_seed(b"secret", "table.column", "value");
+            let s1 = with_seed(seed, || rnd_chars(32, &CHARS));
+            let s2 = with_seed(seed, || rnd_chars(32, &CHARS));
+
+            assert_eq!(s1, s2);
+        }
+
+        #[test]
+        fn different_seeds() {
+            let s1 = with_seed(derive_seed(b"secret", "t.c", "value1"), || {
+                rnd_chars(32, &CHARS)
+            });
+            let s2 = with_seed(derive_seed(b"secret", "t.c", "value2"), || {
+                rnd_chars(32, &CHARS)
+            });
+
+            assert_ne!(s1, s2);
+        }
+
+        #[test]
+        fn nested() {
+            let outer = derive_seed(b"secret", "t.c", "outer");
+            let inner = derive_seed(b"secret", "t.c", "inner");
+
+            let (s1, s2) = with_seed(outer, || {
+                let s1 = rnd_chars(16, &CHARS);
+                with_seed(inner, || rnd_chars(16, &CHARS));
+                (s1, rnd_chars(16, &CHARS))
+            });
+            let expected = with_seed(outer, || (rnd_chars(16, &CHARS), rnd_chars(16, &CHARS)));
+
+            assert_eq!((s1, s2), expected);
+        }
+    }
+
+    mod derive_seed {
+        use super::*;
+
+        #[test]
+        fn depends_on_all_parts() {
+            let seed = derive_seed(b"secret", "t.c", "value");
+
+            assert_eq!(seed, derive_seed(b"secret", "t.c", "value"));
+            assert_ne!(seed, derive_seed(b"other", "t.c", "value"));
+            assert_ne!(seed, derive_seed(b"secret", "t.d", "value"));
+            assert_ne!(seed, derive_seed(b"secret", "t.c", "other"));
+            assert_ne!(
+                derive_seed(b"secret", "t.c", "v"),
+                derive_seed(b"secret", "t.cv", "")
+            );
+        }
+    }
 }
@@ -0,0 +1,602 @@
+//! Checks of the config. Every problem is reported with its location in the YAML document,
+//! so all problems can be fixed at once (not one by one in failed dumps).
+
+use crate::{ColumnType, OnOverflow, Rule, Settings, Transformer};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+use yaml_rust2::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::Marker,
+};
+
+/// Table columns by full table names (`schema.table`), e.g. from a schema snapshot file
+pub type Schema = BTreeMap<String, Vec<String>>;
+
+/// Column types by full table names (like [Schema])
+pub type ColumnTypes = BTreeMap<String, HashMap<String, ColumnType>>;
+
+/// Number of values generated by every rule to check column types
+const TYPE_SAMPLE_SIZE: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PathItem {
+    Key(String),
+    Index(usize),
+}
+
+/// A path to a node of the config, e.g. `tables[0].rules.email`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ConfigPath(Vec<PathItem>);
+
+impl ConfigPath {
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn key(&self, key: &str) -> Self {
+        self.with(PathItem::Key(String::from(key)))
+    }
+
+    pub fn index(&self, index: usize) -> Self {
+        self.with(PathItem::Index(index))
+    }
+
+    fn with(&self, item: PathItem) -> Self {
+        let mut items = self.0.clone();
+        items.push(item);
+        Self(items)
+    }
+
+    fn parent(&self) -> Option<Self> {
+        match self.0.len() {
+            0 => None,
+            len => Some(Self(self.0[..len - 1].to_vec())),
+        }
+    }
+}
+
+impl fmt::Display for ConfigPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, item) in self.0.iter().enumerate() {
+            match item {
+                PathItem::Key(key) if i == 0 => write!(f, "{}", key)?,
+                PathItem::Key(key) => write!(f, ".{}", key)?,
+                PathItem::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub path: ConfigPath,
+    pub message: String,
+}
+
+impl Problem {
+    pub fn new(path: ConfigPath, message: String) -> Self {
+        Self { path, message }
+    }
+}
+
+/// Line and column (both start from 1)
+pub type Location = (usize, usize);
+
+pub struct Validation {
+    /// Settings (if the config can be parsed)
+    pub settings: Option<Settings>,
+    pub problems: Vec<Problem>,
+    /// Possible problems that don't make the config invalid
+    pub warnings: Vec<Problem>,
+    locations: HashMap<ConfigPath, Location>,
+}
+
+impl Validation {
+    /// Parses the YAML config and initializes all rules
+    pub fn new(config: &str) -> Self {
+        let locations = locations(config);
+        match Settings::from_yaml_with_problems(config) {
+            Ok((settings, problems)) => Self {
+                settings: Some(settings),
+                problems,
+                warnings: vec![],
+                locations,
+            },
+            Err(e) => Self {
+                settings: None,
+                problems: vec![Problem::new(ConfigPath::root(), e.to_string())],
+                warnings: vec![],
+                locations,
+            },
+        }
+    }
+
+    pub fn add(&mut self, problem: Problem) {
+        self.problems.push(problem);
+    }
+
+    /// Checks that tables and columns from `tables`, `table_order` and `subset` exist
+    pub fn check_schema(&mut self, schema: &Schema) {
+        let settings = match &self.settings {
+            Some(settings) => settings,
+            None => return,
+        };
+
+        let mut problems = vec![];
+        for (i, table) in settings.tables.iter().enumerate() {
+            let path = ConfigPath::root().key("tables").index(i);
+            let columns = match find_table(schema, &table.name) {
+                Some(columns) => columns,
+                None => {
+                    problems.push(Problem::new(
+                        path.key("name"),
+                        format!("The table `{}` doesn't exist", table.name),
+                    ));
+                    continue;
+                }
+            };
+            let no_column = |name: &str| {
+                format!(
+                    "The column `{}` doesn't exist in the table `{}`",
+                    name, table.name
+                )
+            };
+
+            let mut names: Vec<_> = table.rules.keys().collect();
+            names.sort();
+            for name in names {
+                if !columns.contains(name) {
+                    problems.push(Problem::new(path.key("rules").key(name), no_column(name)));
+                }
+            }
+
+            for (j, name) in table.rule_order.iter().flatten().enumerate() {
+                let path = path.key("rule_order").index(j);
+                if !columns.contains(name) {
+                    problems.push(Problem::new(path, no_column(name)));
+                } else if !table.rules.contains_key(name) {
+                    problems.push(Problem::new(
+                        path,
+                        format!("The column `{}` has no rule in `rules`", name),
+                    ));
+                }
+            }
+        }
+
+        for (i, name) in settings.table_order.iter().enumerate() {
+            if find_table(schema, name).is_none() {
+                problems.push(Problem::new(
+                    ConfigPath::root().key("table_order").index(i),
+                    format!("The table `{}` doesn't exist", name),
+                ));
+            }
+        }
+
+        for (i, root) in settings.subset.iter().flat_map(|s| &s.roots).enumerate() {
+            if find_table(schema, &root.table).is_none() {
+                problems.push(Problem::new(
+                    ConfigPath::root()
+                        .key("subset")
+                        .key("roots")
+                        .index(i)
+                        .key("table"),
+                    format!("The table `{}` doesn't exist", root.table),
+                ));
+            }
+        }
+
+        self.problems.extend(problems);
+    }
+
+    /// Warns about rules that may produce values that don't fit column types
+    /// (every rule generates sample values)
+    pub fn check_types(&mut self, types: &ColumnTypes) {
+        let settings = match &self.settings {
+            Some(settings) => settings,
+            None => return,
+        };
+
+        let mut warnings = vec![];
+        for (i, table) in settings.tables.iter().enumerate() {
+            let types = match find_table(types, &table.name) {
+                Some(types) => types,
+                None => continue,
+            };
+
+            let mut names: Vec<_> = table.rules.keys().collect();
+            names.sort();
+            for name in names {
+                let column_type = match types.get(name) {
+                    Some(column_type) => column_type,
+                    None => continue,
+                };
+                let field_name = format!("{}.{}", table.name, name);
+                if let Some(message) = type_warning(&field_name, &table.rules[name], column_type) {
+                    let path = ConfigPath::root()
+                        .key("tables")
+                        .index(i)
+                        .key("rules")
+                        .key(name);
+                    warnings.push(Problem::new(path, message));
+                }
+            }
+        }
+
+        self.warnings.extend(warnings);
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// The location of the problem (or of the closest parent node) in the YAML document
+    pub fn location(&self, problem: &Problem) -> Option<Location> {
+        let mut path = Some(problem.path.clone());
+        while let Some(p) = path {
+            if let Some(location) = self.locations.get(&p) {
+                return Some(*location);
+            }
+            path = p.parent();
+        }
+        None
+    }
+
+    /// Describes every problem (one line per problem), e.g.
+    /// `config.yml:12:7: tables[0].rules.emial: The column ...`
+    pub fn report(&self, file_name: &str) -> String {
+        self.describe(&self.problems, file_name, "")
+    }
+
+    /// Describes every warning like [Validation::report]
+    pub fn report_warnings(&self, file_name: &str) -> String {
+        self.describe(&self.warnings, file_name, "warning: ")
+    }
+
+    fn describe(&self, problems: &[Problem], file_name: &str, prefix: &str) -> String {
+        problems
+            .iter()
+            .map(|problem| {
+                let location = match self.location(problem) {
+                    Some((line, col)) => format!("{}:{}:{}", file_name, line, col),
+                    None => String::from(file_name),
+                };
+                if problem.path == ConfigPath::root() {
+                    format!("{}: {}{}", location, prefix, problem.message)
+                } else {
+                    format!(
+                        "{}: {}: {}{}",
+                        location, problem.path, prefix, problem.message
+                    )
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Runs the transformer of the rule on sample values and checks them
+fn type_warning(field_name: &str, rule: &Rule, column_type: &ColumnType) -> Option<String> {
+    let mut may_be_null = rule.nullify.is_some_and(|p| p > 0.0);
+    for _ in 0..TYPE_SAMPLE_SIZE {
+        // Transformers that need the row (e.g. templates with `prev`) may fail without it
+        let value = match rule.transformer.transform(field_name, "", &None) {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                may_be_null = true;
+                continue;
+            }
+            Err(_) => continue,
+        };
+        if rule.on_overflow != Some(OnOverflow::Truncate) {
+            if let Err(reason) = column_type.check(&value) {
+                return Some(format!(
+                    "The rule `{}` may produce values that don't fit the column type `{}`: {} \
+                    (see `on_overflow`)",
+                    field_name, column_type, reason
+                ));
+            }
+        }
+    }
+
+    if may_be_null && !column_type.nullable {
+        return Some(format!(
+            "The rule `{}` may produce NULL values, but the column is NOT NULL",
+            field_name
+        ));
+    }
+    None
+}
+
+/// Finds columns (or other values) by the full table name or by the name without the schema
+fn find_table<'a, V>(tables: &'a BTreeMap<String, V>, table: &str) -> Option<&'a V> {
+    tables.get(table).or_else(|| {
+        tables
+            .iter()
+            .find_map(|(name, value)| match name.split_once('.') {
+                Some((_, short_name)) if short_name == table => Some(value),
+                _ => None,
+            })
+    })
+}
+
+/// Locations of all nodes (keys for mapping values) of the YAML document
+fn locations(config: &str) -> HashMap<ConfigPath, Location> {
+    let mut collector = LocationCollector::default();
+    // Syntax errors are reported by the settings parser
+    let _ = Parser::new_from_str(config).load(&mut collector, false);
+    collector.locations
+}
+
+enum Container {
+    Mapping {
+        path: ConfigPath,
+        key: Option<String>,
+    },
+    Sequence {
+        path: ConfigPath,
+        index: usize,
+    },
+}
+
+#[derive(Default)]
+struct LocationCollector {
+    stack: Vec<Container>,
+    locations: HashMap<ConfigPath, Location>,
+}
+
+impl LocationCollector {
+    fn node_path(&self) -> ConfigPath {
+        match self.stack.last() {
+            None => ConfigPath::root(),
+            Some(Container::Mapping { path, key }) => path.key(key.as_deref().unwrap_or_default()),
+            Some(Container::Sequence { path, index }) => path.index(*index),
+        }
+    }
+
+    fn start_node(&mut self, mark: Marker) -> ConfigPath {
+        let path = self.node_path();
+        self.record(path.clone(), mark);
+        path
+    }
+
+    fn record(&mut self, path: ConfigPath, mark: Marker) {
+        self.locations
+            .entry(path)
+            .or_insert((mark.line(), mark.col() + 1));
+    }
+
+    fn end_node(&mut self) {
+        match self.stack.last_mut() {
+            Some(Container::Mapping { key, .. }) => *key = None,
+            Some(Container::Sequence { index, .. }) => *index += 1,
+            None => {}
+        }
+    }
+}
+
+impl MarkedEventReceiver for LocationCollector {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::Scalar(value, ..) => match self.stack.last_mut() {
+                Some(Container::Mapping {
+                    path,
+                    key: key @ None,
+                }) => {
+                    let mapping_path = path.clone();
+                    let key_path = path.key(&value);
+                    *key = Some(value);
+                    // The mapping starts with its first key
+                    self.record(mapping_path, mark);
+                    self.record(key_path, mark);
+                }
+                _ => {
+                    self.start_node(mark);
+                    self.end_node();
+                }
+            },
+            Event::Alias(_) => {
+                self.start_node(mark);
+                self.end_node();
+            }
+            Event::MappingStart(..) => {
+                // The marker of a block mapping points after its first key
+                let path = self.node_path();
+                self.stack.push(Container::Mapping { path, key: None });
+            }
+            Event::SequenceStart(..) => {
+                let path = self.start_node(mark);
+                self.stack.push(Container::Sequence { path, index: 0 });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.stack.pop();
+                self.end_node();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"tables:
+  - name: users
+    rule_order:
+      - greeting
+      - emial
+    rules:
+      emial:
+        email: {}
+      greeting:
+        template:
+          format: "Hello, {{ final.first_name }"
+  - name: orders
+    rules:
+      note:
+        words: {}
+table_order:
+  - users
+  - public.payments
+"#;
+
+    fn schema() -> Schema {
+        let mut schema = Schema::new();
+        schema.insert(
+            String::from("public.users"),
+            vec![String::from("email"), String::from("greeting")],
+        );
+        schema.insert(String::from("public.payments"), vec![String::from("id")]);
+        schema
+    }
+
+    #[test]
+    fn path() {
+        let path = ConfigPath::root()
+            .key("tables")
+            .index(1)
+            .key("rules")
+            .key("email");
+        assert_eq!(path.to_string(), "tables[1].rules.email");
+        assert_eq!(path.parent().unwrap().to_string(), "tables[1].rules");
+        assert_eq!(ConfigPath::root().to_string(), "");
+    }
+
+    #[test]
+    fn locations_of_nodes() {
+        let locations = locations(CONFIG);
+        let location = |path: ConfigPath| locations[&path];
+
+        let table = ConfigPath::root().key("tables").index(0);
+        assert_eq!(location(ConfigPath::root().key("tables")), (1, 1));
+        assert_eq!(location(table.clone()), (2, 5));
+        assert_eq!(location(table.key("rule_order").index(1)), (5, 9));
+        assert_eq!(location(table.key("rules").key("emial")), (7, 7));
+        assert_eq!(
+            location(ConfigPath::root().key("table_order").index(1)),
+            (18, 5)
+        );
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let validation = Validation::new(CONFIG);
+
+        assert!(validation.settings.is_some());
+        assert_eq!(validation.problems.len(), 1);
+        assert!(validation.problems[0]
+            .message
+            .starts_with("The rule `users.greeting` is invalid: Failed to parse"));
+        assert_eq!(validation.location(&validation.problems[0]), Some((9, 7)));
+    }
+
+    #[test]
+    fn invalid_config() {
+        let validation = Validation::new("tables:\n  - name: users\n    rules: 1\n");
+
+        assert!(validation.settings.is_none());
+        assert_eq!(validation.problems.len(), 1);
+        assert_eq!(validation.problems[0].path, ConfigPath::root());
+    }
+
+    #[test]
+    fn schema_problems() {
+        let mut validation = Validation::new(CONFIG);
+        validation.problems.clear();
+        validation.check_schema(&schema());
+
+        assert_eq!(
+            validation.report("config.yml"),
+            "config.yml:7:7: tables[0].rules.emial: \
+            The column `emial` doesn't exist in the table `users`\n\
+            config.yml:5:9: tables[0].rule_order[1]: \
+            The column `emial` doesn't exist in the table `users`\n\
+            config.yml:12:5: tables[1].name: The table `orders` doesn't exist"
+        );
+        assert!(!validation.is_valid());
+    }
+
+    #[test]
+    fn subset_problems() {
+        let config =
+            "subset:\n  roots:\n    - table: users\n    - table: orders\n      percent: 200\n";
+        let mut validation = Validation::new(config);
+        validation.check_schema(&schema());
+
+        assert_eq!(
+            validation.report("config.yml"),
+            "config.yml:1:1: subset: The `percent` of the subset root `orders` \
+            must be greater than 0 and not greater than 100\n\
+            config.yml:4:7: subset.roots[1].table: The table `orders` doesn't exist"
+        );
+    }
+
+    #[test]
+    fn type_warnings() {
+        let config = r#"tables:
+  - name: users
+    rules:
+      age:
+        random_num:
+          min: 100000
+          max: 200000
+      bio:
+        paragraph: {}
+      nickname:
+        paragraph: {}
+        on_overflow: truncate
+      email:
+        set_null: ~
+      login:
+        email: {}
+"#;
+        let types = ColumnTypes::from([(
+            String::from("public.users"),
+            HashMap::from([
+                (String::from("age"), ColumnType::new("smallint")),
+                (
+                    String::from("bio"),
+                    ColumnType::new("character varying").with_length(20),
+                ),
+                (
+                    String::from("nickname"),
+                    ColumnType::new("character varying").with_length(20),
+                ),
+                (String::from("email"), ColumnType::new("text").not_null()),
+                (
+                    String::from("login"),
+                    ColumnType::new("character varying").with_length(100),
+                ),
+            ]),
+        )]);
+        let mut validation = Validation::new(config);
+        validation.check_types(&types);
+
+        assert!(validation.is_valid());
+        assert_eq!(
+            validation.report_warnings("config.yml"),
+            "config.yml:4:7: tables[0].rules.age: warning: The rule `users.age` may produce values \
+            that don't fit the column type `smallint`: the value is out of range of `smallint` \
+            (see `on_overflow`)\n\
+            config.yml:8:7: tables[0].rules.bio: warning: The rule `users.bio` may produce values \
+            that don't fit the column type `character varying(20)`: the value is longer than 20 characters \
+            (see `on_overflow`)\n\
+            config.yml:13:7: tables[0].rules.email: warning: The rule `users.email` may produce NULL values, \
+            but the column is NOT NULL"
+        );
+    }
+
+    #[test]
+    fn valid() {
+        let config =
+            "tables:\n  - name: public.users\n    rules:\n      email:\n        email: {}\n";
+        let mut validation = Validation::new(config);
+        validation.check_schema(&schema());
+
+        assert!(validation.is_valid());
+        assert_eq!(validation.report("config.yml"), "");
+    }
+}
@@ -13,7 +13,7 @@ fn dummy_with_rng<R: Rng + ?Sized>(_: &Passport<L>, _rng: &mut R) -> Self {
 }
 
 use datanymizer_engine::{
-    ExtData, FkTransformer, LocaleConfig, Localized, LocalizedFaker, TransformContext,
+    utils::rnd, ExtData, FkTransformer, LocaleConfig, Localized, LocalizedFaker, TransformContext,
     TransformResult, Transformer, TransformerDefaults, TransformerInitContext,
 };
 use fake::Fake;
@@ -38,7 +38,7 @@ fn set_locale(&mut self, l: Option<LocaleConfig>) {
 
 impl LocalizedFaker<String> for PassportTransformer {
     fn fake<L: ExtData>(&self, l: L) -> String {
-        Passport(l).fake()
+        Passport(l).fake_with_rng(&mut rnd::rng())
     }
 }
 
@@ -54,8 +54,9 @@ fn transform(
         self.transform_with_faker()
     }
 
-    fn init(&mut self, ctx: &TransformerInitContext) {
+    fn init(&mut self, ctx: &TransformerInitContext) -> anyhow::Result<()> {
         self.set_defaults_for_faker(&ctx.defaults);
+        Ok(())
     }
 }
 
@@ -75,6 +76,7 @@ fn set_defaults() {
         TransformerDefaults {
             locale: LocaleConfig::RU,
         },
-    ));
+    ))
+    .unwrap();
     assert_eq!(t.locale(), Some(LocaleConfig::RU));
 }